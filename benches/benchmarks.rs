@@ -1,5 +1,5 @@
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use rucola::{hash, common::api::{DefaultInit, SingleInputUpdate, SingleOutputFinish}};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rucola::{hash, hash::SHABackend, common::api::{DefaultInit, SingleInputUpdate, SingleOutputFinish}};
 use utilities::testutils;
 
 
@@ -11,9 +11,63 @@ pub fn criterion_benchmark(c: &mut Criterion) {
     let mut hash: [u8; 20] = [0;20];
     for t in tv {
         hash.fill(0);
-        c.bench_function("sha1 bench long inputs".as_str(), |b| b.iter(|| testutils::single_test(black_box(&t.0), &mut sha1, &mut hash)));
+        c.bench_function(&format!("sha1 bench long inputs {}", t.0.len()), |b| b.iter(|| testutils::single_test(black_box(&t.0), &mut sha1, &mut hash)));
     }
 }
 
-criterion_group!(benches, criterion_benchmark);
-criterion_main!(benches);
\ No newline at end of file
+/* The accelerated backends are only detected with `--features std`, or when the
+ * extensions are enabled at compile time. */
+pub fn backend_benchmark(c: &mut Criterion) {
+    let input = [0x5au8; 16384];
+    let mut hash: [u8; 32] = [0; 32];
+    let mut group = c.benchmark_group("sha backends");
+
+    for backend in [SHABackend::Scalar, SHABackend::SHANI, SHABackend::ARMv8] {
+        if !backend.is_supported() {
+            continue;
+        }
+
+        for (name, mut sha) in [("sha1", hash::SHA::new_sha1()), ("sha256", hash::SHA::new_sha256())] {
+            sha.set_backend(backend).unwrap();
+            for size in [64, 1024, 16384] {
+                group.throughput(Throughput::Bytes(size as u64));
+                group.bench_with_input(BenchmarkId::new(format!("{} {:?}", name, backend), size), &input[..size],
+                    |b, m| b.iter(|| testutils::single_test(black_box(m), &mut sha, &mut hash)));
+            }
+        }
+    }
+    group.finish();
+}
+
+/* Many short records, one context for all of them against `sha256_many`, per backend.
+ * Only the scalar backend runs the records in lockstep, the accelerated ones hash
+ * them one after another like the context loop. */
+pub fn many_benchmark(c: &mut Criterion) {
+    let records: Vec<Vec<u8>> = (0..1000).map(|i| vec![i as u8; 40 + i % 60]).collect();
+    let inputs: Vec<&[u8]> = records.iter().map(|r| &r[..]).collect();
+    let mut digests = vec![0u8; 32 * inputs.len()];
+    let mut group = c.benchmark_group("sha256 1000 records");
+
+    for backend in [SHABackend::Scalar, SHABackend::SHANI, SHABackend::ARMv8] {
+        if !backend.is_supported() {
+            continue;
+        }
+
+        group.bench_function(format!("context {:?}", backend), |b| b.iter(|| {
+            let mut sha = hash::SHA::new_sha256();
+            sha.set_backend(backend).unwrap();
+            for (input, digest) in inputs.iter().zip(digests.chunks_exact_mut(32)) {
+                sha.init().unwrap();
+                sha.update(black_box(input)).unwrap();
+                sha.finish(digest).unwrap();
+            }
+        }));
+        group.bench_function(format!("sha256_many {:?}", backend), |b| b.iter(|| {
+            hash::SHA::sha256_many_with_backend(backend, black_box(&inputs), &mut digests).unwrap()
+        }));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark, backend_benchmark, many_benchmark);
+criterion_main!(benches);
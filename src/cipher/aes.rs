@@ -0,0 +1,359 @@
+use crate::common::api::BlockCipher;
+use crate::common::Error;
+
+pub const AES_BLOCKSIZE: usize = 16;
+const AES_MAX_ROUNDS: usize = 14;
+
+static RCON: [u8; 10] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x1b, 0x36];
+
+/// AES (FIPS 197) with a key of `KEYSIZE` bytes.
+///
+/// The implementation does not use any lookup tables. SubBytes is computed on a
+/// bitsliced representation of the state with the circuit of Boyar and Peralta,
+/// all other steps are plain bit operations, so the timing does not depend on the
+/// key or the data.
+#[derive(Debug, Clone)]
+pub struct AES<const KEYSIZE: usize> {
+    round_keys: [[u8; AES_BLOCKSIZE]; AES_MAX_ROUNDS + 1],
+}
+
+pub type AES128 = AES<16>;
+pub type AES192 = AES<24>;
+pub type AES256 = AES<32>;
+
+impl<const KEYSIZE: usize> AES<KEYSIZE> {
+    const ROUNDS: usize = KEYSIZE / 4 + 6;
+
+    fn _key_expansion(&mut self, key: &[u8]) {
+        let nk = KEYSIZE / 4;
+        let mut w = [[0u8; 4]; 4 * (AES_MAX_ROUNDS + 1)];
+
+        for (i, word) in key.chunks(4).enumerate() {
+            w[i].copy_from_slice(word);
+        }
+
+        for i in nk..4 * (Self::ROUNDS + 1) {
+            let mut temp = w[i - 1];
+
+            if i % nk == 0 {
+                temp.rotate_left(1);
+                _sub_word(&mut temp);
+                temp[0] ^= RCON[i / nk - 1];
+            } else if nk > 6 && i % nk == 4 {
+                _sub_word(&mut temp);
+            }
+
+            for j in 0..4 {
+                w[i][j] = w[i - nk][j] ^ temp[j];
+            }
+        }
+
+        for (i, rk) in self.round_keys.iter_mut().take(Self::ROUNDS + 1).enumerate() {
+            for c in 0..4 {
+                rk[4 * c..4 * c + 4].copy_from_slice(&w[4 * i + c]);
+            }
+        }
+    }
+}
+
+impl<const KEYSIZE: usize> BlockCipher<AES_BLOCKSIZE> for AES<KEYSIZE> {
+    const KEY_SIZE: usize = KEYSIZE;
+
+    fn new(key: &[u8]) -> Result<Self, Error> {
+        if key.len() != KEYSIZE || !(KEYSIZE == 16 || KEYSIZE == 24 || KEYSIZE == 32) {
+            return Err(Error::Err);
+        }
+
+        let mut aes = AES {
+            round_keys: [[0; AES_BLOCKSIZE]; AES_MAX_ROUNDS + 1],
+        };
+        aes._key_expansion(key);
+
+        Ok(aes)
+    }
+
+    fn encrypt_block(&self, block: &mut [u8; AES_BLOCKSIZE]) {
+        _add_round_key(block, &self.round_keys[0]);
+
+        for round in 1..Self::ROUNDS {
+            _sub_bytes(block);
+            _shift_rows(block);
+            _mix_columns(block);
+            _add_round_key(block, &self.round_keys[round]);
+        }
+
+        _sub_bytes(block);
+        _shift_rows(block);
+        _add_round_key(block, &self.round_keys[Self::ROUNDS]);
+    }
+
+    /* The straightforward inverse cipher (FIPS 197, section 5.3) with the round keys
+     * of the encryption key schedule applied in reverse order. */
+    fn decrypt_block(&self, block: &mut [u8; AES_BLOCKSIZE]) {
+        _add_round_key(block, &self.round_keys[Self::ROUNDS]);
+
+        for round in (1..Self::ROUNDS).rev() {
+            _inv_shift_rows(block);
+            _inv_sub_bytes(block);
+            _add_round_key(block, &self.round_keys[round]);
+            _inv_mix_columns(block);
+        }
+
+        _inv_shift_rows(block);
+        _inv_sub_bytes(block);
+        _add_round_key(block, &self.round_keys[0]);
+    }
+}
+
+fn _add_round_key(block: &mut [u8; AES_BLOCKSIZE], rk: &[u8; AES_BLOCKSIZE]) {
+    for (b, k) in block.iter_mut().zip(rk.iter()) {
+        *b ^= k;
+    }
+}
+
+fn _sub_word(word: &mut [u8; 4]) {
+    let mut block = [0u8; AES_BLOCKSIZE];
+    block[..4].copy_from_slice(word);
+    _sub_bytes(&mut block);
+    word.copy_from_slice(&block[..4]);
+}
+
+/* The state is stored column by column, byte r + 4c is row r, column c. */
+fn _shift_rows(block: &mut [u8; AES_BLOCKSIZE]) {
+    let s = *block;
+    for r in 1..4 {
+        for c in 0..4 {
+            block[r + 4 * c] = s[r + 4 * ((c + r) % 4)];
+        }
+    }
+}
+
+fn _inv_shift_rows(block: &mut [u8; AES_BLOCKSIZE]) {
+    let s = *block;
+    for r in 1..4 {
+        for c in 0..4 {
+            block[r + 4 * ((c + r) % 4)] = s[r + 4 * c];
+        }
+    }
+}
+
+/* Multiplication by x in GF(2^8) without a data dependent branch. */
+fn _xtime(b: u8) -> u8 {
+    (b << 1) ^ ((b >> 7).wrapping_neg() & 0x1b)
+}
+
+fn _mix_columns(block: &mut [u8; AES_BLOCKSIZE]) {
+    for col in block.chunks_mut(4) {
+        let (a0, a1, a2, a3) = (col[0], col[1], col[2], col[3]);
+        let t = a0 ^ a1 ^ a2 ^ a3;
+
+        col[0] = a0 ^ t ^ _xtime(a0 ^ a1);
+        col[1] = a1 ^ t ^ _xtime(a1 ^ a2);
+        col[2] = a2 ^ t ^ _xtime(a2 ^ a3);
+        col[3] = a3 ^ t ^ _xtime(a3 ^ a0);
+    }
+}
+
+/* InvMixColumns is MixColumns after multiplying every column with
+ * 04x^2 + 05 (FIPS 197, 4.3): {0e,0b,0d,09} = {02,01,01,03} * {05,00,04,00}. */
+fn _inv_mix_columns(block: &mut [u8; AES_BLOCKSIZE]) {
+    for col in block.chunks_mut(4) {
+        let u = _xtime(_xtime(col[0] ^ col[2]));
+        let v = _xtime(_xtime(col[1] ^ col[3]));
+
+        col[0] ^= u;
+        col[1] ^= v;
+        col[2] ^= u;
+        col[3] ^= v;
+    }
+
+    _mix_columns(block);
+}
+
+/* Applies the S-box to all 16 bytes. The bytes are transposed into 8 bit planes
+ * (plane i holds bit i of every byte), run through the circuit and transposed back. */
+fn _sub_bytes(block: &mut [u8; AES_BLOCKSIZE]) {
+    let mut q = _bitslice(block);
+    _bitslice_sbox(&mut q);
+    _unbitslice(&q, block);
+}
+
+/* The inverse S-box is derived from the forward one: InvS(y) = L(S(L(y) ^ 05)) ^ 05
+ * with the linear map L(b) = rotl(b, 1) ^ rotl(b, 3) ^ rotl(b, 6). */
+fn _inv_sub_bytes(block: &mut [u8; AES_BLOCKSIZE]) {
+    let mut q = _bitslice(block);
+    _bitslice_l05(&mut q);
+    _bitslice_sbox(&mut q);
+    _bitslice_l05(&mut q);
+    _unbitslice(&q, block);
+}
+
+fn _bitslice(block: &[u8; AES_BLOCKSIZE]) -> [u16; 8] {
+    let mut q = [0u16; 8];
+    for (j, b) in block.iter().enumerate() {
+        for (i, plane) in q.iter_mut().enumerate() {
+            *plane |= (((*b >> i) & 1) as u16) << j;
+        }
+    }
+    q
+}
+
+fn _unbitslice(q: &[u16; 8], block: &mut [u8; AES_BLOCKSIZE]) {
+    for (j, b) in block.iter_mut().enumerate() {
+        let mut v = 0u8;
+        for (i, plane) in q.iter().enumerate() {
+            v |= (((plane >> j) & 1) as u8) << i;
+        }
+        *b = v;
+    }
+}
+
+/* b -> L(b) ^ 05 on the bit planes; rotating a byte left by n moves plane i to i + n. */
+fn _bitslice_l05(q: &mut [u16; 8]) {
+    let p = *q;
+    for (i, plane) in q.iter_mut().enumerate() {
+        *plane = p[(i + 7) % 8] ^ p[(i + 5) % 8] ^ p[(i + 2) % 8];
+    }
+    q[0] = !q[0];
+    q[2] = !q[2];
+}
+
+/* Boyar and Peralta, "A new combinational logic minimization technique with
+ * applications to cryptology" (https://eprint.iacr.org/2009/191). The inputs x*
+ * and outputs s* are numbered from the most significant bit. */
+fn _bitslice_sbox(q: &mut [u16; 8]) {
+    let (x0, x1, x2, x3) = (q[7], q[6], q[5], q[4]);
+    let (x4, x5, x6, x7) = (q[3], q[2], q[1], q[0]);
+
+    /* Top linear transformation. */
+    let y14 = x3 ^ x5;
+    let y13 = x0 ^ x6;
+    let y9 = x0 ^ x3;
+    let y8 = x0 ^ x5;
+    let t0 = x1 ^ x2;
+    let y1 = t0 ^ x7;
+    let y4 = y1 ^ x3;
+    let y12 = y13 ^ y14;
+    let y2 = y1 ^ x0;
+    let y5 = y1 ^ x6;
+    let y3 = y5 ^ y8;
+    let t1 = x4 ^ y12;
+    let y15 = t1 ^ x5;
+    let y20 = t1 ^ x1;
+    let y6 = y15 ^ x7;
+    let y10 = y15 ^ t0;
+    let y11 = y20 ^ y9;
+    let y7 = x7 ^ y11;
+    let y17 = y10 ^ y11;
+    let y19 = y10 ^ y8;
+    let y16 = t0 ^ y11;
+    let y21 = y13 ^ y16;
+    let y18 = x0 ^ y16;
+
+    /* Non-linear section. */
+    let t2 = y12 & y15;
+    let t3 = y3 & y6;
+    let t4 = t3 ^ t2;
+    let t5 = y4 & x7;
+    let t6 = t5 ^ t2;
+    let t7 = y13 & y16;
+    let t8 = y5 & y1;
+    let t9 = t8 ^ t7;
+    let t10 = y2 & y7;
+    let t11 = t10 ^ t7;
+    let t12 = y9 & y11;
+    let t13 = y14 & y17;
+    let t14 = t13 ^ t12;
+    let t15 = y8 & y10;
+    let t16 = t15 ^ t12;
+    let t17 = t4 ^ t14;
+    let t18 = t6 ^ t16;
+    let t19 = t9 ^ t14;
+    let t20 = t11 ^ t16;
+    let t21 = t17 ^ y20;
+    let t22 = t18 ^ y19;
+    let t23 = t19 ^ y21;
+    let t24 = t20 ^ y18;
+
+    let t25 = t21 ^ t22;
+    let t26 = t21 & t23;
+    let t27 = t24 ^ t26;
+    let t28 = t25 & t27;
+    let t29 = t28 ^ t22;
+    let t30 = t23 ^ t24;
+    let t31 = t22 ^ t26;
+    let t32 = t31 & t30;
+    let t33 = t32 ^ t24;
+    let t34 = t23 ^ t33;
+    let t35 = t27 ^ t33;
+    let t36 = t24 & t35;
+    let t37 = t36 ^ t34;
+    let t38 = t27 ^ t36;
+    let t39 = t29 & t38;
+    let t40 = t25 ^ t39;
+
+    let t41 = t40 ^ t37;
+    let t42 = t29 ^ t33;
+    let t43 = t29 ^ t40;
+    let t44 = t33 ^ t37;
+    let t45 = t42 ^ t41;
+    let z0 = t44 & y15;
+    let z1 = t37 & y6;
+    let z2 = t33 & x7;
+    let z3 = t43 & y16;
+    let z4 = t40 & y1;
+    let z5 = t29 & y7;
+    let z6 = t42 & y11;
+    let z7 = t45 & y17;
+    let z8 = t41 & y10;
+    let z9 = t44 & y12;
+    let z10 = t37 & y3;
+    let z11 = t33 & y4;
+    let z12 = t43 & y13;
+    let z13 = t40 & y5;
+    let z14 = t29 & y2;
+    let z15 = t42 & y9;
+    let z16 = t45 & y14;
+    let z17 = t41 & y8;
+
+    /* Bottom linear transformation. */
+    let t46 = z15 ^ z16;
+    let t47 = z10 ^ z11;
+    let t48 = z5 ^ z13;
+    let t49 = z9 ^ z10;
+    let t50 = z2 ^ z12;
+    let t51 = z2 ^ z5;
+    let t52 = z7 ^ z8;
+    let t53 = z0 ^ z3;
+    let t54 = z6 ^ z7;
+    let t55 = z16 ^ z17;
+    let t56 = z12 ^ t48;
+    let t57 = t50 ^ t53;
+    let t58 = z4 ^ t46;
+    let t59 = z3 ^ t54;
+    let t60 = t46 ^ t57;
+    let t61 = z14 ^ t57;
+    let t62 = t52 ^ t58;
+    let t63 = t49 ^ t58;
+    let t64 = z4 ^ t59;
+    let t65 = t61 ^ t62;
+    let t66 = z1 ^ t63;
+    let s0 = t59 ^ t63;
+    let s6 = t56 ^ !t62;
+    let s7 = t48 ^ !t60;
+    let t67 = t64 ^ t65;
+    let s3 = t53 ^ t66;
+    let s4 = t51 ^ t66;
+    let s5 = t47 ^ t65;
+    let s1 = t64 ^ !s3;
+    let s2 = t55 ^ !t67;
+
+    q[7] = s0;
+    q[6] = s1;
+    q[5] = s2;
+    q[4] = s3;
+    q[3] = s4;
+    q[2] = s5;
+    q[1] = s6;
+    q[0] = s7;
+}
@@ -0,0 +1,187 @@
+use crate::cipher::AES_BLOCKSIZE;
+use crate::common::api::{AEAD, BlockCipher};
+use crate::common::{Success, Error};
+use crate::utils::ct_eq;
+
+/// Counter with CBC-MAC (SP 800-38C) over a block cipher with 128-bit blocks.
+///
+/// The nonce length n is fixed per instance, between 7 and 13 bytes, and determines
+/// the length field L = 15 - n of the counter blocks: messages must be shorter than
+/// 2^(8L) bytes. Tags are 4, 6, 8, 10, 12, 14 or 16 bytes long. `with_lengths`
+/// takes both, `AEAD::new` uses 12 byte nonces and 16 byte tags. Encryption and
+/// decryption go through the `AEAD` trait.
+#[derive(Debug, Clone)]
+pub struct CCM<C: BlockCipher<AES_BLOCKSIZE>> {
+    cipher: C,
+    nonce_len: usize,
+    tag_len: usize,
+}
+
+impl<C: BlockCipher<AES_BLOCKSIZE>> CCM<C> {
+    pub fn with_lengths(key: &[u8], nonce_len: usize, tag_len: usize) -> Result<Self, Error> {
+        if !(7..=13).contains(&nonce_len) || !matches!(tag_len, 4 | 6 | 8 | 10 | 12 | 14 | 16) {
+            return Err(Error::Err);
+        }
+
+        Ok(CCM { cipher: C::new(key)?, nonce_len, tag_len })
+    }
+
+    /* The message length has to fit into the L bytes of the length field. */
+    fn _check(&self, nonce: &[u8], msg_len: usize, tag_len: usize) -> Result<Success, Error> {
+        let l = 15 - self.nonce_len;
+        if nonce.len() != self.nonce_len || tag_len != self.tag_len {
+            return Err(Error::Err);
+        }
+        if l < 8 && (msg_len as u64) >> (8 * l) != 0 {
+            return Err(Error::Err);
+        }
+
+        Ok(Success::OK)
+    }
+
+    /* Processes B0 and the encoded associated data (appendix A.2.1 and A.2.2) and
+     * returns the CBC-MAC state. */
+    fn _mac_start(&self, nonce: &[u8], aad: &[u8], msg_len: usize) -> [u8; AES_BLOCKSIZE] {
+        let l = 15 - self.nonce_len;
+        let mut x = [0u8; AES_BLOCKSIZE];
+
+        x[0] = ((!aad.is_empty() as u8) << 6) | (((self.tag_len - 2) / 2) as u8) << 3 | (l - 1) as u8;
+        x[1..1 + self.nonce_len].copy_from_slice(nonce);
+        x[1 + self.nonce_len..].copy_from_slice(&(msg_len as u64).to_be_bytes()[8 - l..]);
+        self.cipher.encrypt_block(&mut x);
+
+        if aad.is_empty() {
+            return x;
+        }
+
+        /* The length prefix takes 2, 6 or 10 bytes of the first block. */
+        let mut block = [0u8; AES_BLOCKSIZE];
+        let len = aad.len() as u64;
+        let prefix = if len < 0xff00 {
+            block[..2].copy_from_slice(&(len as u16).to_be_bytes());
+            2
+        } else if len <= u32::MAX as u64 {
+            block[..2].copy_from_slice(&[0xff, 0xfe]);
+            block[2..6].copy_from_slice(&(len as u32).to_be_bytes());
+            6
+        } else {
+            block[..2].copy_from_slice(&[0xff, 0xff]);
+            block[2..10].copy_from_slice(&len.to_be_bytes());
+            10
+        };
+
+        let take = usize::min(AES_BLOCKSIZE - prefix, aad.len());
+        block[prefix..prefix + take].copy_from_slice(&aad[..take]);
+        self._mac_block(&mut x, &block[..prefix + take]);
+
+        for chunk in aad[take..].chunks(AES_BLOCKSIZE) {
+            self._mac_block(&mut x, chunk);
+        }
+
+        x
+    }
+
+    /* X = E(X ^ B), a partial block is padded with zeros. */
+    fn _mac_block(&self, x: &mut [u8; AES_BLOCKSIZE], block: &[u8]) {
+        for (a, b) in x.iter_mut().zip(block.iter()) {
+            *a ^= b;
+        }
+        self.cipher.encrypt_block(x);
+    }
+
+    /* Counter block Ctr_i = flags || N || [i]_L, encrypted. */
+    fn _keystream(&self, nonce: &[u8], i: u64) -> [u8; AES_BLOCKSIZE] {
+        let l = 15 - self.nonce_len;
+        let mut ctr = [0u8; AES_BLOCKSIZE];
+
+        ctr[0] = (l - 1) as u8;
+        ctr[1..1 + self.nonce_len].copy_from_slice(nonce);
+        ctr[1 + self.nonce_len..].copy_from_slice(&i.to_be_bytes()[8 - l..]);
+        self.cipher.encrypt_block(&mut ctr);
+
+        ctr
+    }
+
+    /* Counter mode starting with Ctr_1, applied in place. */
+    fn _ctr(&self, nonce: &[u8], buffer: &mut [u8]) {
+        for (i, chunk) in buffer.chunks_mut(AES_BLOCKSIZE).enumerate() {
+            let ks = self._keystream(nonce, i as u64 + 1);
+            for (b, k) in chunk.iter_mut().zip(ks.iter()) {
+                *b ^= k;
+            }
+        }
+    }
+
+    /* T ^ MSB_t(S0), with S0 = E(Ctr_0). */
+    fn _encrypt_tag(&self, nonce: &[u8], x: &mut [u8; AES_BLOCKSIZE]) {
+        let s0 = self._keystream(nonce, 0);
+        for (a, b) in x.iter_mut().zip(s0.iter()) {
+            *a ^= b;
+        }
+    }
+
+    /* The MAC is over the plaintext, so each block is decrypted into a temporary
+     * block for the CBC-MAC and the output is only written once the tag matches. */
+    fn _verify(&self, nonce: &[u8], aad: &[u8], ciphertext: &[u8], tag: &[u8]) -> Result<Success, Error> {
+        self._check(nonce, ciphertext.len(), tag.len())?;
+
+        let mut x = self._mac_start(nonce, aad, ciphertext.len());
+        for (i, chunk) in ciphertext.chunks(AES_BLOCKSIZE).enumerate() {
+            let mut block = self._keystream(nonce, i as u64 + 1);
+            for (b, c) in block.iter_mut().zip(chunk.iter()) {
+                *b ^= c;
+            }
+            self._mac_block(&mut x, &block[..chunk.len()]);
+            block.fill(0);
+        }
+        self._encrypt_tag(nonce, &mut x);
+
+        if !ct_eq(&x[..self.tag_len], tag) {
+            return Err(Error::AuthFailed);
+        }
+
+        Ok(Success::OK)
+    }
+}
+
+/* The parameters of AEAD_AES_128_CCM and AEAD_AES_256_CCM (RFC 5116, section 5.3)
+ * when created through the trait. */
+impl<C: BlockCipher<AES_BLOCKSIZE>> AEAD for CCM<C> {
+    const KEY_SIZE: usize = C::KEY_SIZE;
+    const NONCE_SIZE: usize = 12;
+    const TAG_SIZE: usize = 16;
+
+    fn new(key: &[u8]) -> Result<Self, Error> {
+        CCM::with_lengths(key, Self::NONCE_SIZE, Self::TAG_SIZE)
+    }
+
+    fn nonce_len(&self) -> usize {
+        self.nonce_len
+    }
+
+    fn tag_len(&self) -> usize {
+        self.tag_len
+    }
+
+    fn seal_in_place(&self, nonce: &[u8], aad: &[u8], buffer: &mut [u8], tag: &mut [u8]) -> Result<Success, Error> {
+        self._check(nonce, buffer.len(), tag.len())?;
+
+        let mut x = self._mac_start(nonce, aad, buffer.len());
+        for block in buffer.chunks(AES_BLOCKSIZE) {
+            self._mac_block(&mut x, block);
+        }
+
+        self._ctr(nonce, buffer);
+        self._encrypt_tag(nonce, &mut x);
+        tag.copy_from_slice(&x[..self.tag_len]);
+
+        Ok(Success::OK)
+    }
+
+    fn open_in_place(&self, nonce: &[u8], aad: &[u8], buffer: &mut [u8], tag: &[u8]) -> Result<Success, Error> {
+        self._verify(nonce, aad, buffer, tag)?;
+        self._ctr(nonce, buffer);
+
+        Ok(Success::OK)
+    }
+}
@@ -0,0 +1,281 @@
+use crate::common::api::{CipherStreamingAPI, IVInit, InputOutputUpdate, OutputFinish};
+use crate::common::{Success, Error};
+use crate::cipher::keystream::Keystream;
+
+pub const CHACHA_KEY_SIZE: usize = 32;
+pub const CHACHA_BLOCKSIZE: usize = 64;
+pub const CHACHA_NONCE_SIZE: usize = 12;
+pub const CHACHA_LEGACY_NONCE_SIZE: usize = 8;
+pub const XCHACHA_NONCE_SIZE: usize = 24;
+pub const HCHACHA_NONCE_SIZE: usize = 16;
+
+/* "expand 32-byte k" */
+const SIGMA: [u32; 4] = [0x61707865, 0x3320646e, 0x79622d32, 0x6b206574];
+
+/// ChaCha20 as specified in RFC 8439: 96-bit nonce and 32-bit block counter, so at
+/// most 256 GiB of keystream per nonce.
+///
+/// The key is set on construction, `init` takes the nonce and starts at block 0.
+/// `seek` moves to any byte position of the keystream, e.g. to block 1 as used by
+/// ChaCha20-Poly1305. Input that would need the counter to wrap is rejected with
+/// `Error::CounterOverflow` before any output is written. Encryption and decryption
+/// are the same operation.
+#[derive(Debug, Clone)]
+pub struct ChaCha20 {
+    ctx: ChaChaCtx,
+}
+
+/// The original ChaCha20 with a 64-bit nonce and a 64-bit block counter.
+#[derive(Debug, Clone)]
+pub struct ChaCha20Legacy {
+    ctx: ChaChaCtx,
+}
+
+/// XChaCha20 (draft-irtf-cfrg-xchacha): the first 16 bytes of the 192-bit nonce and
+/// the key derive a subkey with HChaCha20, which is used for ChaCha20 with the last
+/// 8 bytes of the nonce. Long nonces can be chosen at random.
+#[derive(Debug, Clone)]
+pub struct XChaCha20 {
+    key: [u8; CHACHA_KEY_SIZE],
+    ctx: ChaChaCtx,
+}
+
+/* State of all variants. `state` holds the constants, the key and the nonce; the
+ * first `counter_words` words of row 3 are the counter of the next block, which is
+ * kept by `keystream`. */
+#[derive(Debug, Clone)]
+struct ChaChaCtx {
+    state: [u32; 16],
+    counter_words: usize,
+    keystream: Keystream,
+}
+
+impl ChaChaCtx {
+    fn new(key: &[u8; CHACHA_KEY_SIZE], counter_words: usize) -> Self {
+        let mut ctx = ChaChaCtx {
+            state: [0; 16],
+            counter_words,
+            keystream: Keystream::new(1 << (32 * counter_words)),
+        };
+        ctx._set_key(key);
+
+        ctx
+    }
+
+    fn _set_key(&mut self, key: &[u8; CHACHA_KEY_SIZE]) {
+        self.state[..4].copy_from_slice(&SIGMA);
+        for (w, k) in self.state[4..12].iter_mut().zip(key.chunks_exact(4)) {
+            *w = u32::from_le_bytes(k.try_into().unwrap());
+        }
+    }
+
+    fn _init(&mut self, nonce: &[u8]) {
+        for (w, n) in self.state[12 + self.counter_words..].iter_mut().zip(nonce.chunks_exact(4)) {
+            *w = u32::from_le_bytes(n.try_into().unwrap());
+        }
+        self.keystream._init();
+    }
+
+    fn _seek(&mut self, pos: u64) -> Result<Success, Error> {
+        let counter_words = self.counter_words;
+        self.keystream._seek(pos, |counter, buffer| _block(&mut self.state, counter_words, counter, buffer))
+    }
+
+    fn _process(&mut self, input: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        let counter_words = self.counter_words;
+        self.keystream._process(input, output, |counter, buffer| _block(&mut self.state, counter_words, counter, buffer))
+    }
+
+    fn _finish(&mut self) -> Result<usize, Error> {
+        let ret = self.keystream._finish()?;
+        self.state[12..].fill(0);
+
+        Ok(ret)
+    }
+}
+
+/* Keystream block `counter`, written to the first `counter_words` words of row 3. */
+fn _block(state: &mut [u32; 16], counter_words: usize, counter: u128, buffer: &mut [u8; CHACHA_BLOCKSIZE]) {
+    state[12] = counter as u32;
+    if counter_words == 2 {
+        state[13] = (counter >> 32) as u32;
+    }
+
+    let mut x = _rounds(state);
+    for (i, (w, s)) in x.iter_mut().zip(state.iter()).enumerate() {
+        *w = w.wrapping_add(*s);
+        buffer[4 * i..4 * i + 4].copy_from_slice(&w.to_le_bytes());
+    }
+    x.fill(0);
+}
+
+fn _quarter_round(x: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    x[a] = x[a].wrapping_add(x[b]);
+    x[d] = (x[d] ^ x[a]).rotate_left(16);
+    x[c] = x[c].wrapping_add(x[d]);
+    x[b] = (x[b] ^ x[c]).rotate_left(12);
+    x[a] = x[a].wrapping_add(x[b]);
+    x[d] = (x[d] ^ x[a]).rotate_left(8);
+    x[c] = x[c].wrapping_add(x[d]);
+    x[b] = (x[b] ^ x[c]).rotate_left(7);
+}
+
+/* The 20 rounds, 10 column and 10 diagonal rounds, without the final addition. */
+fn _rounds(state: &[u32; 16]) -> [u32; 16] {
+    let mut x = *state;
+    for _ in 0..10 {
+        _quarter_round(&mut x, 0, 4, 8, 12);
+        _quarter_round(&mut x, 1, 5, 9, 13);
+        _quarter_round(&mut x, 2, 6, 10, 14);
+        _quarter_round(&mut x, 3, 7, 11, 15);
+        _quarter_round(&mut x, 0, 5, 10, 15);
+        _quarter_round(&mut x, 1, 6, 11, 12);
+        _quarter_round(&mut x, 2, 7, 8, 13);
+        _quarter_round(&mut x, 3, 4, 9, 14);
+    }
+
+    x
+}
+
+/// HChaCha20 (draft-irtf-cfrg-xchacha, section 2.2): derives a 256-bit subkey from
+/// the key and a 128-bit nonce, which take the places of the key and of counter and
+/// nonce in the ChaCha20 state.
+pub fn hchacha20(key: &[u8; CHACHA_KEY_SIZE], nonce: &[u8; HCHACHA_NONCE_SIZE]) -> [u8; CHACHA_KEY_SIZE] {
+    let mut ctx = ChaChaCtx::new(key, 0);
+    ctx._init(nonce);
+
+    let mut x = _rounds(&ctx.state);
+    let mut subkey = [0u8; CHACHA_KEY_SIZE];
+    for (out, w) in subkey.chunks_exact_mut(4).zip(x[..4].iter().chain(x[12..].iter())) {
+        out.copy_from_slice(&w.to_le_bytes());
+    }
+    x.fill(0);
+    ctx.state.fill(0);
+
+    subkey
+}
+
+fn _key(key: &[u8]) -> Result<&[u8; CHACHA_KEY_SIZE], Error> {
+    key.try_into().map_err(|_| Error::Err)
+}
+
+impl ChaCha20 {
+    pub fn new(key: &[u8]) -> Result<Self, Error> {
+        Ok(ChaCha20 { ctx: ChaChaCtx::new(_key(key)?, 1) })
+    }
+
+    /// Moves to byte `pos` of the keystream for the current nonce.
+    pub fn seek(&mut self, pos: u64) -> Result<Success, Error> {
+        self.ctx._seek(pos)
+    }
+}
+
+impl ChaCha20Legacy {
+    pub fn new(key: &[u8]) -> Result<Self, Error> {
+        Ok(ChaCha20Legacy { ctx: ChaChaCtx::new(_key(key)?, 2) })
+    }
+
+    /// Moves to byte `pos` of the keystream for the current nonce.
+    pub fn seek(&mut self, pos: u64) -> Result<Success, Error> {
+        self.ctx._seek(pos)
+    }
+}
+
+impl XChaCha20 {
+    pub fn new(key: &[u8]) -> Result<Self, Error> {
+        let key = *_key(key)?;
+        Ok(XChaCha20 { key, ctx: ChaChaCtx::new(&[0; CHACHA_KEY_SIZE], 1) })
+    }
+
+    /// Moves to byte `pos` of the keystream for the current nonce.
+    pub fn seek(&mut self, pos: u64) -> Result<Success, Error> {
+        self.ctx._seek(pos)
+    }
+}
+
+impl CipherStreamingAPI for ChaCha20 {}
+
+impl IVInit for ChaCha20 {
+    fn init(&mut self, iv: &[u8]) -> Result<Success, Error> {
+        if iv.len() != CHACHA_NONCE_SIZE {
+            return Err(Error::Err);
+        }
+
+        self.ctx._init(iv);
+        Ok(Success::OK)
+    }
+}
+
+impl InputOutputUpdate for ChaCha20 {
+    fn update(&mut self, input: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        self.ctx._process(input, output)
+    }
+}
+
+impl OutputFinish for ChaCha20 {
+    fn finish(&mut self, _output: &mut [u8]) -> Result<usize, Error> {
+        self.ctx._finish()
+    }
+}
+
+impl CipherStreamingAPI for ChaCha20Legacy {}
+
+impl IVInit for ChaCha20Legacy {
+    fn init(&mut self, iv: &[u8]) -> Result<Success, Error> {
+        if iv.len() != CHACHA_LEGACY_NONCE_SIZE {
+            return Err(Error::Err);
+        }
+
+        self.ctx._init(iv);
+        Ok(Success::OK)
+    }
+}
+
+impl InputOutputUpdate for ChaCha20Legacy {
+    fn update(&mut self, input: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        self.ctx._process(input, output)
+    }
+}
+
+impl OutputFinish for ChaCha20Legacy {
+    fn finish(&mut self, _output: &mut [u8]) -> Result<usize, Error> {
+        self.ctx._finish()
+    }
+}
+
+impl CipherStreamingAPI for XChaCha20 {}
+
+impl IVInit for XChaCha20 {
+    fn init(&mut self, iv: &[u8]) -> Result<Success, Error> {
+        if iv.len() != XCHACHA_NONCE_SIZE {
+            return Err(Error::Err);
+        }
+
+        let (n1, n2) = iv.split_at(HCHACHA_NONCE_SIZE);
+        let mut subkey = hchacha20(&self.key, n1.try_into().unwrap());
+        self.ctx._set_key(&subkey);
+        subkey.fill(0);
+
+        /* ChaCha20 nonce: four zero bytes and the last 8 bytes of the nonce. */
+        let mut nonce = [0u8; CHACHA_NONCE_SIZE];
+        nonce[4..].copy_from_slice(n2);
+        self.ctx._init(&nonce);
+
+        Ok(Success::OK)
+    }
+}
+
+impl InputOutputUpdate for XChaCha20 {
+    fn update(&mut self, input: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        self.ctx._process(input, output)
+    }
+}
+
+impl OutputFinish for XChaCha20 {
+    fn finish(&mut self, _output: &mut [u8]) -> Result<usize, Error> {
+        let ret = self.ctx._finish();
+        self.ctx.state[4..12].fill(0);
+
+        ret
+    }
+}
@@ -0,0 +1,367 @@
+use crate::cipher::chacha::{ChaCha20, hchacha20, CHACHA_BLOCKSIZE, CHACHA_KEY_SIZE, CHACHA_NONCE_SIZE,
+    HCHACHA_NONCE_SIZE, XCHACHA_NONCE_SIZE};
+use crate::cipher::modes::Direction;
+use crate::common::api::{AEAD, AEADDecryptor, AEADEncryptor, IVInit, InputOutputUpdate, OutputFinish, SingleInputUpdate,
+    SingleOutputFinish, StreamingAEAD};
+use crate::common::{Success, Error};
+use crate::mac::{Poly1305, POLY1305_KEY_SIZE};
+use crate::utils::ct_eq;
+
+pub const CHACHA20_POLY1305_TAG_SIZE: usize = 16;
+
+/* The keystream of a nonce is 2^32 blocks, the first one gives the Poly1305 key. */
+const CHACHA20_POLY1305_MAX_MSG_SIZE: u64 = ((1 << 32) - 1) * CHACHA_BLOCKSIZE as u64;
+
+/// ChaCha20-Poly1305 (RFC 8439, section 2.8) with a 256-bit key and a 96-bit nonce.
+///
+/// Encryption and decryption go through the `AEAD` trait. Messages are limited to
+/// 2^38 - 64 bytes, the keystream of one nonce after the Poly1305 key.
+#[derive(Debug, Clone)]
+pub struct ChaCha20Poly1305 {
+    key: [u8; CHACHA_KEY_SIZE],
+}
+
+/// XChaCha20-Poly1305 (draft-irtf-cfrg-xchacha): ChaCha20-Poly1305 under the
+/// HChaCha20 subkey of the first 16 bytes of a 192-bit nonce, with the remaining 8
+/// bytes as nonce. Nonces can be chosen at random.
+#[derive(Debug, Clone)]
+pub struct XChaCha20Poly1305 {
+    key: [u8; CHACHA_KEY_SIZE],
+}
+
+fn _key(key: &[u8]) -> Result<[u8; CHACHA_KEY_SIZE], Error> {
+    key.try_into().map_err(|_| Error::Err)
+}
+
+impl XChaCha20Poly1305 {
+    /* ChaCha20-Poly1305 under the subkey, with four zero bytes and the last 8 bytes
+     * of the nonce as its nonce. */
+    fn _derive(&self, nonce: &[u8]) -> Result<(ChaCha20Poly1305, [u8; CHACHA_NONCE_SIZE]), Error> {
+        if nonce.len() != XCHACHA_NONCE_SIZE {
+            return Err(Error::Err);
+        }
+
+        let (n1, n2) = nonce.split_at(HCHACHA_NONCE_SIZE);
+        let inner = ChaCha20Poly1305 { key: hchacha20(&self.key, n1.try_into().unwrap()) };
+        let mut n = [0u8; CHACHA_NONCE_SIZE];
+        n[4..].copy_from_slice(n2);
+
+        Ok((inner, n))
+    }
+}
+
+/* ChaCha20 under the key and nonce, positioned at block 1, and the one-time
+ * Poly1305 key taken from block 0. */
+fn _cipher(key: &[u8; CHACHA_KEY_SIZE], nonce: &[u8], len: usize) -> Result<(ChaCha20, Poly1305), Error> {
+    if len as u64 > CHACHA20_POLY1305_MAX_MSG_SIZE {
+        return Err(Error::Err);
+    }
+
+    let mut cipher = ChaCha20::new(key)?;
+    cipher.init(nonce)?;
+
+    let mut otk = [0u8; POLY1305_KEY_SIZE];
+    cipher.update(&[0; POLY1305_KEY_SIZE], &mut otk)?;
+    let mac = Poly1305::new(&otk)?;
+    otk.fill(0);
+    cipher.seek(CHACHA_BLOCKSIZE as u64)?;
+
+    Ok((cipher, mac))
+}
+
+fn _apply(cipher: &mut ChaCha20, buffer: &mut [u8]) -> Result<Success, Error> {
+    let mut block = [0u8; CHACHA_BLOCKSIZE];
+    for chunk in buffer.chunks_mut(CHACHA_BLOCKSIZE) {
+        let ks = &mut block[..chunk.len()];
+        cipher.update(chunk, ks)?;
+        chunk.copy_from_slice(ks);
+    }
+    block.fill(0);
+
+    Ok(Success::OK)
+}
+
+fn _verify(mac: &mut Poly1305, aad: &[u8], ciphertext: &[u8], tag: &[u8]) -> Result<Success, Error> {
+    if tag.len() != CHACHA20_POLY1305_TAG_SIZE {
+        return Err(Error::Err);
+    }
+
+    let mut expected = [0u8; CHACHA20_POLY1305_TAG_SIZE];
+    _tag(mac, aad, ciphertext, &mut expected)?;
+    if !ct_eq(&expected, tag) {
+        return Err(Error::AuthFailed);
+    }
+
+    Ok(Success::OK)
+}
+
+/* Poly1305 over aad || pad16 || ciphertext || pad16 || le64(aad len) || le64(ct len). */
+fn _tag(mac: &mut Poly1305, aad: &[u8], ciphertext: &[u8], tag: &mut [u8]) -> Result<Success, Error> {
+    let pad = [0u8; 16];
+    mac.update(aad)?;
+    mac.update(&pad[..(16 - aad.len() % 16) % 16])?;
+    mac.update(ciphertext)?;
+    mac.update(&pad[..(16 - ciphertext.len() % 16) % 16])?;
+    mac.update(&(aad.len() as u64).to_le_bytes())?;
+    mac.update(&(ciphertext.len() as u64).to_le_bytes())?;
+    mac.finish(tag)
+}
+
+impl AEAD for ChaCha20Poly1305 {
+    const KEY_SIZE: usize = CHACHA_KEY_SIZE;
+    const NONCE_SIZE: usize = CHACHA_NONCE_SIZE;
+    const TAG_SIZE: usize = CHACHA20_POLY1305_TAG_SIZE;
+
+    fn new(key: &[u8]) -> Result<Self, Error> {
+        Ok(ChaCha20Poly1305 { key: _key(key)? })
+    }
+
+    fn nonce_len(&self) -> usize {
+        CHACHA_NONCE_SIZE
+    }
+
+    fn tag_len(&self) -> usize {
+        CHACHA20_POLY1305_TAG_SIZE
+    }
+
+    fn seal_in_place(&self, nonce: &[u8], aad: &[u8], buffer: &mut [u8], tag: &mut [u8]) -> Result<Success, Error> {
+        if tag.len() != CHACHA20_POLY1305_TAG_SIZE {
+            return Err(Error::Err);
+        }
+
+        let (mut cipher, mut mac) = _cipher(&self.key, nonce, buffer.len())?;
+        _apply(&mut cipher, buffer)?;
+        _tag(&mut mac, aad, buffer, tag)
+    }
+
+    fn open_in_place(&self, nonce: &[u8], aad: &[u8], buffer: &mut [u8], tag: &[u8]) -> Result<Success, Error> {
+        let (mut cipher, mut mac) = _cipher(&self.key, nonce, buffer.len())?;
+        _verify(&mut mac, aad, buffer, tag)?;
+        _apply(&mut cipher, buffer)
+    }
+}
+
+impl AEAD for XChaCha20Poly1305 {
+    const KEY_SIZE: usize = CHACHA_KEY_SIZE;
+    const NONCE_SIZE: usize = XCHACHA_NONCE_SIZE;
+    const TAG_SIZE: usize = CHACHA20_POLY1305_TAG_SIZE;
+
+    fn new(key: &[u8]) -> Result<Self, Error> {
+        Ok(XChaCha20Poly1305 { key: _key(key)? })
+    }
+
+    fn nonce_len(&self) -> usize {
+        XCHACHA_NONCE_SIZE
+    }
+
+    fn tag_len(&self) -> usize {
+        CHACHA20_POLY1305_TAG_SIZE
+    }
+
+    fn seal_in_place(&self, nonce: &[u8], aad: &[u8], buffer: &mut [u8], tag: &mut [u8]) -> Result<Success, Error> {
+        let (inner, n) = self._derive(nonce)?;
+        inner.seal_in_place(&n, aad, buffer, tag)
+    }
+
+    fn open_in_place(&self, nonce: &[u8], aad: &[u8], buffer: &mut [u8], tag: &[u8]) -> Result<Success, Error> {
+        let (inner, n) = self._derive(nonce)?;
+        inner.open_in_place(&n, aad, buffer, tag)
+    }
+}
+
+impl StreamingAEAD for ChaCha20Poly1305 {
+    type Encryptor = ChaCha20Poly1305Ctx;
+    type Decryptor = ChaCha20Poly1305Ctx;
+
+    fn encryptor(&self) -> ChaCha20Poly1305Ctx {
+        ChaCha20Poly1305Ctx::_with_key(self.key, CHACHA_NONCE_SIZE, Direction::Encrypt)
+    }
+
+    fn decryptor(&self) -> ChaCha20Poly1305Ctx {
+        ChaCha20Poly1305Ctx::_with_key(self.key, CHACHA_NONCE_SIZE, Direction::Decrypt)
+    }
+}
+
+impl StreamingAEAD for XChaCha20Poly1305 {
+    type Encryptor = ChaCha20Poly1305Ctx;
+    type Decryptor = ChaCha20Poly1305Ctx;
+
+    fn encryptor(&self) -> ChaCha20Poly1305Ctx {
+        ChaCha20Poly1305Ctx::_with_key(self.key, XCHACHA_NONCE_SIZE, Direction::Encrypt)
+    }
+
+    fn decryptor(&self) -> ChaCha20Poly1305Ctx {
+        ChaCha20Poly1305Ctx::_with_key(self.key, XCHACHA_NONCE_SIZE, Direction::Decrypt)
+    }
+}
+
+/// Streaming ChaCha20-Poly1305 and XChaCha20-Poly1305: `init` with the nonce,
+/// `update_aad` for all associated data, `update` for the message and finally
+/// `finish` to get the tag when encrypting or `verify` to check it when decrypting.
+///
+/// Decryption hands out plaintext before the tag has been checked. Callers must not
+/// use any of it unless `verify` succeeds; use `AEAD::open` where that cannot be
+/// guaranteed.
+#[derive(Debug, Clone)]
+pub struct ChaCha20Poly1305Ctx {
+    key: [u8; CHACHA_KEY_SIZE],
+    nonce_size: usize,
+    direction: Direction,
+    cipher: ChaCha20,
+    mac: Poly1305,
+    aad_len: u64,
+    msg_len: u64,
+    aad_done: bool,
+    initialized: bool,
+}
+
+impl ChaCha20Poly1305Ctx {
+    /// ChaCha20-Poly1305 with a 96-bit nonce.
+    pub fn new(key: &[u8], direction: Direction) -> Result<Self, Error> {
+        Ok(Self::_with_key(_key(key)?, CHACHA_NONCE_SIZE, direction))
+    }
+
+    /// XChaCha20-Poly1305 with a 192-bit nonce.
+    pub fn new_xchacha(key: &[u8], direction: Direction) -> Result<Self, Error> {
+        Ok(Self::_with_key(_key(key)?, XCHACHA_NONCE_SIZE, direction))
+    }
+
+    fn _with_key(key: [u8; CHACHA_KEY_SIZE], nonce_size: usize, direction: Direction) -> Self {
+        ChaCha20Poly1305Ctx {
+            key,
+            nonce_size,
+            direction,
+            cipher: ChaCha20::new(&[0; CHACHA_KEY_SIZE]).unwrap(),
+            mac: Poly1305::new(&[0; POLY1305_KEY_SIZE]).unwrap(),
+            aad_len: 0,
+            msg_len: 0,
+            aad_done: false,
+            initialized: false,
+        }
+    }
+
+    /// Adds associated data. Only allowed before the first call of `update`.
+    pub fn update_aad(&mut self, aad: &[u8]) -> Result<Success, Error> {
+        if !self.initialized || self.aad_done {
+            return Err(Error::Err);
+        }
+
+        self.mac.update(aad)?;
+        self.aad_len += aad.len() as u64;
+
+        Ok(Success::OK)
+    }
+
+    /// Checks the tag of a decrypted message.
+    pub fn verify(&mut self, tag: &[u8]) -> Result<Success, Error> {
+        if !self.initialized || self.direction != Direction::Decrypt || tag.len() != CHACHA20_POLY1305_TAG_SIZE {
+            return Err(Error::Err);
+        }
+
+        let mut expected = [0u8; CHACHA20_POLY1305_TAG_SIZE];
+        self._finish_tag(&mut expected)?;
+        if !ct_eq(&expected, tag) {
+            return Err(Error::AuthFailed);
+        }
+
+        Ok(Success::OK)
+    }
+
+    fn _pad_aad(&mut self) -> Result<Success, Error> {
+        if !self.aad_done {
+            self.mac.update(&[0; 16][..(16 - self.aad_len as usize % 16) % 16])?;
+            self.aad_done = true;
+        }
+
+        Ok(Success::OK)
+    }
+
+    fn _finish_tag(&mut self, tag: &mut [u8]) -> Result<Success, Error> {
+        self._pad_aad()?;
+        self.mac.update(&[0; 16][..(16 - self.msg_len as usize % 16) % 16])?;
+        self.mac.update(&self.aad_len.to_le_bytes())?;
+        self.mac.update(&self.msg_len.to_le_bytes())?;
+        self.mac.finish(tag)?;
+
+        self.cipher.finish(&mut [])?;
+        self.initialized = false;
+
+        Ok(Success::OK)
+    }
+}
+
+impl IVInit for ChaCha20Poly1305Ctx {
+    fn init(&mut self, iv: &[u8]) -> Result<Success, Error> {
+        if iv.len() != self.nonce_size {
+            return Err(Error::Err);
+        }
+
+        let (cipher, mac) = if self.nonce_size == XCHACHA_NONCE_SIZE {
+            let (inner, n) = XChaCha20Poly1305 { key: self.key }._derive(iv)?;
+            _cipher(&inner.key, &n, 0)?
+        } else {
+            _cipher(&self.key, iv, 0)?
+        };
+        self.cipher = cipher;
+        self.mac = mac;
+        self.aad_len = 0;
+        self.msg_len = 0;
+        self.aad_done = false;
+        self.initialized = true;
+
+        Ok(Success::OK)
+    }
+}
+
+impl InputOutputUpdate for ChaCha20Poly1305Ctx {
+    fn update(&mut self, input: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        if !self.initialized || output.len() < input.len() {
+            return Err(Error::Err);
+        }
+        if self.msg_len + input.len() as u64 > CHACHA20_POLY1305_MAX_MSG_SIZE {
+            return Err(Error::CounterOverflow);
+        }
+
+        /* The associated data ends with the first update. */
+        self._pad_aad()?;
+        if self.direction == Direction::Decrypt {
+            self.mac.update(input)?;
+        }
+        self.cipher.update(input, &mut output[..input.len()])?;
+        if self.direction == Direction::Encrypt {
+            self.mac.update(&output[..input.len()])?;
+        }
+        self.msg_len += input.len() as u64;
+
+        Ok(input.len())
+    }
+}
+
+impl OutputFinish for ChaCha20Poly1305Ctx {
+    /// Writes the tag when encrypting.
+    fn finish(&mut self, output: &mut [u8]) -> Result<usize, Error> {
+        if !self.initialized || self.direction != Direction::Encrypt || output.len() < CHACHA20_POLY1305_TAG_SIZE {
+            return Err(Error::Err);
+        }
+
+        self._finish_tag(&mut output[..CHACHA20_POLY1305_TAG_SIZE])?;
+
+        Ok(CHACHA20_POLY1305_TAG_SIZE)
+    }
+}
+
+impl AEADEncryptor for ChaCha20Poly1305Ctx {
+    fn update_aad(&mut self, aad: &[u8]) -> Result<Success, Error> {
+        ChaCha20Poly1305Ctx::update_aad(self, aad)
+    }
+}
+
+impl AEADDecryptor for ChaCha20Poly1305Ctx {
+    fn update_aad(&mut self, aad: &[u8]) -> Result<Success, Error> {
+        ChaCha20Poly1305Ctx::update_aad(self, aad)
+    }
+
+    fn verify(&mut self, tag: &[u8]) -> Result<Success, Error> {
+        ChaCha20Poly1305Ctx::verify(self, tag)
+    }
+}
@@ -0,0 +1,345 @@
+use crate::cipher::AES_BLOCKSIZE;
+use crate::cipher::ghash::GHASH;
+use crate::cipher::modes::Direction;
+use crate::common::api::{AEAD, AEADDecryptor, AEADEncryptor, BlockCipher, IVInit, InputOutputUpdate, OutputFinish, StreamingAEAD};
+use crate::common::{Success, Error};
+use crate::utils::ct_eq;
+
+pub const GCM_TAG_SIZE: usize = 16;
+pub const GCM_IV_SIZE: usize = 12;
+
+/* The plaintext is limited to 2^39 - 256 bits, so the 32-bit counter never wraps
+ * (SP 800-38D, section 5.2.1.1). */
+const GCM_MAX_MSG_SIZE: u64 = (1 << 36) - 32;
+
+/// Galois/Counter Mode (SP 800-38D) over a block cipher with 128-bit blocks.
+///
+/// Tags can be truncated to 12 to 16 bytes and, for applications that follow the
+/// restrictions of appendix C of SP 800-38D, to 8 or 4 bytes. Any IV length from
+/// one byte on is accepted; 96-bit IVs are recommended. Encryption and decryption
+/// go through the `AEAD` trait.
+#[derive(Debug, Clone)]
+pub struct GCM<C: BlockCipher<AES_BLOCKSIZE>> {
+    cipher: C,
+    pub(crate) h: [u8; AES_BLOCKSIZE],
+    tag_len: usize,
+}
+
+impl<C: BlockCipher<AES_BLOCKSIZE>> GCM<C> {
+    pub fn with_tag_len(key: &[u8], tag_len: usize) -> Result<Self, Error> {
+        if !matches!(tag_len, 4 | 8 | 12..=16) {
+            return Err(Error::Err);
+        }
+
+        let cipher = C::new(key)?;
+        let mut h = [0u8; AES_BLOCKSIZE];
+        cipher.encrypt_block(&mut h);
+
+        Ok(GCM { cipher, h, tag_len })
+    }
+
+    /* Pre-counter block J0 (section 7.1, step 2). */
+    pub(crate) fn _j0(&self, iv: &[u8]) -> Result<[u8; AES_BLOCKSIZE], Error> {
+        let mut j0 = [0u8; AES_BLOCKSIZE];
+
+        if iv.is_empty() {
+            return Err(Error::Err);
+        }
+
+        if iv.len() == GCM_IV_SIZE {
+            j0[..GCM_IV_SIZE].copy_from_slice(iv);
+            j0[AES_BLOCKSIZE - 1] = 1;
+        } else {
+            let mut ghash = GHASH::new(&self.h);
+            let mut lengths = [0u8; AES_BLOCKSIZE];
+            lengths[8..].copy_from_slice(&(iv.len() as u64 * 8).to_be_bytes());
+
+            ghash.update(iv);
+            ghash.pad();
+            ghash.update(&lengths);
+            ghash.finish(&mut j0);
+        }
+
+        Ok(j0)
+    }
+
+    /* GCTR starting with inc32(J0), applied in place. */
+    fn _gctr(&self, j0: &[u8; AES_BLOCKSIZE], buffer: &mut [u8]) {
+        let mut cb = *j0;
+
+        for chunk in buffer.chunks_mut(AES_BLOCKSIZE) {
+            _inc32(&mut cb);
+            let mut ks = cb;
+            self.cipher.encrypt_block(&mut ks);
+            for (b, k) in chunk.iter_mut().zip(ks.iter()) {
+                *b ^= k;
+            }
+        }
+    }
+
+    fn _tag(&self, j0: &[u8; AES_BLOCKSIZE], aad: &[u8], ciphertext: &[u8], tag: &mut [u8]) {
+        let mut ghash = GHASH::new(&self.h);
+        ghash.update(aad);
+        ghash.pad();
+        ghash.update(ciphertext);
+        self._finish_tag(j0, &mut ghash, aad.len() as u64, ciphertext.len() as u64, tag);
+    }
+
+    /* T = MSB_t(GCTR(J0, S)) with S = GHASH(A || 0^v || C || 0^u || [len(A)]64 || [len(C)]64). */
+    pub(crate) fn _finish_tag(&self, j0: &[u8; AES_BLOCKSIZE], ghash: &mut GHASH, aad_len: u64, msg_len: u64, tag: &mut [u8]) {
+        let mut lengths = [0u8; AES_BLOCKSIZE];
+        lengths[..8].copy_from_slice(&(aad_len * 8).to_be_bytes());
+        lengths[8..].copy_from_slice(&(msg_len * 8).to_be_bytes());
+
+        let mut s = [0u8; AES_BLOCKSIZE];
+        ghash.pad();
+        ghash.update(&lengths);
+        ghash.finish(&mut s);
+
+        let mut ek = *j0;
+        self.cipher.encrypt_block(&mut ek);
+        for (t, (a, b)) in tag.iter_mut().zip(s.iter().zip(ek.iter())) {
+            *t = a ^ b;
+        }
+    }
+
+    fn _verify(&self, iv: &[u8], aad: &[u8], ciphertext: &[u8], tag: &[u8]) -> Result<[u8; AES_BLOCKSIZE], Error> {
+        if tag.len() != self.tag_len || ciphertext.len() as u64 > GCM_MAX_MSG_SIZE {
+            return Err(Error::Err);
+        }
+
+        let j0 = self._j0(iv)?;
+        let mut expected = [0u8; GCM_TAG_SIZE];
+        self._tag(&j0, aad, ciphertext, &mut expected[..self.tag_len]);
+
+        if !ct_eq(&expected[..self.tag_len], tag) {
+            return Err(Error::AuthFailed);
+        }
+
+        Ok(j0)
+    }
+}
+
+impl<C: BlockCipher<AES_BLOCKSIZE>> AEAD for GCM<C> {
+    const KEY_SIZE: usize = C::KEY_SIZE;
+    const NONCE_SIZE: usize = GCM_IV_SIZE;
+    const TAG_SIZE: usize = GCM_TAG_SIZE;
+
+    fn new(key: &[u8]) -> Result<Self, Error> {
+        GCM::with_tag_len(key, GCM_TAG_SIZE)
+    }
+
+    fn nonce_len(&self) -> usize {
+        GCM_IV_SIZE
+    }
+
+    fn tag_len(&self) -> usize {
+        self.tag_len
+    }
+
+    fn seal_in_place(&self, nonce: &[u8], aad: &[u8], buffer: &mut [u8], tag: &mut [u8]) -> Result<Success, Error> {
+        if tag.len() != self.tag_len || buffer.len() as u64 > GCM_MAX_MSG_SIZE {
+            return Err(Error::Err);
+        }
+
+        let j0 = self._j0(nonce)?;
+        self._gctr(&j0, buffer);
+        self._tag(&j0, aad, buffer, tag);
+
+        Ok(Success::OK)
+    }
+
+    fn open_in_place(&self, nonce: &[u8], aad: &[u8], buffer: &mut [u8], tag: &[u8]) -> Result<Success, Error> {
+        let j0 = self._verify(nonce, aad, buffer, tag)?;
+        self._gctr(&j0, buffer);
+
+        Ok(Success::OK)
+    }
+}
+
+impl<C: BlockCipher<AES_BLOCKSIZE> + Clone> StreamingAEAD for GCM<C> {
+    type Encryptor = GCMCtx<C>;
+    type Decryptor = GCMCtx<C>;
+
+    fn encryptor(&self) -> GCMCtx<C> {
+        GCMCtx::_with_gcm(self.clone(), Direction::Encrypt)
+    }
+
+    fn decryptor(&self) -> GCMCtx<C> {
+        GCMCtx::_with_gcm(self.clone(), Direction::Decrypt)
+    }
+}
+
+/* Increments the rightmost 32 bits modulo 2^32. */
+fn _inc32(cb: &mut [u8; AES_BLOCKSIZE]) {
+    let ctr = u32::from_be_bytes(cb[12..].try_into().unwrap()).wrapping_add(1);
+    cb[12..].copy_from_slice(&ctr.to_be_bytes());
+}
+
+/// Streaming GCM: `init` with the IV, `update_aad` for all associated data, `update`
+/// for the message and finally `finish` to get the tag when encrypting or `verify`
+/// to check it when decrypting.
+///
+/// Decryption hands out plaintext before the tag has been checked. Callers must not
+/// use any of it unless `verify` succeeds; use `AEAD::open` where that cannot be
+/// guaranteed.
+#[derive(Debug, Clone)]
+pub struct GCMCtx<C: BlockCipher<AES_BLOCKSIZE>> {
+    gcm: GCM<C>,
+    direction: Direction,
+    ghash: GHASH,
+    j0: [u8; AES_BLOCKSIZE],
+    cb: [u8; AES_BLOCKSIZE],
+    keystream: [u8; AES_BLOCKSIZE],
+    ks_pos: usize,
+    aad_len: u64,
+    msg_len: u64,
+    aad_done: bool,
+    initialized: bool,
+}
+
+impl<C: BlockCipher<AES_BLOCKSIZE>> GCMCtx<C> {
+    pub fn new(key: &[u8], direction: Direction) -> Result<Self, Error> {
+        Self::with_tag_len(key, direction, GCM_TAG_SIZE)
+    }
+
+    pub fn with_tag_len(key: &[u8], direction: Direction, tag_len: usize) -> Result<Self, Error> {
+        Ok(Self::_with_gcm(GCM::with_tag_len(key, tag_len)?, direction))
+    }
+
+    fn _with_gcm(gcm: GCM<C>, direction: Direction) -> Self {
+        let ghash = GHASH::new(&gcm.h);
+
+        GCMCtx {
+            gcm,
+            direction,
+            ghash,
+            j0: [0; AES_BLOCKSIZE],
+            cb: [0; AES_BLOCKSIZE],
+            keystream: [0; AES_BLOCKSIZE],
+            ks_pos: AES_BLOCKSIZE,
+            aad_len: 0,
+            msg_len: 0,
+            aad_done: false,
+            initialized: false,
+        }
+    }
+
+    /// Adds associated data. Only allowed before the first call of `update`.
+    pub fn update_aad(&mut self, aad: &[u8]) -> Result<Success, Error> {
+        if !self.initialized || self.aad_done {
+            return Err(Error::Err);
+        }
+
+        self.ghash.update(aad);
+        self.aad_len += aad.len() as u64;
+
+        Ok(Success::OK)
+    }
+
+    /// Checks the tag of a decrypted message.
+    pub fn verify(&mut self, tag: &[u8]) -> Result<Success, Error> {
+        if !self.initialized || self.direction != Direction::Decrypt || tag.len() != self.gcm.tag_len {
+            return Err(Error::Err);
+        }
+
+        let mut expected = [0u8; GCM_TAG_SIZE];
+        self._finish_tag(&mut expected[..tag.len()]);
+
+        if !ct_eq(&expected[..tag.len()], tag) {
+            return Err(Error::AuthFailed);
+        }
+
+        Ok(Success::OK)
+    }
+
+    fn _finish_tag(&mut self, tag: &mut [u8]) {
+        self.gcm._finish_tag(&self.j0, &mut self.ghash, self.aad_len, self.msg_len, tag);
+
+        self.keystream.fill(0);
+        self.initialized = false;
+    }
+}
+
+impl<C: BlockCipher<AES_BLOCKSIZE>> IVInit for GCMCtx<C> {
+    fn init(&mut self, iv: &[u8]) -> Result<Success, Error> {
+        self.j0 = self.gcm._j0(iv)?;
+        self.cb = self.j0;
+        self.ghash = GHASH::new(&self.gcm.h);
+        self.ks_pos = AES_BLOCKSIZE;
+        self.aad_len = 0;
+        self.msg_len = 0;
+        self.aad_done = false;
+        self.initialized = true;
+
+        Ok(Success::OK)
+    }
+}
+
+impl<C: BlockCipher<AES_BLOCKSIZE>> InputOutputUpdate for GCMCtx<C> {
+    fn update(&mut self, input: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        if !self.initialized || output.len() < input.len() {
+            return Err(Error::Err);
+        }
+        if self.msg_len + input.len() as u64 > GCM_MAX_MSG_SIZE {
+            return Err(Error::CounterOverflow);
+        }
+
+        /* The associated data ends with the first update. */
+        if !self.aad_done {
+            self.ghash.pad();
+            self.aad_done = true;
+        }
+        if self.direction == Direction::Decrypt {
+            self.ghash.update(input);
+        }
+
+        for (o, i) in output.iter_mut().zip(input.iter()) {
+            if self.ks_pos == AES_BLOCKSIZE {
+                _inc32(&mut self.cb);
+                self.keystream = self.cb;
+                self.gcm.cipher.encrypt_block(&mut self.keystream);
+                self.ks_pos = 0;
+            }
+            *o = i ^ self.keystream[self.ks_pos];
+            self.ks_pos += 1;
+        }
+
+        if self.direction == Direction::Encrypt {
+            self.ghash.update(&output[..input.len()]);
+        }
+        self.msg_len += input.len() as u64;
+
+        Ok(input.len())
+    }
+}
+
+impl<C: BlockCipher<AES_BLOCKSIZE>> OutputFinish for GCMCtx<C> {
+    /// Writes the tag when encrypting.
+    fn finish(&mut self, output: &mut [u8]) -> Result<usize, Error> {
+        let tag_len = self.gcm.tag_len;
+        if !self.initialized || self.direction != Direction::Encrypt || output.len() < tag_len {
+            return Err(Error::Err);
+        }
+
+        self._finish_tag(&mut output[..tag_len]);
+
+        Ok(tag_len)
+    }
+}
+
+impl<C: BlockCipher<AES_BLOCKSIZE>> AEADEncryptor for GCMCtx<C> {
+    fn update_aad(&mut self, aad: &[u8]) -> Result<Success, Error> {
+        GCMCtx::update_aad(self, aad)
+    }
+}
+
+impl<C: BlockCipher<AES_BLOCKSIZE>> AEADDecryptor for GCMCtx<C> {
+    fn update_aad(&mut self, aad: &[u8]) -> Result<Success, Error> {
+        GCMCtx::update_aad(self, aad)
+    }
+
+    fn verify(&mut self, tag: &[u8]) -> Result<Success, Error> {
+        GCMCtx::verify(self, tag)
+    }
+}
@@ -0,0 +1,154 @@
+use crate::cipher::AES_BLOCKSIZE;
+use crate::cipher::polyval::POLYVAL;
+use crate::common::api::{AEAD, BlockCipher};
+use crate::common::{Success, Error};
+use crate::utils::ct_eq;
+
+pub const GCM_SIV_NONCE_SIZE: usize = 12;
+pub const GCM_SIV_TAG_SIZE: usize = 16;
+
+/* Plaintext and associated data are limited to 2^36 bytes (RFC 8452, section 6). */
+const GCM_SIV_MAX_SIZE: u64 = 1 << 36;
+
+/* The derived keys are at most a 16-byte authentication key and a 32-byte
+ * encryption key. */
+const GCM_SIV_MAX_KEY_MATERIAL: usize = 48;
+
+/// AES-GCM-SIV (RFC 8452) for `C` = AES-128 or AES-256.
+///
+/// Per-nonce authentication and encryption keys are derived from the key, the tag is
+/// computed with POLYVAL over the plaintext and doubles as the initial counter block.
+/// Repeating a nonce only reveals whether the same message was encrypted. The tag is
+/// appended to the ciphertext; if decryption fails the output is zeroed, in place the
+/// ciphertext is restored.
+#[derive(Debug, Clone)]
+pub struct GCMSIV<C: BlockCipher<AES_BLOCKSIZE>> {
+    cipher: C,
+}
+
+impl<C: BlockCipher<AES_BLOCKSIZE>> GCMSIV<C> {
+    /* Section 4: the first 8 bytes of AES(K, LE32(i) || N) for i = 0, 1 give the
+     * authentication key, the following ones the encryption key. */
+    fn _derive_keys(&self, nonce: &[u8]) -> Result<(POLYVAL, C), Error> {
+        let mut material = [0u8; GCM_SIV_MAX_KEY_MATERIAL];
+        let len = 16 + C::KEY_SIZE;
+
+        for (i, chunk) in material[..len].chunks_mut(8).enumerate() {
+            let mut block = [0u8; AES_BLOCKSIZE];
+            block[..4].copy_from_slice(&(i as u32).to_le_bytes());
+            block[4..].copy_from_slice(nonce);
+            self.cipher.encrypt_block(&mut block);
+            chunk.copy_from_slice(&block[..8]);
+            block.fill(0);
+        }
+
+        let polyval = POLYVAL::new(material[..16].try_into().unwrap());
+        let enc = C::new(&material[16..len]);
+        material.fill(0);
+
+        Ok((polyval, enc?))
+    }
+
+    fn _tag(&self, polyval: &mut POLYVAL, enc: &C, nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> [u8; AES_BLOCKSIZE] {
+        let mut lengths = [0u8; AES_BLOCKSIZE];
+        lengths[..8].copy_from_slice(&(aad.len() as u64 * 8).to_le_bytes());
+        lengths[8..].copy_from_slice(&(plaintext.len() as u64 * 8).to_le_bytes());
+
+        let mut s = [0u8; AES_BLOCKSIZE];
+        polyval.update(aad);
+        polyval.pad();
+        polyval.update(plaintext);
+        polyval.pad();
+        polyval.update(&lengths);
+        polyval.finish(&mut s);
+
+        for (a, b) in s.iter_mut().zip(nonce.iter()) {
+            *a ^= b;
+        }
+        s[AES_BLOCKSIZE - 1] &= 0x7f;
+        enc.encrypt_block(&mut s);
+
+        s
+    }
+
+    /* Counter mode from the tag with the top bit set, the first 32 bits are a
+     * little-endian counter that wraps. */
+    fn _ctr(&self, enc: &C, tag: &[u8; AES_BLOCKSIZE], buffer: &mut [u8]) {
+        let mut cb = *tag;
+        cb[AES_BLOCKSIZE - 1] |= 0x80;
+
+        for chunk in buffer.chunks_mut(AES_BLOCKSIZE) {
+            let mut ks = cb;
+            enc.encrypt_block(&mut ks);
+            for (b, k) in chunk.iter_mut().zip(ks.iter()) {
+                *b ^= k;
+            }
+
+            let ctr = u32::from_le_bytes(cb[..4].try_into().unwrap()).wrapping_add(1);
+            cb[..4].copy_from_slice(&ctr.to_le_bytes());
+        }
+    }
+
+    fn _check(nonce: &[u8], aad: &[u8], msg_len: usize) -> Result<Success, Error> {
+        if nonce.len() != GCM_SIV_NONCE_SIZE
+            || aad.len() as u64 > GCM_SIV_MAX_SIZE
+            || msg_len as u64 > GCM_SIV_MAX_SIZE
+        {
+            return Err(Error::Err);
+        }
+
+        Ok(Success::OK)
+    }
+}
+
+impl<C: BlockCipher<AES_BLOCKSIZE>> AEAD for GCMSIV<C> {
+    const KEY_SIZE: usize = C::KEY_SIZE;
+    const NONCE_SIZE: usize = GCM_SIV_NONCE_SIZE;
+    const TAG_SIZE: usize = GCM_SIV_TAG_SIZE;
+
+    fn new(key: &[u8]) -> Result<Self, Error> {
+        if C::KEY_SIZE != 16 && C::KEY_SIZE != 32 {
+            return Err(Error::Err);
+        }
+
+        Ok(GCMSIV { cipher: C::new(key)? })
+    }
+
+    fn nonce_len(&self) -> usize {
+        GCM_SIV_NONCE_SIZE
+    }
+
+    fn tag_len(&self) -> usize {
+        GCM_SIV_TAG_SIZE
+    }
+
+    fn seal_in_place(&self, nonce: &[u8], aad: &[u8], buffer: &mut [u8], tag: &mut [u8]) -> Result<Success, Error> {
+        Self::_check(nonce, aad, buffer.len())?;
+        if tag.len() != GCM_SIV_TAG_SIZE {
+            return Err(Error::Err);
+        }
+
+        let (mut polyval, enc) = self._derive_keys(nonce)?;
+        let t = self._tag(&mut polyval, &enc, nonce, aad, buffer);
+        self._ctr(&enc, &t, buffer);
+        tag.copy_from_slice(&t);
+
+        Ok(Success::OK)
+    }
+
+    fn open_in_place(&self, nonce: &[u8], aad: &[u8], buffer: &mut [u8], tag: &[u8]) -> Result<Success, Error> {
+        Self::_check(nonce, aad, buffer.len())?;
+        let tag: &[u8; AES_BLOCKSIZE] = tag.try_into().map_err(|_| Error::Err)?;
+
+        let (mut polyval, enc) = self._derive_keys(nonce)?;
+        self._ctr(&enc, tag, buffer);
+
+        let expected = self._tag(&mut polyval, &enc, nonce, aad, buffer);
+        if !ct_eq(&expected, tag) {
+            self._ctr(&enc, tag, buffer);
+            return Err(Error::AuthFailed);
+        }
+
+        Ok(Success::OK)
+    }
+}
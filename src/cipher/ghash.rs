@@ -0,0 +1,144 @@
+/* GHASH (SP 800-38D, section 6.4) without table lookups.
+ *
+ * The carry-less multiplication uses integer multiplications on operands where only
+ * every fourth bit is set, so the carries land in the holes and are masked out
+ * afterwards (as in BearSSL's ghash_ctmul64). GCM's bit order is reflected: the
+ * product of the bit reversed operands gives the upper half of the 128x128 bit
+ * product, the plain operands give the lower half. */
+
+pub const GHASH_BLOCKSIZE: usize = 16;
+
+/// The universal hash of GCM, keyed with the hash subkey H.
+#[derive(Debug, Clone)]
+pub struct GHASH {
+    /* Most significant half first, as the bytes are read big-endian. */
+    h: [u64; 2],
+    y: [u64; 2],
+    buffer: [u8; GHASH_BLOCKSIZE],
+    rem_pos: usize,
+}
+
+impl GHASH {
+    pub fn new(h: &[u8; GHASH_BLOCKSIZE]) -> Self {
+        GHASH {
+            h: _load(h),
+            y: [0; 2],
+            buffer: [0; GHASH_BLOCKSIZE],
+            rem_pos: 0,
+        }
+    }
+
+    /// Absorbs `input`, buffering a partial block.
+    pub fn update(&mut self, mut input: &[u8]) {
+        if self.rem_pos > 0 {
+            let take = usize::min(GHASH_BLOCKSIZE - self.rem_pos, input.len());
+            self.buffer[self.rem_pos..self.rem_pos + take].copy_from_slice(&input[..take]);
+            self.rem_pos += take;
+            input = &input[take..];
+
+            if self.rem_pos < GHASH_BLOCKSIZE {
+                return;
+            }
+            let block = self.buffer;
+            self._mul(&block);
+            self.rem_pos = 0;
+        }
+
+        let mut blocks = input.chunks_exact(GHASH_BLOCKSIZE);
+        for block in &mut blocks {
+            self._mul(block.try_into().unwrap());
+        }
+
+        let rest = blocks.remainder();
+        self.buffer[..rest.len()].copy_from_slice(rest);
+        self.rem_pos = rest.len();
+    }
+
+    /// Completes a partial block with zeros.
+    pub fn pad(&mut self) {
+        if self.rem_pos > 0 {
+            self.buffer[self.rem_pos..].fill(0);
+            let block = self.buffer;
+            self._mul(&block);
+            self.rem_pos = 0;
+        }
+    }
+
+    /// Writes the hash value and resets the state, the key is kept.
+    pub fn finish(&mut self, output: &mut [u8; GHASH_BLOCKSIZE]) {
+        self.pad();
+        output[..8].copy_from_slice(&self.y[0].to_be_bytes());
+        output[8..].copy_from_slice(&self.y[1].to_be_bytes());
+
+        self.y = [0; 2];
+        self.buffer.fill(0);
+    }
+
+    /* Y = (Y ^ X) * H */
+    fn _mul(&mut self, block: &[u8; GHASH_BLOCKSIZE]) {
+        let x = _load(block);
+        let (y1, y0) = (self.y[0] ^ x[0], self.y[1] ^ x[1]);
+        let (h1, h0) = (self.h[0], self.h[1]);
+
+        /* Karatsuba on the 64-bit halves, for the plain and the reversed operands. */
+        let (y1r, y0r) = (y1.reverse_bits(), y0.reverse_bits());
+        let (h1r, h0r) = (h1.reverse_bits(), h0.reverse_bits());
+
+        let z0 = _bmul64(y0, h0);
+        let z1 = _bmul64(y1, h1);
+        let mut z2 = _bmul64(y0 ^ y1, h0 ^ h1);
+        let mut z0h = _bmul64(y0r, h0r);
+        let mut z1h = _bmul64(y1r, h1r);
+        let mut z2h = _bmul64(y0r ^ y1r, h0r ^ h1r);
+
+        z2 ^= z0 ^ z1;
+        z2h ^= z0h ^ z1h;
+        z0h = z0h.reverse_bits() >> 1;
+        z1h = z1h.reverse_bits() >> 1;
+        z2h = z2h.reverse_bits() >> 1;
+
+        /* 256-bit product v3:v2:v1:v0, shifted by one to undo the reflection. */
+        let mut v0 = z0;
+        let mut v1 = z0h ^ z2;
+        let mut v2 = z1 ^ z2h;
+        let mut v3 = z1h;
+
+        v3 = (v3 << 1) | (v2 >> 63);
+        v2 = (v2 << 1) | (v1 >> 63);
+        v1 = (v1 << 1) | (v0 >> 63);
+        v0 <<= 1;
+
+        /* Reduction modulo x^128 + x^7 + x^2 + x + 1. */
+        v2 ^= v0 ^ (v0 >> 1) ^ (v0 >> 2) ^ (v0 >> 7);
+        v1 ^= (v0 << 63) ^ (v0 << 62) ^ (v0 << 57);
+        v3 ^= v1 ^ (v1 >> 1) ^ (v1 >> 2) ^ (v1 >> 7);
+        v2 ^= (v1 << 63) ^ (v1 << 62) ^ (v1 << 57);
+
+        self.y = [v3, v2];
+    }
+}
+
+fn _load(block: &[u8; GHASH_BLOCKSIZE]) -> [u64; 2] {
+    [
+        u64::from_be_bytes(block[..8].try_into().unwrap()),
+        u64::from_be_bytes(block[8..].try_into().unwrap()),
+    ]
+}
+
+/* Carry-less multiplication, lower 64 bits of the product. */
+fn _bmul64(x: u64, y: u64) -> u64 {
+    const M0: u64 = 0x1111_1111_1111_1111;
+    const M1: u64 = 0x2222_2222_2222_2222;
+    const M2: u64 = 0x4444_4444_4444_4444;
+    const M3: u64 = 0x8888_8888_8888_8888;
+
+    let (x0, x1, x2, x3) = (x & M0, x & M1, x & M2, x & M3);
+    let (y0, y1, y2, y3) = (y & M0, y & M1, y & M2, y & M3);
+
+    let z0 = x0.wrapping_mul(y0) ^ x1.wrapping_mul(y3) ^ x2.wrapping_mul(y2) ^ x3.wrapping_mul(y1);
+    let z1 = x0.wrapping_mul(y1) ^ x1.wrapping_mul(y0) ^ x2.wrapping_mul(y3) ^ x3.wrapping_mul(y2);
+    let z2 = x0.wrapping_mul(y2) ^ x1.wrapping_mul(y1) ^ x2.wrapping_mul(y0) ^ x3.wrapping_mul(y3);
+    let z3 = x0.wrapping_mul(y3) ^ x1.wrapping_mul(y2) ^ x2.wrapping_mul(y1) ^ x3.wrapping_mul(y0);
+
+    (z0 & M0) | (z1 & M1) | (z2 & M2) | (z3 & M3)
+}
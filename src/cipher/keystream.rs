@@ -0,0 +1,116 @@
+use crate::common::{Success, Error};
+
+const KEYSTREAM_BLOCKSIZE: usize = 64;
+
+/* Buffered keystream of a counter mode block function, shared by Salsa20 and
+ * ChaCha20. `counter` is the number of the next block and `max_blocks` the number
+ * of blocks the counter of the variant can address. `buffer` is the current
+ * keystream block, used up to `rem_pos`. The block function writes the keystream
+ * block for a counter value into the buffer. */
+#[derive(Debug, Clone)]
+pub(crate) struct Keystream {
+    counter: u128,
+    max_blocks: u128,
+    buffer: [u8; KEYSTREAM_BLOCKSIZE],
+    rem_pos: usize,
+    initialized: bool,
+}
+
+impl Keystream {
+    pub(crate) fn new(max_blocks: u128) -> Self {
+        Keystream {
+            counter: 0,
+            max_blocks,
+            buffer: [0; KEYSTREAM_BLOCKSIZE],
+            rem_pos: KEYSTREAM_BLOCKSIZE,
+            initialized: false,
+        }
+    }
+
+    /* Starts at block 0, called once the nonce is set. */
+    pub(crate) fn _init(&mut self) {
+        self.counter = 0;
+        self.buffer.fill(0);
+        self.rem_pos = KEYSTREAM_BLOCKSIZE;
+        self.initialized = true;
+    }
+
+    fn _next_block<F>(&mut self, block: &mut F)
+    where
+        F: FnMut(u128, &mut [u8; KEYSTREAM_BLOCKSIZE]),
+    {
+        block(self.counter, &mut self.buffer);
+        self.counter += 1;
+        self.rem_pos = 0;
+    }
+
+    pub(crate) fn _seek<F>(&mut self, pos: u64, mut block: F) -> Result<Success, Error>
+    where
+        F: FnMut(u128, &mut [u8; KEYSTREAM_BLOCKSIZE]),
+    {
+        if !self.initialized {
+            return Err(Error::Err);
+        }
+
+        let index = pos as u128 / KEYSTREAM_BLOCKSIZE as u128;
+        let offset = pos as usize % KEYSTREAM_BLOCKSIZE;
+        if index > self.max_blocks || (index == self.max_blocks && offset != 0) {
+            return Err(Error::CounterOverflow);
+        }
+
+        self.counter = index;
+        self.rem_pos = KEYSTREAM_BLOCKSIZE;
+        if offset != 0 {
+            self._next_block(&mut block);
+            self.rem_pos = offset;
+        }
+
+        Ok(Success::OK)
+    }
+
+    pub(crate) fn _process<F>(&mut self, input: &[u8], output: &mut [u8], mut block: F) -> Result<usize, Error>
+    where
+        F: FnMut(u128, &mut [u8; KEYSTREAM_BLOCKSIZE]),
+    {
+        if !self.initialized || output.len() < input.len() {
+            return Err(Error::Err);
+        }
+
+        /* Keystream left before the counter would wrap. */
+        let available = (self.max_blocks - self.counter) * KEYSTREAM_BLOCKSIZE as u128
+            + (KEYSTREAM_BLOCKSIZE - self.rem_pos) as u128;
+        if input.len() as u128 > available {
+            return Err(Error::CounterOverflow);
+        }
+
+        let mut n = 0;
+        while n < input.len() {
+            if self.rem_pos == KEYSTREAM_BLOCKSIZE {
+                self._next_block(&mut block);
+            }
+
+            let take = usize::min(KEYSTREAM_BLOCKSIZE - self.rem_pos, input.len() - n);
+            let ks = &self.buffer[self.rem_pos..self.rem_pos + take];
+            for ((o, i), k) in output[n..n + take].iter_mut().zip(input[n..n + take].iter()).zip(ks.iter()) {
+                *o = i ^ k;
+            }
+            self.rem_pos += take;
+            n += take;
+        }
+
+        Ok(input.len())
+    }
+
+    pub(crate) fn _finish(&mut self) -> Result<usize, Error> {
+        if !self.initialized {
+            return Err(Error::Err);
+        }
+
+        self.buffer.fill(0);
+        self.counter = 0;
+        self.rem_pos = KEYSTREAM_BLOCKSIZE;
+        self.initialized = false;
+
+        Ok(0)
+    }
+}
@@ -0,0 +1,212 @@
+use crate::cipher::AES_BLOCKSIZE;
+use crate::common::api::BlockCipher;
+use crate::common::Error;
+use crate::utils::ct_eq;
+
+pub const KW_SEMIBLOCK_SIZE: usize = 8;
+
+const KW_ICV1: [u8; KW_SEMIBLOCK_SIZE] = [0xa6; KW_SEMIBLOCK_SIZE];
+const KWP_ICV2: [u8; 4] = [0xa6, 0x59, 0x59, 0xa6];
+
+/// AES Key Wrap (SP 800-38F KW, RFC 3394) under a key-encryption key.
+///
+/// `wrap` takes keys of at least two semiblocks (16 bytes) in whole semiblocks and
+/// produces one semiblock more. `unwrap` fails with `Error::IntegrityCheck` if the
+/// integrity check value does not match; the output is zeroed in that case.
+///
+/// `with_inverse_cipher` selects the variant of SP 800-38F that uses the inverse
+/// cipher function for wrapping and the forward cipher for unwrapping.
+#[derive(Debug, Clone)]
+pub struct KW<C: BlockCipher<AES_BLOCKSIZE>> {
+    cipher: C,
+    inverse: bool,
+}
+
+impl<C: BlockCipher<AES_BLOCKSIZE>> KW<C> {
+    pub fn new(kek: &[u8]) -> Result<Self, Error> {
+        Ok(KW { cipher: C::new(kek)?, inverse: false })
+    }
+
+    pub fn with_inverse_cipher(kek: &[u8]) -> Result<Self, Error> {
+        Ok(KW { cipher: C::new(kek)?, inverse: true })
+    }
+
+    /// Wraps `input` into `output` and returns the number of bytes written.
+    pub fn wrap(&self, input: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        let len = input.len();
+        if len < 2 * KW_SEMIBLOCK_SIZE || !len.is_multiple_of(KW_SEMIBLOCK_SIZE) || output.len() < len + KW_SEMIBLOCK_SIZE {
+            return Err(Error::Err);
+        }
+
+        let s = &mut output[..len + KW_SEMIBLOCK_SIZE];
+        s[..KW_SEMIBLOCK_SIZE].copy_from_slice(&KW_ICV1);
+        s[KW_SEMIBLOCK_SIZE..].copy_from_slice(input);
+        self._w(s);
+
+        Ok(len + KW_SEMIBLOCK_SIZE)
+    }
+
+    /// Unwraps `input` into `output` and returns the length of the key.
+    pub fn unwrap(&self, input: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        let len = input.len();
+        if len < 3 * KW_SEMIBLOCK_SIZE || !len.is_multiple_of(KW_SEMIBLOCK_SIZE) || output.len() < len - KW_SEMIBLOCK_SIZE {
+            return Err(Error::Err);
+        }
+
+        let r = &mut output[..len - KW_SEMIBLOCK_SIZE];
+        let a = self._w_inv(input, r);
+        if !ct_eq(&a, &KW_ICV1) {
+            r.fill(0);
+            return Err(Error::IntegrityCheck);
+        }
+
+        Ok(r.len())
+    }
+
+    /* Wrapping function W (SP 800-38F, algorithm 1) applied in place to S = A || R,
+     * six rounds over all semiblocks of R. */
+    fn _w(&self, s: &mut [u8]) {
+        let n = s.len() / KW_SEMIBLOCK_SIZE - 1;
+        let mut block = [0u8; AES_BLOCKSIZE];
+
+        for j in 0..6 {
+            for i in 1..=n {
+                let r = i * KW_SEMIBLOCK_SIZE;
+                block[..KW_SEMIBLOCK_SIZE].copy_from_slice(&s[..KW_SEMIBLOCK_SIZE]);
+                block[KW_SEMIBLOCK_SIZE..].copy_from_slice(&s[r..r + KW_SEMIBLOCK_SIZE]);
+                self._forward(&mut block);
+
+                let t = (n * j + i) as u64;
+                let a = u64::from_be_bytes(block[..KW_SEMIBLOCK_SIZE].try_into().unwrap()) ^ t;
+                s[..KW_SEMIBLOCK_SIZE].copy_from_slice(&a.to_be_bytes());
+                s[r..r + KW_SEMIBLOCK_SIZE].copy_from_slice(&block[KW_SEMIBLOCK_SIZE..]);
+            }
+        }
+        block.fill(0);
+    }
+
+    /* Unwrapping function W^-1 (algorithm 2): the semiblocks of `input` after the
+     * first are unwrapped into `r`, the resulting A is returned. */
+    fn _w_inv(&self, input: &[u8], r: &mut [u8]) -> [u8; KW_SEMIBLOCK_SIZE] {
+        let n = r.len() / KW_SEMIBLOCK_SIZE;
+        let mut a = u64::from_be_bytes(input[..KW_SEMIBLOCK_SIZE].try_into().unwrap());
+        let mut block = [0u8; AES_BLOCKSIZE];
+        r.copy_from_slice(&input[KW_SEMIBLOCK_SIZE..]);
+
+        for j in (0..6).rev() {
+            for i in (1..=n).rev() {
+                let pos = (i - 1) * KW_SEMIBLOCK_SIZE;
+                let t = (n * j + i) as u64;
+                block[..KW_SEMIBLOCK_SIZE].copy_from_slice(&(a ^ t).to_be_bytes());
+                block[KW_SEMIBLOCK_SIZE..].copy_from_slice(&r[pos..pos + KW_SEMIBLOCK_SIZE]);
+                self._backward(&mut block);
+
+                a = u64::from_be_bytes(block[..KW_SEMIBLOCK_SIZE].try_into().unwrap());
+                r[pos..pos + KW_SEMIBLOCK_SIZE].copy_from_slice(&block[KW_SEMIBLOCK_SIZE..]);
+            }
+        }
+        block.fill(0);
+
+        a.to_be_bytes()
+    }
+
+    fn _forward(&self, block: &mut [u8; AES_BLOCKSIZE]) {
+        if self.inverse {
+            self.cipher.decrypt_block(block);
+        } else {
+            self.cipher.encrypt_block(block);
+        }
+    }
+
+    fn _backward(&self, block: &mut [u8; AES_BLOCKSIZE]) {
+        if self.inverse {
+            self.cipher.encrypt_block(block);
+        } else {
+            self.cipher.decrypt_block(block);
+        }
+    }
+}
+
+/// AES Key Wrap with Padding (SP 800-38F KWP, RFC 5649).
+///
+/// Keys of 1 to 2^32 - 1 bytes are padded to whole semiblocks and wrapped with
+/// their length. `unwrap` needs an output of the padded length, i.e. the input
+/// length minus one semiblock, and returns the length of the key.
+#[derive(Debug, Clone)]
+pub struct KWP<C: BlockCipher<AES_BLOCKSIZE>> {
+    kw: KW<C>,
+}
+
+impl<C: BlockCipher<AES_BLOCKSIZE>> KWP<C> {
+    pub fn new(kek: &[u8]) -> Result<Self, Error> {
+        Ok(KWP { kw: KW::new(kek)? })
+    }
+
+    pub fn with_inverse_cipher(kek: &[u8]) -> Result<Self, Error> {
+        Ok(KWP { kw: KW::with_inverse_cipher(kek)? })
+    }
+
+    /// Wraps `input` into `output` and returns the number of bytes written.
+    pub fn wrap(&self, input: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        let len = input.len();
+        let padded = len.next_multiple_of(KW_SEMIBLOCK_SIZE);
+        if len == 0 || len as u64 > u32::MAX as u64 || output.len() < padded + KW_SEMIBLOCK_SIZE {
+            return Err(Error::Err);
+        }
+
+        let s = &mut output[..padded + KW_SEMIBLOCK_SIZE];
+        s[..4].copy_from_slice(&KWP_ICV2);
+        s[4..KW_SEMIBLOCK_SIZE].copy_from_slice(&(len as u32).to_be_bytes());
+        s[KW_SEMIBLOCK_SIZE..KW_SEMIBLOCK_SIZE + len].copy_from_slice(input);
+        s[KW_SEMIBLOCK_SIZE + len..].fill(0);
+
+        /* A single padded semiblock is enciphered directly. */
+        if padded == KW_SEMIBLOCK_SIZE {
+            let block: &mut [u8; AES_BLOCKSIZE] = s.try_into().unwrap();
+            self.kw._forward(block);
+        } else {
+            self.kw._w(s);
+        }
+
+        Ok(padded + KW_SEMIBLOCK_SIZE)
+    }
+
+    /// Unwraps `input` into `output` and returns the length of the key.
+    pub fn unwrap(&self, input: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        let len = input.len();
+        if len < 2 * KW_SEMIBLOCK_SIZE || !len.is_multiple_of(KW_SEMIBLOCK_SIZE) || output.len() < len - KW_SEMIBLOCK_SIZE {
+            return Err(Error::Err);
+        }
+
+        let n = len - KW_SEMIBLOCK_SIZE;
+        let r = &mut output[..n];
+        let a = if len == AES_BLOCKSIZE {
+            let mut block: [u8; AES_BLOCKSIZE] = input.try_into().unwrap();
+            self.kw._backward(&mut block);
+            r.copy_from_slice(&block[KW_SEMIBLOCK_SIZE..]);
+            let a = block[..KW_SEMIBLOCK_SIZE].try_into().unwrap();
+            block.fill(0);
+            a
+        } else {
+            self.kw._w_inv(input, r)
+        };
+
+        /* The ICV, the message length indicator 8 * (n - 1) < MLI <= 8 * n and the
+         * zero padding are all checked before deciding. */
+        let mli = u32::from_be_bytes(a[4..].try_into().unwrap()) as u64;
+        let n = n as u64;
+        let mut bad = !ct_eq(&a[..4], &KWP_ICV2) as u8;
+        bad |= (mli > n) as u8 | (mli + (KW_SEMIBLOCK_SIZE as u64) <= n) as u8;
+        for (i, b) in r.iter().enumerate().skip(r.len() - KW_SEMIBLOCK_SIZE) {
+            let in_pad = (((i as u64).wrapping_sub(mli) >> 63) as u8 ^ 1).wrapping_neg();
+            bad |= in_pad & b;
+        }
+
+        if bad != 0 {
+            r.fill(0);
+            return Err(Error::IntegrityCheck);
+        }
+
+        Ok(mli as usize)
+    }
+}
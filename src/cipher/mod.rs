@@ -0,0 +1,36 @@
+mod aes;
+mod sm4;
+mod modes;
+mod ghash;
+mod gcm;
+mod ccm;
+mod polyval;
+mod siv;
+mod gcm_siv;
+mod xts;
+mod kw;
+mod keystream;
+mod chacha;
+mod chacha20_poly1305;
+mod salsa;
+mod secretbox;
+mod stream;
+
+pub use crate::cipher::aes::{AES, AES128, AES192, AES256, AES_BLOCKSIZE};
+pub use crate::cipher::sm4::{SM4, SM4_BLOCKSIZE, SM4_KEY_SIZE};
+pub use crate::cipher::modes::{Direction, Padding, ECB, CBC, CFB8, CFB128, OFB, CTR};
+pub use crate::cipher::gcm::{GCM, GCMCtx, GCM_IV_SIZE, GCM_TAG_SIZE};
+pub use crate::cipher::ccm::CCM;
+pub use crate::cipher::siv::{SIV, SIV_TAG_SIZE};
+pub use crate::cipher::gcm_siv::{GCMSIV, GCM_SIV_NONCE_SIZE, GCM_SIV_TAG_SIZE};
+pub use crate::cipher::xts::{XTS, XTS_TWEAK_SIZE};
+pub use crate::cipher::kw::{KW, KWP, KW_SEMIBLOCK_SIZE};
+pub use crate::cipher::chacha::{ChaCha20, ChaCha20Legacy, XChaCha20, hchacha20, CHACHA_KEY_SIZE,
+    CHACHA_BLOCKSIZE, CHACHA_NONCE_SIZE, CHACHA_LEGACY_NONCE_SIZE, XCHACHA_NONCE_SIZE, HCHACHA_NONCE_SIZE};
+pub use crate::cipher::chacha20_poly1305::{ChaCha20Poly1305, XChaCha20Poly1305, ChaCha20Poly1305Ctx, CHACHA20_POLY1305_TAG_SIZE};
+pub use crate::cipher::salsa::{Salsa20, XSalsa20, hsalsa20, SALSA_KEY_SIZE, SALSA_BLOCKSIZE, SALSA_NONCE_SIZE,
+    XSALSA_NONCE_SIZE, HSALSA_NONCE_SIZE};
+pub use crate::cipher::secretbox::{SecretBox, SECRETBOX_KEY_SIZE, SECRETBOX_NONCE_SIZE, SECRETBOX_MAC_SIZE};
+pub use crate::cipher::stream::{STREAMEncryptor, STREAMDecryptor, STREAM_NONCE_OVERHEAD};
+pub use crate::cipher::ghash::{GHASH, GHASH_BLOCKSIZE};
+pub use crate::cipher::polyval::{POLYVAL, POLYVAL_BLOCKSIZE};
@@ -0,0 +1,78 @@
+use crate::cipher::AES_BLOCKSIZE;
+use crate::cipher::modes::{BlockOperations, Direction, ModeDataCtx, Padding};
+use crate::common::api::{BlockCipher, CipherStreamingAPI, IVInit, InputOutputUpdate, OutputFinish};
+use crate::common::{Success, Error};
+
+/// Cipher Block Chaining mode.
+#[derive(Debug, Clone)]
+pub struct CBC<C: BlockCipher<BLOCKSIZE>, const BLOCKSIZE: usize = AES_BLOCKSIZE> {
+    data: ModeDataCtx<BLOCKSIZE>,
+    cipher: C,
+    direction: Direction,
+    padding: Padding,
+}
+
+impl<C: BlockCipher<BLOCKSIZE>, const BLOCKSIZE: usize> CBC<C, BLOCKSIZE> {
+    pub fn new(key: &[u8], direction: Direction, padding: Padding) -> Result<Self, Error> {
+        Ok(CBC {
+            data: ModeDataCtx::new(),
+            cipher: C::new(key)?,
+            direction,
+            padding,
+        })
+    }
+}
+
+impl<C: BlockCipher<BLOCKSIZE>, const BLOCKSIZE: usize> BlockOperations<BLOCKSIZE> for CBC<C, BLOCKSIZE> {
+    fn _data(&mut self) -> &mut ModeDataCtx<BLOCKSIZE> {
+        &mut self.data
+    }
+
+    fn _direction(&self) -> Direction {
+        self.direction
+    }
+
+    fn _padding(&self) -> Padding {
+        self.padding
+    }
+
+    fn _transform(&mut self, block: &mut [u8; BLOCKSIZE]) {
+        match self.direction {
+            Direction::Encrypt => {
+                for (b, v) in block.iter_mut().zip(self.data.iv.iter()) {
+                    *b ^= v;
+                }
+                self.cipher.encrypt_block(block);
+                self.data.iv = *block;
+            },
+            Direction::Decrypt => {
+                let c = *block;
+                self.cipher.decrypt_block(block);
+                for (b, v) in block.iter_mut().zip(self.data.iv.iter()) {
+                    *b ^= v;
+                }
+                self.data.iv = c;
+            },
+        }
+    }
+}
+
+impl<C: BlockCipher<BLOCKSIZE>, const BLOCKSIZE: usize> CipherStreamingAPI for CBC<C, BLOCKSIZE> {}
+
+impl<C: BlockCipher<BLOCKSIZE>, const BLOCKSIZE: usize> IVInit for CBC<C, BLOCKSIZE> {
+    fn init(&mut self, iv: &[u8]) -> Result<Success, Error> {
+        self.data._init(iv, 0)
+    }
+}
+
+impl<C: BlockCipher<BLOCKSIZE>, const BLOCKSIZE: usize> InputOutputUpdate for CBC<C, BLOCKSIZE> {
+    fn update(&mut self, input: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        self._process(input, output)
+    }
+}
+
+impl<C: BlockCipher<BLOCKSIZE>, const BLOCKSIZE: usize> OutputFinish for CBC<C, BLOCKSIZE> {
+    fn finish(&mut self, output: &mut [u8]) -> Result<usize, Error> {
+        self._finish(output)
+    }
+}
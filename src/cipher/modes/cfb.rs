@@ -0,0 +1,127 @@
+use crate::cipher::AES_BLOCKSIZE;
+use crate::cipher::modes::{Direction, ModeDataCtx, StreamOperations};
+use crate::common::api::{BlockCipher, CipherStreamingAPI, IVInit, InputOutputUpdate, OutputFinish};
+use crate::common::{Success, Error};
+
+/// Cipher Feedback mode with 8-bit segments.
+#[derive(Debug, Clone)]
+pub struct CFB8<C: BlockCipher<BLOCKSIZE>, const BLOCKSIZE: usize = AES_BLOCKSIZE> {
+    data: ModeDataCtx<BLOCKSIZE>,
+    cipher: C,
+    direction: Direction,
+}
+
+/// Cipher Feedback mode with segments of a full block.
+#[derive(Debug, Clone)]
+pub struct CFB128<C: BlockCipher<BLOCKSIZE>, const BLOCKSIZE: usize = AES_BLOCKSIZE> {
+    data: ModeDataCtx<BLOCKSIZE>,
+    cipher: C,
+    direction: Direction,
+}
+
+impl<C: BlockCipher<BLOCKSIZE>, const BLOCKSIZE: usize> CFB8<C, BLOCKSIZE> {
+    pub fn new(key: &[u8], direction: Direction) -> Result<Self, Error> {
+        Ok(CFB8 {
+            data: ModeDataCtx::new(),
+            cipher: C::new(key)?,
+            direction,
+        })
+    }
+}
+
+impl<C: BlockCipher<BLOCKSIZE>, const BLOCKSIZE: usize> CFB128<C, BLOCKSIZE> {
+    pub fn new(key: &[u8], direction: Direction) -> Result<Self, Error> {
+        Ok(CFB128 {
+            data: ModeDataCtx::new(),
+            cipher: C::new(key)?,
+            direction,
+        })
+    }
+}
+
+impl<C: BlockCipher<BLOCKSIZE>, const BLOCKSIZE: usize> StreamOperations<BLOCKSIZE> for CFB8<C, BLOCKSIZE> {
+    fn _data(&mut self) -> &mut ModeDataCtx<BLOCKSIZE> {
+        &mut self.data
+    }
+
+    /* The shift register is encrypted for every byte, the ciphertext byte is shifted in. */
+    fn _process_byte(&mut self, input: u8) -> u8 {
+        let mut ks = self.data.iv;
+        self.cipher.encrypt_block(&mut ks);
+        let output = input ^ ks[0];
+
+        self.data.iv.rotate_left(1);
+        self.data.iv[BLOCKSIZE - 1] = match self.direction {
+            Direction::Encrypt => output,
+            Direction::Decrypt => input,
+        };
+
+        output
+    }
+}
+
+impl<C: BlockCipher<BLOCKSIZE>, const BLOCKSIZE: usize> StreamOperations<BLOCKSIZE> for CFB128<C, BLOCKSIZE> {
+    fn _data(&mut self) -> &mut ModeDataCtx<BLOCKSIZE> {
+        &mut self.data
+    }
+
+    /* The ciphertext bytes overwrite the register as they are produced, so once a
+     * block is complete the register holds the ciphertext block to encrypt next. */
+    fn _process_byte(&mut self, input: u8) -> u8 {
+        if self.data.rem_pos == BLOCKSIZE {
+            self.data.buffer = self.data.iv;
+            self.cipher.encrypt_block(&mut self.data.buffer);
+            self.data.rem_pos = 0;
+        }
+
+        let pos = self.data.rem_pos;
+        let output = input ^ self.data.buffer[pos];
+        self.data.iv[pos] = match self.direction {
+            Direction::Encrypt => output,
+            Direction::Decrypt => input,
+        };
+        self.data.rem_pos += 1;
+
+        output
+    }
+}
+
+impl<C: BlockCipher<BLOCKSIZE>, const BLOCKSIZE: usize> CipherStreamingAPI for CFB8<C, BLOCKSIZE> {}
+
+impl<C: BlockCipher<BLOCKSIZE>, const BLOCKSIZE: usize> IVInit for CFB8<C, BLOCKSIZE> {
+    fn init(&mut self, iv: &[u8]) -> Result<Success, Error> {
+        self.data._init(iv, 0)
+    }
+}
+
+impl<C: BlockCipher<BLOCKSIZE>, const BLOCKSIZE: usize> InputOutputUpdate for CFB8<C, BLOCKSIZE> {
+    fn update(&mut self, input: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        self._process(input, output)
+    }
+}
+
+impl<C: BlockCipher<BLOCKSIZE>, const BLOCKSIZE: usize> OutputFinish for CFB8<C, BLOCKSIZE> {
+    fn finish(&mut self, _output: &mut [u8]) -> Result<usize, Error> {
+        self._finish()
+    }
+}
+
+impl<C: BlockCipher<BLOCKSIZE>, const BLOCKSIZE: usize> CipherStreamingAPI for CFB128<C, BLOCKSIZE> {}
+
+impl<C: BlockCipher<BLOCKSIZE>, const BLOCKSIZE: usize> IVInit for CFB128<C, BLOCKSIZE> {
+    fn init(&mut self, iv: &[u8]) -> Result<Success, Error> {
+        self.data._init(iv, BLOCKSIZE)
+    }
+}
+
+impl<C: BlockCipher<BLOCKSIZE>, const BLOCKSIZE: usize> InputOutputUpdate for CFB128<C, BLOCKSIZE> {
+    fn update(&mut self, input: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        self._process(input, output)
+    }
+}
+
+impl<C: BlockCipher<BLOCKSIZE>, const BLOCKSIZE: usize> OutputFinish for CFB128<C, BLOCKSIZE> {
+    fn finish(&mut self, _output: &mut [u8]) -> Result<usize, Error> {
+        self._finish()
+    }
+}
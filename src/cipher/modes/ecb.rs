@@ -0,0 +1,69 @@
+use crate::cipher::AES_BLOCKSIZE;
+use crate::cipher::modes::{BlockOperations, Direction, ModeDataCtx, Padding};
+use crate::common::api::{BlockCipher, CipherStreamingAPI, IVInit, InputOutputUpdate, OutputFinish};
+use crate::common::{Success, Error};
+
+/// Electronic Codebook mode. `init` takes an empty IV.
+#[derive(Debug, Clone)]
+pub struct ECB<C: BlockCipher<BLOCKSIZE>, const BLOCKSIZE: usize = AES_BLOCKSIZE> {
+    data: ModeDataCtx<BLOCKSIZE>,
+    cipher: C,
+    direction: Direction,
+    padding: Padding,
+}
+
+impl<C: BlockCipher<BLOCKSIZE>, const BLOCKSIZE: usize> ECB<C, BLOCKSIZE> {
+    pub fn new(key: &[u8], direction: Direction, padding: Padding) -> Result<Self, Error> {
+        Ok(ECB {
+            data: ModeDataCtx::new(),
+            cipher: C::new(key)?,
+            direction,
+            padding,
+        })
+    }
+}
+
+impl<C: BlockCipher<BLOCKSIZE>, const BLOCKSIZE: usize> BlockOperations<BLOCKSIZE> for ECB<C, BLOCKSIZE> {
+    fn _data(&mut self) -> &mut ModeDataCtx<BLOCKSIZE> {
+        &mut self.data
+    }
+
+    fn _direction(&self) -> Direction {
+        self.direction
+    }
+
+    fn _padding(&self) -> Padding {
+        self.padding
+    }
+
+    fn _transform(&mut self, block: &mut [u8; BLOCKSIZE]) {
+        match self.direction {
+            Direction::Encrypt => self.cipher.encrypt_block(block),
+            Direction::Decrypt => self.cipher.decrypt_block(block),
+        }
+    }
+}
+
+impl<C: BlockCipher<BLOCKSIZE>, const BLOCKSIZE: usize> CipherStreamingAPI for ECB<C, BLOCKSIZE> {}
+
+impl<C: BlockCipher<BLOCKSIZE>, const BLOCKSIZE: usize> IVInit for ECB<C, BLOCKSIZE> {
+    fn init(&mut self, iv: &[u8]) -> Result<Success, Error> {
+        if !iv.is_empty() {
+            return Err(Error::Err);
+        }
+
+        self.data._init(&[0; BLOCKSIZE], 0)
+    }
+}
+
+impl<C: BlockCipher<BLOCKSIZE>, const BLOCKSIZE: usize> InputOutputUpdate for ECB<C, BLOCKSIZE> {
+    fn update(&mut self, input: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        self._process(input, output)
+    }
+}
+
+impl<C: BlockCipher<BLOCKSIZE>, const BLOCKSIZE: usize> OutputFinish for ECB<C, BLOCKSIZE> {
+    fn finish(&mut self, output: &mut [u8]) -> Result<usize, Error> {
+        self._finish(output)
+    }
+}
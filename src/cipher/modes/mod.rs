@@ -0,0 +1,208 @@
+mod ecb;
+mod cbc;
+mod cfb;
+mod ofb;
+mod ctr;
+
+pub use crate::cipher::modes::ecb::ECB;
+pub use crate::cipher::modes::cbc::CBC;
+pub use crate::cipher::modes::cfb::{CFB8, CFB128};
+pub use crate::cipher::modes::ofb::OFB;
+pub use crate::cipher::modes::ctr::CTR;
+
+use crate::common::{Success, Error};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction {
+    Encrypt,
+    Decrypt,
+}
+
+/// Padding of the block modes ECB and CBC. Without padding the message has to be a
+/// multiple of the block size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Padding {
+    None,
+    PKCS7,
+}
+
+/// State shared by the modes of operation (SP 800-38A).
+///
+/// For the block modes `buffer` collects input until a full block is available,
+/// for the stream modes it holds the current keystream block. `iv` is the chaining
+/// value, shift register or counter block.
+#[derive(Debug, Clone)]
+pub struct ModeDataCtx<const BLOCKSIZE: usize> {
+    buffer: [u8; BLOCKSIZE],
+    rem_pos: usize,
+    iv: [u8; BLOCKSIZE],
+    initialized: bool,
+}
+
+impl<const BLOCKSIZE: usize> ModeDataCtx<BLOCKSIZE> {
+    fn new() -> Self {
+        ModeDataCtx {
+            buffer: [0; BLOCKSIZE],
+            rem_pos: 0,
+            iv: [0; BLOCKSIZE],
+            initialized: false,
+        }
+    }
+
+    fn _init(&mut self, iv: &[u8], rem_pos: usize) -> Result<Success, Error> {
+        if iv.len() != BLOCKSIZE {
+            return Err(Error::Err);
+        }
+
+        self.iv.copy_from_slice(iv);
+        self.buffer.fill(0);
+        self.rem_pos = rem_pos;
+        self.initialized = true;
+
+        Ok(Success::OK)
+    }
+
+    fn _clear(&mut self) {
+        self.buffer.fill(0);
+        self.iv.fill(0);
+        self.rem_pos = 0;
+        self.initialized = false;
+    }
+}
+
+/* ECB and CBC: input is processed in full blocks. */
+trait BlockOperations<const BLOCKSIZE: usize> {
+    fn _data(&mut self) -> &mut ModeDataCtx<BLOCKSIZE>;
+
+    fn _direction(&self) -> Direction;
+
+    fn _padding(&self) -> Padding;
+
+    /* En- or decrypts one block and updates the chaining value. */
+    fn _transform(&mut self, block: &mut [u8; BLOCKSIZE]);
+
+    fn _process(&mut self, mut input: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        /* With padding the last block can only be decrypted in finish, so a full
+         * buffered block is held back until more input arrives. */
+        let hold_back = self._direction() == Direction::Decrypt && self._padding() == Padding::PKCS7;
+        let data = self._data();
+        if !data.initialized {
+            return Err(Error::Err);
+        }
+
+        let total = data.rem_pos + input.len();
+        let mut nblocks = total / BLOCKSIZE;
+        if hold_back && nblocks > 0 && total.is_multiple_of(BLOCKSIZE) {
+            nblocks -= 1;
+        }
+        if output.len() < nblocks * BLOCKSIZE {
+            return Err(Error::Err);
+        }
+
+        for out in output[..nblocks * BLOCKSIZE].chunks_mut(BLOCKSIZE) {
+            let data = self._data();
+            let take = BLOCKSIZE - data.rem_pos;
+            data.buffer[data.rem_pos..].copy_from_slice(&input[..take]);
+            input = &input[take..];
+            data.rem_pos = 0;
+
+            let mut block = data.buffer;
+            self._transform(&mut block);
+            out.copy_from_slice(&block);
+        }
+
+        let data = self._data();
+        data.buffer[data.rem_pos..data.rem_pos + input.len()].copy_from_slice(input);
+        data.rem_pos += input.len();
+
+        Ok(nblocks * BLOCKSIZE)
+    }
+
+    fn _finish(&mut self, output: &mut [u8]) -> Result<usize, Error> {
+        let (direction, padding) = (self._direction(), self._padding());
+        let data = self._data();
+        if !data.initialized {
+            return Err(Error::Err);
+        }
+
+        let mut block = data.buffer;
+        let rem_pos = data.rem_pos;
+
+        let ret = match (direction, padding) {
+            (_, Padding::None) if rem_pos != 0 => Err(Error::Err),
+            (_, Padding::None) => Ok(0),
+            (Direction::Encrypt, Padding::PKCS7) if output.len() < BLOCKSIZE => return Err(Error::Err),
+            (Direction::Encrypt, Padding::PKCS7) => {
+                block[rem_pos..].fill((BLOCKSIZE - rem_pos) as u8);
+                self._transform(&mut block);
+                output[..BLOCKSIZE].copy_from_slice(&block);
+                Ok(BLOCKSIZE)
+            },
+            (Direction::Decrypt, Padding::PKCS7) if rem_pos != BLOCKSIZE => Err(Error::Err),
+            (Direction::Decrypt, Padding::PKCS7) => {
+                self._transform(&mut block);
+                _pkcs7_unpad(&block, output)
+            },
+        };
+
+        block.fill(0);
+        self._data()._clear();
+
+        ret
+    }
+}
+
+/* Checks the padding of the last block without branching on its content and copies
+ * the message bytes to `output`. */
+fn _pkcs7_unpad<const BLOCKSIZE: usize>(block: &[u8; BLOCKSIZE], output: &mut [u8]) -> Result<usize, Error> {
+    let pad = block[BLOCKSIZE - 1];
+    let mut bad = (pad == 0) as u8 | (pad as usize > BLOCKSIZE) as u8;
+
+    for (i, b) in block.iter().rev().enumerate() {
+        /* 0xff for the bytes that are part of the padding. */
+        let in_pad = ((i as u8).wrapping_sub(pad) >> 7).wrapping_neg();
+        bad |= in_pad & (b ^ pad);
+    }
+
+    if bad != 0 {
+        return Err(Error::Err);
+    }
+
+    let len = BLOCKSIZE - pad as usize;
+    if output.len() < len {
+        return Err(Error::Err);
+    }
+    output[..len].copy_from_slice(&block[..len]);
+
+    Ok(len)
+}
+
+/* CFB, OFB and CTR: the cipher produces a keystream and every byte of input gives
+ * one byte of output. */
+trait StreamOperations<const BLOCKSIZE: usize> {
+    fn _data(&mut self) -> &mut ModeDataCtx<BLOCKSIZE>;
+
+    fn _process_byte(&mut self, input: u8) -> u8;
+
+    fn _process(&mut self, input: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        if !self._data().initialized || output.len() < input.len() {
+            return Err(Error::Err);
+        }
+
+        for (o, i) in output.iter_mut().zip(input.iter()) {
+            *o = self._process_byte(*i);
+        }
+
+        Ok(input.len())
+    }
+
+    fn _finish(&mut self) -> Result<usize, Error> {
+        let data = self._data();
+        if !data.initialized {
+            return Err(Error::Err);
+        }
+        data._clear();
+
+        Ok(0)
+    }
+}
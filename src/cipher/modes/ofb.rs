@@ -0,0 +1,59 @@
+use crate::cipher::AES_BLOCKSIZE;
+use crate::cipher::modes::{ModeDataCtx, StreamOperations};
+use crate::common::api::{BlockCipher, CipherStreamingAPI, IVInit, InputOutputUpdate, OutputFinish};
+use crate::common::{Success, Error};
+
+/// Output Feedback mode. Encryption and decryption are the same operation.
+#[derive(Debug, Clone)]
+pub struct OFB<C: BlockCipher<BLOCKSIZE>, const BLOCKSIZE: usize = AES_BLOCKSIZE> {
+    data: ModeDataCtx<BLOCKSIZE>,
+    cipher: C,
+}
+
+impl<C: BlockCipher<BLOCKSIZE>, const BLOCKSIZE: usize> OFB<C, BLOCKSIZE> {
+    pub fn new(key: &[u8]) -> Result<Self, Error> {
+        Ok(OFB {
+            data: ModeDataCtx::new(),
+            cipher: C::new(key)?,
+        })
+    }
+}
+
+impl<C: BlockCipher<BLOCKSIZE>, const BLOCKSIZE: usize> StreamOperations<BLOCKSIZE> for OFB<C, BLOCKSIZE> {
+    fn _data(&mut self) -> &mut ModeDataCtx<BLOCKSIZE> {
+        &mut self.data
+    }
+
+    fn _process_byte(&mut self, input: u8) -> u8 {
+        if self.data.rem_pos == BLOCKSIZE {
+            self.cipher.encrypt_block(&mut self.data.iv);
+            self.data.buffer = self.data.iv;
+            self.data.rem_pos = 0;
+        }
+
+        let output = input ^ self.data.buffer[self.data.rem_pos];
+        self.data.rem_pos += 1;
+
+        output
+    }
+}
+
+impl<C: BlockCipher<BLOCKSIZE>, const BLOCKSIZE: usize> CipherStreamingAPI for OFB<C, BLOCKSIZE> {}
+
+impl<C: BlockCipher<BLOCKSIZE>, const BLOCKSIZE: usize> IVInit for OFB<C, BLOCKSIZE> {
+    fn init(&mut self, iv: &[u8]) -> Result<Success, Error> {
+        self.data._init(iv, BLOCKSIZE)
+    }
+}
+
+impl<C: BlockCipher<BLOCKSIZE>, const BLOCKSIZE: usize> InputOutputUpdate for OFB<C, BLOCKSIZE> {
+    fn update(&mut self, input: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        self._process(input, output)
+    }
+}
+
+impl<C: BlockCipher<BLOCKSIZE>, const BLOCKSIZE: usize> OutputFinish for OFB<C, BLOCKSIZE> {
+    fn finish(&mut self, _output: &mut [u8]) -> Result<usize, Error> {
+        self._finish()
+    }
+}
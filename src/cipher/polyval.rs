@@ -0,0 +1,75 @@
+/* POLYVAL (RFC 8452, section 3) computed with GHASH, following appendix A:
+ *
+ *   POLYVAL(H, X_1, ..., X_n) =
+ *       ByteReverse(GHASH(mulX_GHASH(ByteReverse(H)), ByteReverse(X_1), ..., ByteReverse(X_n)))
+ */
+
+use crate::cipher::ghash::{GHASH, GHASH_BLOCKSIZE};
+
+pub const POLYVAL_BLOCKSIZE: usize = 16;
+
+/// The universal hash of AES-GCM-SIV, keyed with the message-authentication key.
+#[derive(Debug, Clone)]
+pub struct POLYVAL {
+    ghash: GHASH,
+    buffer: [u8; POLYVAL_BLOCKSIZE],
+    rem_pos: usize,
+}
+
+impl POLYVAL {
+    pub fn new(h: &[u8; POLYVAL_BLOCKSIZE]) -> Self {
+        let mut hr = *h;
+        hr.reverse();
+
+        POLYVAL {
+            ghash: GHASH::new(&_mulx_ghash(&hr)),
+            buffer: [0; POLYVAL_BLOCKSIZE],
+            rem_pos: 0,
+        }
+    }
+
+    /// Absorbs `input`, buffering a partial block.
+    pub fn update(&mut self, mut input: &[u8]) {
+        while !input.is_empty() {
+            let take = usize::min(POLYVAL_BLOCKSIZE - self.rem_pos, input.len());
+            self.buffer[self.rem_pos..self.rem_pos + take].copy_from_slice(&input[..take]);
+            self.rem_pos += take;
+            input = &input[take..];
+
+            if self.rem_pos == POLYVAL_BLOCKSIZE {
+                self._process_buffer();
+            }
+        }
+    }
+
+    /// Completes a partial block with zeros.
+    pub fn pad(&mut self) {
+        if self.rem_pos > 0 {
+            self.buffer[self.rem_pos..].fill(0);
+            self._process_buffer();
+        }
+    }
+
+    /// Writes the hash value and resets the state, the key is kept.
+    pub fn finish(&mut self, output: &mut [u8; POLYVAL_BLOCKSIZE]) {
+        self.pad();
+        self.ghash.finish(output);
+        output.reverse();
+    }
+
+    fn _process_buffer(&mut self) {
+        self.buffer.reverse();
+        self.ghash.update(&self.buffer);
+        self.buffer.fill(0);
+        self.rem_pos = 0;
+    }
+}
+
+/* Multiplication by x in GHASH's reflected bit order: a right shift, reduced by
+ * x^128 + x^7 + x^2 + x + 1 without branching on the key. */
+fn _mulx_ghash(block: &[u8; GHASH_BLOCKSIZE]) -> [u8; GHASH_BLOCKSIZE] {
+    let v = u128::from_be_bytes(*block);
+    let reduce = (v & 1).wrapping_neg() & (0xe1 << 120);
+
+    ((v >> 1) ^ reduce).to_be_bytes()
+}
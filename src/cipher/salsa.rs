@@ -0,0 +1,224 @@
+use crate::common::api::{CipherStreamingAPI, IVInit, InputOutputUpdate, OutputFinish};
+use crate::common::{Success, Error};
+use crate::cipher::keystream::Keystream;
+
+pub const SALSA_KEY_SIZE: usize = 32;
+pub const SALSA_BLOCKSIZE: usize = 64;
+pub const SALSA_NONCE_SIZE: usize = 8;
+pub const XSALSA_NONCE_SIZE: usize = 24;
+pub const HSALSA_NONCE_SIZE: usize = 16;
+
+/* "expand 32-byte k", on the diagonal of the state. */
+const SIGMA: [u32; 4] = [0x61707865, 0x3320646e, 0x79622d32, 0x6b206574];
+
+/// Salsa20/20 with a 256-bit key, a 64-bit nonce and a 64-bit block counter.
+///
+/// The key is set on construction, `init` takes the nonce and starts at block 0 and
+/// `seek` moves to any byte position of the keystream. Encryption and decryption
+/// are the same operation.
+#[derive(Debug, Clone)]
+pub struct Salsa20 {
+    ctx: SalsaCtx,
+}
+
+/// XSalsa20: Salsa20 under the HSalsa20 subkey of the key and the first 16 bytes of
+/// a 192-bit nonce, with the last 8 bytes as nonce.
+#[derive(Debug, Clone)]
+pub struct XSalsa20 {
+    key: [u8; SALSA_KEY_SIZE],
+    ctx: SalsaCtx,
+}
+
+/* The state holds the constants in words 0, 5, 10 and 15, the key in words 1-4 and
+ * 11-14, the nonce in words 6 and 7 and the counter of the next block, kept by
+ * `keystream`, in words 8 and 9. */
+#[derive(Debug, Clone)]
+struct SalsaCtx {
+    state: [u32; 16],
+    keystream: Keystream,
+}
+
+impl SalsaCtx {
+    fn new(key: &[u8; SALSA_KEY_SIZE]) -> Self {
+        let mut ctx = SalsaCtx {
+            state: [0; 16],
+            keystream: Keystream::new(1 << 64),
+        };
+        ctx._set_key(key);
+
+        ctx
+    }
+
+    fn _set_key(&mut self, key: &[u8; SALSA_KEY_SIZE]) {
+        for (i, c) in SIGMA.iter().enumerate() {
+            self.state[5 * i] = *c;
+        }
+        let w = |i: usize| u32::from_le_bytes(key[4 * i..4 * i + 4].try_into().unwrap());
+        for i in 0..4 {
+            self.state[1 + i] = w(i);
+            self.state[11 + i] = w(4 + i);
+        }
+    }
+
+    /* Words 6 to 9 from 8 bytes of nonce or, for HSalsa20, 16 bytes of input. */
+    fn _init(&mut self, nonce: &[u8]) {
+        for (w, n) in self.state[6..].iter_mut().zip(nonce.chunks_exact(4)) {
+            *w = u32::from_le_bytes(n.try_into().unwrap());
+        }
+        self.keystream._init();
+    }
+
+    fn _seek(&mut self, pos: u64) -> Result<Success, Error> {
+        self.keystream._seek(pos, |counter, buffer| _block(&mut self.state, counter, buffer))
+    }
+
+    fn _process(&mut self, input: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        self.keystream._process(input, output, |counter, buffer| _block(&mut self.state, counter, buffer))
+    }
+
+    fn _finish(&mut self) -> Result<usize, Error> {
+        let ret = self.keystream._finish()?;
+        self.state[6..10].fill(0);
+
+        Ok(ret)
+    }
+}
+
+/* Keystream block `counter`. */
+fn _block(state: &mut [u32; 16], counter: u128, buffer: &mut [u8; SALSA_BLOCKSIZE]) {
+    state[8] = counter as u32;
+    state[9] = (counter >> 32) as u32;
+
+    let mut x = _rounds(state);
+    for (i, (w, s)) in x.iter_mut().zip(state.iter()).enumerate() {
+        *w = w.wrapping_add(*s);
+        buffer[4 * i..4 * i + 4].copy_from_slice(&w.to_le_bytes());
+    }
+    x.fill(0);
+}
+
+fn _quarter_round(x: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    x[b] ^= x[a].wrapping_add(x[d]).rotate_left(7);
+    x[c] ^= x[b].wrapping_add(x[a]).rotate_left(9);
+    x[d] ^= x[c].wrapping_add(x[b]).rotate_left(13);
+    x[a] ^= x[d].wrapping_add(x[c]).rotate_left(18);
+}
+
+/* The 20 rounds, alternating column and row rounds, without the final addition. */
+fn _rounds(state: &[u32; 16]) -> [u32; 16] {
+    let mut x = *state;
+    for _ in 0..10 {
+        _quarter_round(&mut x, 0, 4, 8, 12);
+        _quarter_round(&mut x, 5, 9, 13, 1);
+        _quarter_round(&mut x, 10, 14, 2, 6);
+        _quarter_round(&mut x, 15, 3, 7, 11);
+        _quarter_round(&mut x, 0, 1, 2, 3);
+        _quarter_round(&mut x, 5, 6, 7, 4);
+        _quarter_round(&mut x, 10, 11, 8, 9);
+        _quarter_round(&mut x, 15, 12, 13, 14);
+    }
+
+    x
+}
+
+/// HSalsa20: derives a 256-bit subkey from the key and a 128-bit input, the words
+/// 0, 5, 10, 15, 6, 7, 8 and 9 of the state after the rounds.
+pub fn hsalsa20(key: &[u8; SALSA_KEY_SIZE], nonce: &[u8; HSALSA_NONCE_SIZE]) -> [u8; SALSA_KEY_SIZE] {
+    let mut ctx = SalsaCtx::new(key);
+    ctx._init(nonce);
+
+    let mut x = _rounds(&ctx.state);
+    let mut subkey = [0u8; SALSA_KEY_SIZE];
+    for (out, i) in subkey.chunks_exact_mut(4).zip([0, 5, 10, 15, 6, 7, 8, 9]) {
+        out.copy_from_slice(&x[i].to_le_bytes());
+    }
+    x.fill(0);
+    ctx.state.fill(0);
+
+    subkey
+}
+
+fn _key(key: &[u8]) -> Result<&[u8; SALSA_KEY_SIZE], Error> {
+    key.try_into().map_err(|_| Error::Err)
+}
+
+impl Salsa20 {
+    pub fn new(key: &[u8]) -> Result<Self, Error> {
+        Ok(Salsa20 { ctx: SalsaCtx::new(_key(key)?) })
+    }
+
+    /// Moves to byte `pos` of the keystream for the current nonce.
+    pub fn seek(&mut self, pos: u64) -> Result<Success, Error> {
+        self.ctx._seek(pos)
+    }
+}
+
+impl XSalsa20 {
+    pub fn new(key: &[u8]) -> Result<Self, Error> {
+        let key = *_key(key)?;
+        Ok(XSalsa20 { key, ctx: SalsaCtx::new(&[0; SALSA_KEY_SIZE]) })
+    }
+
+    /// Moves to byte `pos` of the keystream for the current nonce.
+    pub fn seek(&mut self, pos: u64) -> Result<Success, Error> {
+        self.ctx._seek(pos)
+    }
+}
+
+impl CipherStreamingAPI for Salsa20 {}
+
+impl IVInit for Salsa20 {
+    fn init(&mut self, iv: &[u8]) -> Result<Success, Error> {
+        if iv.len() != SALSA_NONCE_SIZE {
+            return Err(Error::Err);
+        }
+
+        self.ctx._init(iv);
+        Ok(Success::OK)
+    }
+}
+
+impl InputOutputUpdate for Salsa20 {
+    fn update(&mut self, input: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        self.ctx._process(input, output)
+    }
+}
+
+impl OutputFinish for Salsa20 {
+    fn finish(&mut self, _output: &mut [u8]) -> Result<usize, Error> {
+        self.ctx._finish()
+    }
+}
+
+impl CipherStreamingAPI for XSalsa20 {}
+
+impl IVInit for XSalsa20 {
+    fn init(&mut self, iv: &[u8]) -> Result<Success, Error> {
+        if iv.len() != XSALSA_NONCE_SIZE {
+            return Err(Error::Err);
+        }
+
+        let (n1, n2) = iv.split_at(HSALSA_NONCE_SIZE);
+        let mut subkey = hsalsa20(&self.key, n1.try_into().unwrap());
+        self.ctx._set_key(&subkey);
+        subkey.fill(0);
+        self.ctx._init(n2);
+
+        Ok(Success::OK)
+    }
+}
+
+impl InputOutputUpdate for XSalsa20 {
+    fn update(&mut self, input: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        self.ctx._process(input, output)
+    }
+}
+
+impl OutputFinish for XSalsa20 {
+    fn finish(&mut self, _output: &mut [u8]) -> Result<usize, Error> {
+        let ret = self.ctx._finish();
+        self.ctx._set_key(&[0; SALSA_KEY_SIZE]);
+
+        ret
+    }
+}
@@ -0,0 +1,140 @@
+use crate::cipher::salsa::{XSalsa20, SALSA_BLOCKSIZE, SALSA_KEY_SIZE, XSALSA_NONCE_SIZE};
+use crate::common::api::{IVInit, InputOutputUpdate, OutputFinish, SingleInputUpdate, SingleOutputFinish};
+use crate::common::{Success, Error};
+use crate::mac::{Poly1305, POLY1305_KEY_SIZE, POLY1305_TAG_SIZE};
+use crate::utils::ct_eq;
+
+pub const SECRETBOX_KEY_SIZE: usize = SALSA_KEY_SIZE;
+pub const SECRETBOX_NONCE_SIZE: usize = XSALSA_NONCE_SIZE;
+pub const SECRETBOX_MAC_SIZE: usize = POLY1305_TAG_SIZE;
+
+/// NaCl `crypto_secretbox` (XSalsa20-Poly1305) with the byte layout of libsodium.
+///
+/// `seal` and `open` correspond to `crypto_secretbox_easy` and
+/// `crypto_secretbox_open_easy`: the box is the 16-byte MAC followed by the
+/// ciphertext. The `_detached` functions keep the MAC separate. The original NaCl
+/// API with 32 leading zero bytes in the message gives 16 zero bytes followed by
+/// the output of `seal`. Unlike the AEADs there is no associated data.
+///
+/// `open` checks the MAC before any plaintext is written and leaves the output
+/// untouched on failure.
+#[derive(Debug, Clone)]
+pub struct SecretBox {
+    cipher: XSalsa20,
+}
+
+impl SecretBox {
+    pub fn new(key: &[u8]) -> Result<Self, Error> {
+        Ok(SecretBox { cipher: XSalsa20::new(key)? })
+    }
+
+    /// Writes MAC || ciphertext to `output` and returns the number of bytes written.
+    pub fn seal(&self, nonce: &[u8], plaintext: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        let len = plaintext.len();
+        if output.len() < len + SECRETBOX_MAC_SIZE {
+            return Err(Error::Err);
+        }
+
+        let (mac, ciphertext) = output.split_at_mut(SECRETBOX_MAC_SIZE);
+        self.seal_detached(nonce, plaintext, &mut ciphertext[..len], mac)?;
+
+        Ok(len + SECRETBOX_MAC_SIZE)
+    }
+
+    /// Opens MAC || ciphertext and returns the length of the plaintext.
+    pub fn open(&self, nonce: &[u8], input: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        if input.len() < SECRETBOX_MAC_SIZE {
+            return Err(Error::Err);
+        }
+
+        let (mac, ciphertext) = input.split_at(SECRETBOX_MAC_SIZE);
+        self.open_detached(nonce, ciphertext, mac, output)?;
+
+        Ok(ciphertext.len())
+    }
+
+    pub fn seal_detached(&self, nonce: &[u8], plaintext: &[u8], ciphertext: &mut [u8], mac: &mut [u8]) -> Result<Success, Error> {
+        if ciphertext.len() < plaintext.len() {
+            return Err(Error::Err);
+        }
+
+        let ciphertext = &mut ciphertext[..plaintext.len()];
+        ciphertext.copy_from_slice(plaintext);
+        self.seal_in_place(nonce, ciphertext, mac)
+    }
+
+    pub fn open_detached(&self, nonce: &[u8], ciphertext: &[u8], mac: &[u8], plaintext: &mut [u8]) -> Result<Success, Error> {
+        if plaintext.len() < ciphertext.len() {
+            return Err(Error::Err);
+        }
+
+        let (mut cipher, mut poly) = self._start(nonce)?;
+        _verify(&mut poly, ciphertext, mac)?;
+        cipher.update(ciphertext, &mut plaintext[..ciphertext.len()])?;
+        cipher.finish(&mut [])?;
+
+        Ok(Success::OK)
+    }
+
+    /// Encrypts `buffer` in place and writes the MAC to `mac`.
+    pub fn seal_in_place(&self, nonce: &[u8], buffer: &mut [u8], mac: &mut [u8]) -> Result<Success, Error> {
+        if mac.len() != SECRETBOX_MAC_SIZE {
+            return Err(Error::Err);
+        }
+
+        let (mut cipher, mut poly) = self._start(nonce)?;
+        _apply(&mut cipher, buffer)?;
+        poly.update(buffer)?;
+        poly.finish(mac)
+    }
+
+    /// Decrypts `buffer` in place if `mac` is valid. On failure the buffer still
+    /// holds the ciphertext.
+    pub fn open_in_place(&self, nonce: &[u8], buffer: &mut [u8], mac: &[u8]) -> Result<Success, Error> {
+        let (mut cipher, mut poly) = self._start(nonce)?;
+        _verify(&mut poly, buffer, mac)?;
+        _apply(&mut cipher, buffer)
+    }
+
+    /* The first 32 bytes of the keystream are the Poly1305 key, the message is
+     * encrypted with the keystream that follows. */
+    fn _start(&self, nonce: &[u8]) -> Result<(XSalsa20, Poly1305), Error> {
+        let mut cipher = self.cipher.clone();
+        cipher.init(nonce)?;
+
+        let mut otk = [0u8; POLY1305_KEY_SIZE];
+        cipher.update(&[0; POLY1305_KEY_SIZE], &mut otk)?;
+        let poly = Poly1305::new(&otk)?;
+        otk.fill(0);
+
+        Ok((cipher, poly))
+    }
+}
+
+fn _apply(cipher: &mut XSalsa20, buffer: &mut [u8]) -> Result<Success, Error> {
+    let mut block = [0u8; SALSA_BLOCKSIZE];
+    for chunk in buffer.chunks_mut(SALSA_BLOCKSIZE) {
+        let ks = &mut block[..chunk.len()];
+        cipher.update(chunk, ks)?;
+        chunk.copy_from_slice(ks);
+    }
+    block.fill(0);
+    cipher.finish(&mut [])?;
+
+    Ok(Success::OK)
+}
+
+fn _verify(poly: &mut Poly1305, ciphertext: &[u8], mac: &[u8]) -> Result<Success, Error> {
+    if mac.len() != SECRETBOX_MAC_SIZE {
+        return Err(Error::Err);
+    }
+
+    let mut expected = [0u8; SECRETBOX_MAC_SIZE];
+    poly.update(ciphertext)?;
+    poly.finish(&mut expected)?;
+    if !ct_eq(&expected, mac) {
+        return Err(Error::AuthFailed);
+    }
+
+    Ok(Success::OK)
+}
@@ -0,0 +1,197 @@
+use crate::cipher::AES_BLOCKSIZE;
+use crate::common::api::{AEAD, BlockCipher, MAC};
+use crate::common::{Success, Error};
+use crate::mac::{dbl, CMAC};
+use crate::utils::ct_eq;
+
+pub const SIV_TAG_SIZE: usize = 16;
+
+/* S2V takes at most 127 strings, the last one is the plaintext (RFC 5297, section 7). */
+const SIV_MAX_AD: usize = 126;
+
+/// Synthetic Initialization Vector mode (RFC 5297), AES-SIV for `C` = AES.
+///
+/// S2V runs over the MAC `M` with 128-bit output, CMAC over `C` by default. For
+/// CMAC the key is twice the key length of `C`: the first half keys CMAC, the
+/// second half the counter mode. Another MAC is keyed by the caller and passed to
+/// `with_mac` together with the key of the counter mode. The synthetic IV V is
+/// output before the ciphertext. With the same associated data and plaintext the
+/// output is the same, which only reveals that a message was repeated.
+///
+/// Through the `AEAD` trait the associated data and the nonce are the two AD
+/// components; `encrypt_with_ad` takes any number of components, including none for
+/// deterministic encryption. If decryption fails the output is zeroed, the in-place
+/// functions of the trait restore the ciphertext. The detached tag is V.
+#[derive(Debug, Clone)]
+pub struct SIV<C: BlockCipher<AES_BLOCKSIZE> + Clone, M: MAC + Clone = CMAC<C>> {
+    mac: M,
+    cipher: C,
+}
+
+impl<C: BlockCipher<AES_BLOCKSIZE> + Clone, M: MAC + Clone> SIV<C, M> {
+    /// SIV with S2V over `mac`, which has to produce 128-bit MACs, and `key` for
+    /// the counter mode.
+    pub fn with_mac(mac: M, key: &[u8]) -> Result<Self, Error> {
+        if mac.mac_size() != AES_BLOCKSIZE {
+            return Err(Error::Err);
+        }
+
+        Ok(SIV { mac, cipher: C::new(key)? })
+    }
+
+    /// Encrypts `plaintext` to V || C and returns the number of bytes written.
+    pub fn encrypt_with_ad(&self, ad: &[&[u8]], plaintext: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        let len = plaintext.len();
+        if output.len() < len + SIV_TAG_SIZE {
+            return Err(Error::Err);
+        }
+
+        let v = _s2v(&mut self.mac.clone(), ad, plaintext)?;
+        let (iv, ciphertext) = output.split_at_mut(SIV_TAG_SIZE);
+        iv.copy_from_slice(&v);
+        ciphertext[..len].copy_from_slice(plaintext);
+        self._ctr(&v, &mut ciphertext[..len]);
+
+        Ok(len + SIV_TAG_SIZE)
+    }
+
+    /// Decrypts V || C and returns the length of the plaintext.
+    pub fn decrypt_with_ad(&self, ad: &[&[u8]], input: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        if input.len() < SIV_TAG_SIZE || output.len() < input.len() - SIV_TAG_SIZE || ad.len() > SIV_MAX_AD {
+            return Err(Error::Err);
+        }
+
+        let (v, ciphertext) = input.split_at(SIV_TAG_SIZE);
+        let v: &[u8; AES_BLOCKSIZE] = v.try_into().unwrap();
+        let plaintext = &mut output[..ciphertext.len()];
+        plaintext.copy_from_slice(ciphertext);
+        self._ctr(v, plaintext);
+
+        let expected = _s2v(&mut self.mac.clone(), ad, plaintext)?;
+        if !ct_eq(&expected, v) {
+            plaintext.fill(0);
+            return Err(Error::AuthFailed);
+        }
+
+        Ok(ciphertext.len())
+    }
+
+    /* Counter mode with Q = V & 1^64 0 1^31 0 1^31, incremented as a 128-bit integer. */
+    fn _ctr(&self, v: &[u8; AES_BLOCKSIZE], buffer: &mut [u8]) {
+        let mut q = *v;
+        q[8] &= 0x7f;
+        q[12] &= 0x7f;
+        let mut ctr = u128::from_be_bytes(q);
+
+        for chunk in buffer.chunks_mut(AES_BLOCKSIZE) {
+            let mut ks = ctr.to_be_bytes();
+            self.cipher.encrypt_block(&mut ks);
+            for (b, k) in chunk.iter_mut().zip(ks.iter()) {
+                *b ^= k;
+            }
+            ctr = ctr.wrapping_add(1);
+        }
+    }
+}
+
+/* S2V (section 2.4) over a MAC with 128-bit output: the MACs of the AD components
+ * are folded into D by doubling, the plaintext is xored onto the end of D or, if
+ * shorter than a block, padded and xored with dbl(D). */
+fn _s2v<M: MAC>(mac: &mut M, ad: &[&[u8]], plaintext: &[u8]) -> Result<[u8; AES_BLOCKSIZE], Error> {
+    if ad.len() > SIV_MAX_AD || mac.mac_size() != AES_BLOCKSIZE {
+        return Err(Error::Err);
+    }
+
+    let mut d = [0u8; AES_BLOCKSIZE];
+    let mut t = [0u8; AES_BLOCKSIZE];
+
+    mac.init()?;
+    mac.update(&[0; AES_BLOCKSIZE])?;
+    mac.finish(&mut d)?;
+
+    for s in ad {
+        mac.init()?;
+        mac.update(s)?;
+        mac.finish(&mut t)?;
+        d = dbl(&d);
+        for (a, b) in d.iter_mut().zip(t.iter()) {
+            *a ^= b;
+        }
+    }
+
+    mac.init()?;
+    if plaintext.len() >= AES_BLOCKSIZE {
+        let (head, last) = plaintext.split_at(plaintext.len() - AES_BLOCKSIZE);
+        for (a, b) in d.iter_mut().zip(last.iter()) {
+            *a ^= b;
+        }
+        mac.update(head)?;
+    } else {
+        d = dbl(&d);
+        for (a, b) in d.iter_mut().zip(plaintext.iter()) {
+            *a ^= b;
+        }
+        d[plaintext.len()] ^= 0x80;
+    }
+    mac.update(&d)?;
+    mac.finish(&mut t)?;
+    d.fill(0);
+
+    Ok(t)
+}
+
+impl<C: BlockCipher<AES_BLOCKSIZE> + Clone> AEAD for SIV<C> {
+    const KEY_SIZE: usize = 2 * C::KEY_SIZE;
+    const NONCE_SIZE: usize = 16;
+    const TAG_SIZE: usize = SIV_TAG_SIZE;
+
+    fn new(key: &[u8]) -> Result<Self, Error> {
+        if key.len() != 2 * C::KEY_SIZE {
+            return Err(Error::Err);
+        }
+
+        let (k1, k2) = key.split_at(C::KEY_SIZE);
+        SIV::with_mac(CMAC::new(k1)?, k2)
+    }
+
+    fn nonce_len(&self) -> usize {
+        Self::NONCE_SIZE
+    }
+
+    fn tag_len(&self) -> usize {
+        SIV_TAG_SIZE
+    }
+
+    fn seal_in_place(&self, nonce: &[u8], aad: &[u8], buffer: &mut [u8], tag: &mut [u8]) -> Result<Success, Error> {
+        if tag.len() != SIV_TAG_SIZE {
+            return Err(Error::Err);
+        }
+
+        let v = _s2v(&mut self.mac.clone(), &[aad, nonce], buffer)?;
+        self._ctr(&v, buffer);
+        tag.copy_from_slice(&v);
+
+        Ok(Success::OK)
+    }
+
+    fn open_in_place(&self, nonce: &[u8], aad: &[u8], buffer: &mut [u8], tag: &[u8]) -> Result<Success, Error> {
+        let v: &[u8; AES_BLOCKSIZE] = tag.try_into().map_err(|_| Error::Err)?;
+
+        self._ctr(v, buffer);
+        match _s2v(&mut self.mac.clone(), &[aad, nonce], buffer) {
+            Ok(expected) if ct_eq(&expected, v) => Ok(Success::OK),
+            result => {
+                self._ctr(v, buffer);
+                result.and(Err(Error::AuthFailed))
+            }
+        }
+    }
+
+    fn seal(&self, nonce: &[u8], aad: &[u8], plaintext: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        self.encrypt_with_ad(&[aad, nonce], plaintext, output)
+    }
+
+    fn open(&self, nonce: &[u8], aad: &[u8], input: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        self.decrypt_with_ad(&[aad, nonce], input, output)
+    }
+}
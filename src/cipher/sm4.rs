@@ -0,0 +1,132 @@
+use crate::common::api::BlockCipher;
+use crate::common::Error;
+
+pub const SM4_BLOCKSIZE: usize = 16;
+pub const SM4_KEY_SIZE: usize = 16;
+const SM4_ROUNDS: usize = 32;
+
+static FK: [u32; 4] = [0xa3b1bac6, 0x56aa3350, 0x677d9197, 0xb27022dc];
+
+/// SM4 (GB/T 32907-2016), the block cipher of the Chinese commercial cryptography
+/// standards, with a 128-bit key.
+///
+/// Like `AES` it does not use lookup tables: the S-box is computed as
+/// A(I(A(x) ^ 0xd3)) ^ 0xd3 with the affine map A and the inversion I in GF(2^8)
+/// modulo x^8 + x^7 + x^6 + x^5 + x^4 + x^2 + 1, so the timing does not depend on
+/// the key or the data.
+#[derive(Debug, Clone)]
+pub struct SM4 {
+    round_keys: [u32; SM4_ROUNDS],
+}
+
+impl SM4 {
+    fn _key_expansion(&mut self, key: &[u8]) {
+        let mut k = [0u32; 4];
+        for (i, word) in key.chunks(4).enumerate() {
+            k[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]) ^ FK[i];
+        }
+
+        for i in 0..SM4_ROUNDS {
+            let rk = k[i % 4] ^ _t_key(k[(i + 1) % 4] ^ k[(i + 2) % 4] ^ k[(i + 3) % 4] ^ _ck(i));
+            k[i % 4] = rk;
+            self.round_keys[i] = rk;
+        }
+        k.fill(0);
+    }
+
+    fn _crypt(&self, block: &mut [u8; SM4_BLOCKSIZE], decrypt: bool) {
+        let mut x = [0u32; 4];
+        for (i, word) in block.chunks(4).enumerate() {
+            x[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+
+        for i in 0..SM4_ROUNDS {
+            let rk = if decrypt { self.round_keys[SM4_ROUNDS - 1 - i] } else { self.round_keys[i] };
+            x[i % 4] ^= _t(x[(i + 1) % 4] ^ x[(i + 2) % 4] ^ x[(i + 3) % 4] ^ rk);
+        }
+
+        /* The output is the last four words in reverse order. */
+        for (i, word) in block.chunks_mut(4).enumerate() {
+            word.copy_from_slice(&x[3 - i].to_be_bytes());
+        }
+    }
+}
+
+impl BlockCipher<SM4_BLOCKSIZE> for SM4 {
+    const KEY_SIZE: usize = SM4_KEY_SIZE;
+
+    fn new(key: &[u8]) -> Result<Self, Error> {
+        if key.len() != SM4_KEY_SIZE {
+            return Err(Error::Err);
+        }
+
+        let mut sm4 = SM4 { round_keys: [0; SM4_ROUNDS] };
+        sm4._key_expansion(key);
+
+        Ok(sm4)
+    }
+
+    fn encrypt_block(&self, block: &mut [u8; SM4_BLOCKSIZE]) {
+        self._crypt(block, false);
+    }
+
+    fn decrypt_block(&self, block: &mut [u8; SM4_BLOCKSIZE]) {
+        self._crypt(block, true);
+    }
+}
+
+/* CK[i] has the bytes (4i + j) * 7 mod 256 for j = 0..3. */
+fn _ck(i: usize) -> u32 {
+    let b = |j: usize| ((4 * i + j) * 7 % 256) as u8;
+
+    u32::from_be_bytes([b(0), b(1), b(2), b(3)])
+}
+
+fn _tau(x: u32) -> u32 {
+    let b = x.to_be_bytes();
+
+    u32::from_be_bytes([_sbox(b[0]), _sbox(b[1]), _sbox(b[2]), _sbox(b[3])])
+}
+
+/* Round function T = L(tau(x)) of the encryption and T' = L'(tau(x)) of the key
+ * schedule. */
+fn _t(x: u32) -> u32 {
+    let b = _tau(x);
+
+    b ^ b.rotate_left(2) ^ b.rotate_left(10) ^ b.rotate_left(18) ^ b.rotate_left(24)
+}
+
+fn _t_key(x: u32) -> u32 {
+    let b = _tau(x);
+
+    b ^ b.rotate_left(13) ^ b.rotate_left(23)
+}
+
+fn _affine(x: u8) -> u8 {
+    x ^ x.rotate_left(1) ^ x.rotate_left(3) ^ x.rotate_left(6) ^ x.rotate_left(7)
+}
+
+/* Multiplication in GF(2^8) modulo 0x1f5 without branches on the operands. */
+fn _gf_mul(mut a: u8, b: u8) -> u8 {
+    let mut r = 0u8;
+    for i in 0..8 {
+        r ^= a & 0u8.wrapping_sub((b >> i) & 1);
+        a = (a << 1) ^ (0xf5 & 0u8.wrapping_sub(a >> 7));
+    }
+
+    r
+}
+
+/* x^254, which is the inverse of x and maps 0 to 0. */
+fn _gf_inv(x: u8) -> u8 {
+    let mut r = x;
+    for _ in 0..6 {
+        r = _gf_mul(_gf_mul(r, r), x);
+    }
+
+    _gf_mul(r, r)
+}
+
+fn _sbox(x: u8) -> u8 {
+    _affine(_gf_inv(_affine(x) ^ 0xd3)) ^ 0xd3
+}
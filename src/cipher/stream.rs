@@ -0,0 +1,213 @@
+use crate::common::api::AEAD;
+use crate::common::{Success, Error};
+
+/// Nonce bytes taken by the segment counter and the last-segment flag.
+pub const STREAM_NONCE_OVERHEAD: usize = 5;
+
+/* Large enough for the nonces of all AEADs of the crate. */
+const STREAM_MAX_NONCE_SIZE: usize = 32;
+
+/* Segment i of a stream is sealed under prefix || be32(i) || flag with flag = 1 for
+ * the final segment and 0 otherwise. */
+fn _nonce(prefix: &[u8], counter: u64, last: bool, nonce: &mut [u8; STREAM_MAX_NONCE_SIZE]) -> usize {
+    let len = prefix.len() + STREAM_NONCE_OVERHEAD;
+    nonce[..prefix.len()].copy_from_slice(prefix);
+    nonce[prefix.len()..len - 1].copy_from_slice(&(counter as u32).to_be_bytes());
+    nonce[len - 1] = last as u8;
+
+    len
+}
+
+/* The nonce length is the one of the instance, which for CCM need not be A::NONCE_SIZE. */
+fn _prefix<A: AEAD>(aead: &A, nonce_prefix: &[u8]) -> Result<[u8; STREAM_MAX_NONCE_SIZE], Error> {
+    let nonce_len = aead.nonce_len();
+    if nonce_len > STREAM_MAX_NONCE_SIZE || nonce_prefix.len() + STREAM_NONCE_OVERHEAD != nonce_len {
+        return Err(Error::Err);
+    }
+
+    let mut prefix = [0u8; STREAM_MAX_NONCE_SIZE];
+    prefix[..nonce_prefix.len()].copy_from_slice(nonce_prefix);
+
+    Ok(prefix)
+}
+
+/// Online encryption of a stream of segments with the STREAM construction of
+/// Hoang, Reyhanitabar, Rogaway and Vizár, as used by age and Tink, over any AEAD.
+///
+/// Every segment is sealed on its own under a nonce made of a per-stream prefix of
+/// `aead.nonce_len() - STREAM_NONCE_OVERHEAD` bytes, a 32-bit big-endian segment
+/// counter and a flag byte that marks the final segment, so messages of any size are
+/// encrypted without buffering them. The prefix must never repeat under a key; with
+/// an all-zero prefix and ChaCha20-Poly1305 the nonces are those of the age payload.
+///
+/// Each sealed segment is `A::seal` of the plaintext segment and the associated data
+/// passed with it. How the stream is cut into segments is up to the caller, who has
+/// to present the same segments to the decryptor.
+#[derive(Debug, Clone)]
+pub struct STREAMEncryptor<A: AEAD> {
+    aead: A,
+    prefix: [u8; STREAM_MAX_NONCE_SIZE],
+    prefix_len: usize,
+    counter: u64,
+    finished: bool,
+}
+
+impl<A: AEAD> STREAMEncryptor<A> {
+    pub fn new(aead: A, nonce_prefix: &[u8]) -> Result<Self, Error> {
+        Ok(STREAMEncryptor {
+            prefix: _prefix(&aead, nonce_prefix)?,
+            aead,
+            prefix_len: nonce_prefix.len(),
+            counter: 0,
+            finished: false,
+        })
+    }
+
+    /// Seals the next segment, which must not be the last one, and returns the number
+    /// of bytes written.
+    pub fn seal_segment(&mut self, aad: &[u8], plaintext: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        self._seal(aad, plaintext, output, false)
+    }
+
+    /// Seals the final segment, which may be empty, and ends the stream.
+    pub fn seal_last_segment(&mut self, aad: &[u8], plaintext: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        self._seal(aad, plaintext, output, true)
+    }
+
+    fn _seal(&mut self, aad: &[u8], plaintext: &[u8], output: &mut [u8], last: bool) -> Result<usize, Error> {
+        if self.finished {
+            return Err(Error::Err);
+        }
+        /* A non-final segment must leave a counter value for the final one. */
+        if self.counter + !last as u64 > u32::MAX as u64 {
+            return Err(Error::CounterOverflow);
+        }
+
+        let mut nonce = [0u8; STREAM_MAX_NONCE_SIZE];
+        let len = _nonce(&self.prefix[..self.prefix_len], self.counter, last, &mut nonce);
+        let written = self.aead.seal(&nonce[..len], aad, plaintext, output)?;
+
+        self.counter += 1;
+        self.finished = last;
+
+        Ok(written)
+    }
+}
+
+/// Decryption of a stream sealed by `STREAMEncryptor`, segment by segment in order.
+///
+/// A segment is only released if it is authentic at its position, so the plaintext
+/// handed out is always a prefix of the original stream. Errors beyond
+/// `Error::AuthFailed` tell what went wrong:
+///
+/// * `Error::Truncated`: `finish` was called before the final segment was opened, or
+///   the segment passed to `open_last_segment` is a non-final one.
+/// * `Error::OutOfOrder`: a segment follows the final one, or the segment is
+///   authentic but belongs to the neighbouring position (a dropped, duplicated or
+///   swapped segment) or it is the final segment although more are expected.
+///
+/// To tell these apart from forgeries, a segment that fails is tried at up to four
+/// other positions and flags, so a forged segment costs up to five decryptions
+/// instead of one. `set_diagnose(false)` turns this off for callers that face
+/// floods of forged segments; misplaced segments then fail with `Error::AuthFailed`.
+///
+/// After an error the position does not change and no plaintext is left in the output.
+#[derive(Debug, Clone)]
+pub struct STREAMDecryptor<A: AEAD> {
+    aead: A,
+    prefix: [u8; STREAM_MAX_NONCE_SIZE],
+    prefix_len: usize,
+    counter: u64,
+    finished: bool,
+    diagnose: bool,
+}
+
+impl<A: AEAD> STREAMDecryptor<A> {
+    pub fn new(aead: A, nonce_prefix: &[u8]) -> Result<Self, Error> {
+        Ok(STREAMDecryptor {
+            prefix: _prefix(&aead, nonce_prefix)?,
+            aead,
+            prefix_len: nonce_prefix.len(),
+            counter: 0,
+            finished: false,
+            diagnose: true,
+        })
+    }
+
+    /// Switches the diagnosis of segments at the wrong position on or off, it is on
+    /// after `new`. Without it every segment that fails to open at its position is
+    /// reported as `Error::AuthFailed`.
+    pub fn set_diagnose(&mut self, diagnose: bool) {
+        self.diagnose = diagnose;
+    }
+
+    /// Opens the next segment, which must not be the last one, and returns the length
+    /// of the plaintext.
+    pub fn open_segment(&mut self, aad: &[u8], input: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        self._open(aad, input, output, false)
+    }
+
+    /// Opens the final segment and ends the stream.
+    pub fn open_last_segment(&mut self, aad: &[u8], input: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        self._open(aad, input, output, true)
+    }
+
+    /// Checks that the stream has been read up to its final segment.
+    pub fn finish(&self) -> Result<Success, Error> {
+        if !self.finished {
+            return Err(Error::Truncated);
+        }
+
+        Ok(Success::OK)
+    }
+
+    fn _open(&mut self, aad: &[u8], input: &[u8], output: &mut [u8], last: bool) -> Result<usize, Error> {
+        if self.finished {
+            return Err(Error::OutOfOrder);
+        }
+        if self.counter > u32::MAX as u64 {
+            return Err(Error::CounterOverflow);
+        }
+
+        match self._try(self.counter, last, aad, input, output) {
+            Ok(len) => {
+                self.counter += 1;
+                self.finished = last;
+                Ok(len)
+            }
+            Err(Error::AuthFailed) if self.diagnose => Err(self._diagnose(aad, input, output, last)),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn _try(&self, counter: u64, last: bool, aad: &[u8], input: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        let mut nonce = [0u8; STREAM_MAX_NONCE_SIZE];
+        let len = _nonce(&self.prefix[..self.prefix_len], counter, last, &mut nonce);
+
+        self.aead.open(&nonce[..len], aad, input, output)
+    }
+
+    /* Finds out whether a segment that failed at its position is authentic at the
+     * same position with the other flag or at a neighbouring one. Any plaintext of
+     * these trials is wiped again. */
+    fn _diagnose(&self, aad: &[u8], input: &[u8], output: &mut [u8], last: bool) -> Error {
+        let candidates = [
+            (Some(self.counter), !last, if last { Error::Truncated } else { Error::OutOfOrder }),
+            (Some(self.counter + 1), false, Error::OutOfOrder),
+            (Some(self.counter + 1), true, Error::OutOfOrder),
+            (self.counter.checked_sub(1), false, Error::OutOfOrder),
+        ];
+
+        for (counter, flag, error) in candidates {
+            let Some(counter) = counter.filter(|c| *c <= u32::MAX as u64) else {
+                continue;
+            };
+            if let Ok(len) = self._try(counter, flag, aad, input, output) {
+                output[..len].fill(0);
+                return error;
+            }
+        }
+
+        Error::AuthFailed
+    }
+}
@@ -0,0 +1,145 @@
+use crate::cipher::AES_BLOCKSIZE;
+use crate::cipher::modes::Direction;
+use crate::common::api::BlockCipher;
+use crate::common::{Success, Error};
+use crate::utils::ct_eq;
+
+pub const XTS_TWEAK_SIZE: usize = 16;
+
+/* SP 800-38E limits a data unit to 2^20 blocks. */
+const XTS_MAX_DATA_UNIT: usize = AES_BLOCKSIZE << 20;
+
+/// XTS-AES (IEEE 1619, SP 800-38E) for `C` = AES-128 or AES-256.
+///
+/// The key is Key1 || Key2, twice the key length of `C`, and is rejected if both
+/// halves are equal. IEEE 1619 has no AES-192 variant, `new` fails for `AES192`.
+/// Each data unit (sector) is en- or decrypted in place under its own tweak, given
+/// either as the 128-bit value i or as a sector number. Data units are 16 bytes to
+/// 2^24 bytes long; a partial last block is handled by ciphertext stealing, so the
+/// ciphertext has the length of the plaintext. The `_bits` variants take data units
+/// whose length in bits is not a multiple of 8, as SP 800-38E allows.
+#[derive(Debug, Clone)]
+pub struct XTS<C: BlockCipher<AES_BLOCKSIZE>> {
+    cipher: C,
+    tweak_cipher: C,
+}
+
+impl<C: BlockCipher<AES_BLOCKSIZE>> XTS<C> {
+    pub fn new(key: &[u8]) -> Result<Self, Error> {
+        /* 24 byte keys are AES-192, which IEEE 1619 doesn't define. */
+        if C::KEY_SIZE == 24 || key.len() != 2 * C::KEY_SIZE {
+            return Err(Error::Err);
+        }
+
+        let (k1, k2) = key.split_at(C::KEY_SIZE);
+        if ct_eq(k1, k2) {
+            return Err(Error::Err);
+        }
+
+        Ok(XTS { cipher: C::new(k1)?, tweak_cipher: C::new(k2)? })
+    }
+
+    /// Encrypts the data unit with sequence number `sector`, used as tweak in
+    /// little-endian byte order.
+    pub fn encrypt_sector(&self, sector: u64, buffer: &mut [u8]) -> Result<Success, Error> {
+        self._process(&(sector as u128).to_le_bytes(), buffer, buffer.len() * 8, Direction::Encrypt)
+    }
+
+    pub fn decrypt_sector(&self, sector: u64, buffer: &mut [u8]) -> Result<Success, Error> {
+        self._process(&(sector as u128).to_le_bytes(), buffer, buffer.len() * 8, Direction::Decrypt)
+    }
+
+    pub fn encrypt_with_tweak(&self, tweak: &[u8; XTS_TWEAK_SIZE], buffer: &mut [u8]) -> Result<Success, Error> {
+        self._process(tweak, buffer, buffer.len() * 8, Direction::Encrypt)
+    }
+
+    pub fn decrypt_with_tweak(&self, tweak: &[u8; XTS_TWEAK_SIZE], buffer: &mut [u8]) -> Result<Success, Error> {
+        self._process(tweak, buffer, buffer.len() * 8, Direction::Decrypt)
+    }
+
+    /// Encrypts a data unit of `bits` bits, held most significant bit first in the
+    /// `bits.div_ceil(8)` bytes of `buffer`. The unused low bits of the last byte are
+    /// ignored on input and cleared on output.
+    pub fn encrypt_with_tweak_bits(&self, tweak: &[u8; XTS_TWEAK_SIZE], buffer: &mut [u8], bits: usize) -> Result<Success, Error> {
+        self._process(tweak, buffer, bits, Direction::Encrypt)
+    }
+
+    pub fn decrypt_with_tweak_bits(&self, tweak: &[u8; XTS_TWEAK_SIZE], buffer: &mut [u8], bits: usize) -> Result<Success, Error> {
+        self._process(tweak, buffer, bits, Direction::Decrypt)
+    }
+
+    fn _process(&self, tweak: &[u8; XTS_TWEAK_SIZE], buffer: &mut [u8], bits: usize, direction: Direction) -> Result<Success, Error> {
+        let len = buffer.len();
+        if !(AES_BLOCKSIZE..=XTS_MAX_DATA_UNIT).contains(&len) || bits.div_ceil(8) != len || bits < 8 * AES_BLOCKSIZE {
+            return Err(Error::Err);
+        }
+
+        let mut t = *tweak;
+        self.tweak_cipher.encrypt_block(&mut t);
+
+        /* With a partial last block of r bits, the last full block is left for the
+         * stealing. Only the `mask` bits of the last byte belong to the data unit. */
+        let r = bits % (8 * AES_BLOCKSIZE);
+        let full = bits / (8 * AES_BLOCKSIZE) - (r != 0) as usize;
+        let mask = 0xffu8 << ((8 - bits % 8) % 8);
+
+        for block in buffer[..full * AES_BLOCKSIZE].chunks_exact_mut(AES_BLOCKSIZE) {
+            self._xex(direction, &t, block.try_into().unwrap());
+            t = _mul_alpha(&t);
+        }
+
+        /* Ciphertext stealing (IEEE 1619, section 5.3.2 and 5.4.2): the last full
+         * block is processed first, its leading r bits become the partial block and
+         * the partial input is completed with the rest and processed in its place.
+         * Decryption uses the two tweaks in reverse order. */
+        if r != 0 {
+            let (head, tail) = buffer[full * AES_BLOCKSIZE..].split_at_mut(AES_BLOCKSIZE);
+            let head: &mut [u8; AES_BLOCKSIZE] = head.try_into().unwrap();
+            let next = _mul_alpha(&t);
+            let (t1, t2) = match direction {
+                Direction::Encrypt => (&t, &next),
+                Direction::Decrypt => (&next, &t),
+            };
+
+            let n = tail.len();
+            let mut cc = *head;
+            self._xex(direction, t1, &mut cc);
+            let mut pp = cc;
+            pp[..n].copy_from_slice(tail);
+            pp[n - 1] = (tail[n - 1] & mask) | (cc[n - 1] & !mask);
+            tail.copy_from_slice(&cc[..n]);
+            tail[n - 1] &= mask;
+            self._xex(direction, t2, &mut pp);
+            *head = pp;
+
+            cc.fill(0);
+            pp.fill(0);
+        }
+        t.fill(0);
+
+        Ok(Success::OK)
+    }
+
+    /* C = E(P ^ T) ^ T respectively P = D(C ^ T) ^ T. */
+    fn _xex(&self, direction: Direction, t: &[u8; AES_BLOCKSIZE], block: &mut [u8; AES_BLOCKSIZE]) {
+        for (b, k) in block.iter_mut().zip(t.iter()) {
+            *b ^= k;
+        }
+        match direction {
+            Direction::Encrypt => self.cipher.encrypt_block(block),
+            Direction::Decrypt => self.cipher.decrypt_block(block),
+        }
+        for (b, k) in block.iter_mut().zip(t.iter()) {
+            *b ^= k;
+        }
+    }
+}
+
+/* Multiplication by the primitive element alpha of GF(2^128), with the tweak read
+ * as a little-endian integer. */
+fn _mul_alpha(t: &[u8; AES_BLOCKSIZE]) -> [u8; AES_BLOCKSIZE] {
+    let v = u128::from_le_bytes(*t);
+    let reduce = (v >> 127).wrapping_neg() & 0x87;
+
+    ((v << 1) ^ reduce).to_le_bytes()
+}
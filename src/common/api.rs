@@ -13,3 +13,174 @@ pub trait SingleInputUpdate {
 pub trait SingleOutputFinish {
     fn finish(&mut self, output: &mut [u8]) -> Result<Success, Error>;
 }
+
+/// A message authentication code, keyed on construction: `init` starts a message and
+/// `finish` writes the MAC truncated to the length of the output buffer. An output
+/// buffer longer than `mac_size` is an error.
+pub trait MAC: StreamingAPI {
+    /// Size of the untruncated MAC in bytes.
+    fn mac_size(&self) -> usize;
+}
+
+/// A source of entropy for seeding DRBGs, generating keys or nonces.
+pub trait EntropySource {
+    /// Fills `output` completely or fails with `Error::EntropyFailure`.
+    fn fill_entropy(&mut self, output: &mut [u8]) -> Result<Success, Error>;
+}
+
+/// A block cipher with blocks of `BLOCKSIZE` bytes, keyed on construction.
+pub trait BlockCipher<const BLOCKSIZE: usize>: Sized {
+    /// Key length in bytes.
+    const KEY_SIZE: usize;
+
+    fn new(key: &[u8]) -> Result<Self, Error>;
+
+    fn encrypt_block(&self, block: &mut [u8; BLOCKSIZE]);
+
+    fn decrypt_block(&self, block: &mut [u8; BLOCKSIZE]);
+}
+
+/// Streaming interface of ciphers that are keyed on construction and process one
+/// message per IV: `init` with the IV, `update` as often as needed, `finish` once.
+/// `update` and `finish` return the number of bytes written to `output`.
+pub trait CipherStreamingAPI: IVInit + InputOutputUpdate + OutputFinish {}
+
+pub trait IVInit {
+    fn init(&mut self, iv: &[u8]) -> Result<Success, Error>;
+}
+
+pub trait InputOutputUpdate {
+    fn update(&mut self, input: &[u8], output: &mut [u8]) -> Result<usize, Error>;
+}
+
+pub trait OutputFinish {
+    fn finish(&mut self, output: &mut [u8]) -> Result<usize, Error>;
+}
+
+/// Authenticated encryption with associated data, keyed on construction, so that
+/// AES-GCM, ChaCha20-Poly1305 and the others can be exchanged by type parameter.
+///
+/// The in-place functions are the core of every implementation: `seal_in_place`
+/// encrypts a buffer and writes the tag separately, `open_in_place` fails with
+/// `Error::AuthFailed` if the tag does not match and then leaves the ciphertext in
+/// the buffer. The other forms are derived from them. `seal` writes the ciphertext
+/// and the tag in the layout of the respective specification (the tag is appended
+/// for GCM, CCM, GCM-SIV and ChaCha20-Poly1305 and prepended as the synthetic IV for
+/// SIV) and returns the number of bytes written; `open` takes that layout.
+///
+/// If the tag does not match, `open_detached` and `open` fail with
+/// `Error::AuthFailed` and zero the plaintext output, so no unauthenticated data is
+/// left behind. Implementations that override them keep this contract.
+pub trait AEAD: Sized {
+    /// Key length in bytes.
+    const KEY_SIZE: usize;
+    /// Nonce length in bytes of an instance created with `new`, see `nonce_len`.
+    const NONCE_SIZE: usize;
+    /// Length of the untruncated tag in bytes.
+    const TAG_SIZE: usize;
+
+    /// Creates an instance with the default parameters of the algorithm.
+    fn new(key: &[u8]) -> Result<Self, Error>;
+
+    /// Length of the nonce in bytes this instance takes. It differs from
+    /// `NONCE_SIZE` for instances created with other parameters, e.g. CCM.
+    fn nonce_len(&self) -> usize;
+
+    /// Length of the tag in bytes, the ciphertext expansion.
+    fn tag_len(&self) -> usize;
+
+    fn seal_in_place(&self, nonce: &[u8], aad: &[u8], buffer: &mut [u8], tag: &mut [u8]) -> Result<Success, Error>;
+
+    fn open_in_place(&self, nonce: &[u8], aad: &[u8], buffer: &mut [u8], tag: &[u8]) -> Result<Success, Error>;
+
+    fn seal_detached(
+        &self,
+        nonce: &[u8],
+        aad: &[u8],
+        plaintext: &[u8],
+        ciphertext: &mut [u8],
+        tag: &mut [u8],
+    ) -> Result<Success, Error> {
+        if ciphertext.len() < plaintext.len() {
+            return Err(Error::Err);
+        }
+
+        let ciphertext = &mut ciphertext[..plaintext.len()];
+        ciphertext.copy_from_slice(plaintext);
+        self.seal_in_place(nonce, aad, ciphertext, tag)
+    }
+
+    /// Decrypts into `plaintext`, which is zeroed on failure.
+    fn open_detached(
+        &self,
+        nonce: &[u8],
+        aad: &[u8],
+        ciphertext: &[u8],
+        tag: &[u8],
+        plaintext: &mut [u8],
+    ) -> Result<Success, Error> {
+        if plaintext.len() < ciphertext.len() {
+            return Err(Error::Err);
+        }
+
+        let plaintext = &mut plaintext[..ciphertext.len()];
+        plaintext.copy_from_slice(ciphertext);
+        self.open_in_place(nonce, aad, plaintext, tag).inspect_err(|_| plaintext.fill(0))
+    }
+
+    fn seal(&self, nonce: &[u8], aad: &[u8], plaintext: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        let (len, tag_len) = (plaintext.len(), self.tag_len());
+        if output.len() < len + tag_len {
+            return Err(Error::Err);
+        }
+
+        let (ciphertext, tag) = output.split_at_mut(len);
+        self.seal_detached(nonce, aad, plaintext, ciphertext, &mut tag[..tag_len])?;
+
+        Ok(len + tag_len)
+    }
+
+    /// Returns the length of the plaintext.
+    fn open(&self, nonce: &[u8], aad: &[u8], input: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        if input.len() < self.tag_len() {
+            return Err(Error::Err);
+        }
+
+        let (ciphertext, tag) = input.split_at(input.len() - self.tag_len());
+        self.open_detached(nonce, aad, ciphertext, tag, output)?;
+
+        Ok(ciphertext.len())
+    }
+}
+
+/// An AEAD that can process a message in pieces, for messages that do not fit into
+/// memory at once. Two-pass constructions (CCM, SIV, GCM-SIV) cannot offer this.
+pub trait StreamingAEAD: AEAD {
+    type Encryptor: AEADEncryptor;
+    type Decryptor: AEADDecryptor;
+
+    fn encryptor(&self) -> Self::Encryptor;
+
+    fn decryptor(&self) -> Self::Decryptor;
+}
+
+/// Streaming encryption: `init` with the nonce, `update_aad` for all associated
+/// data, `update` for the message and `finish` to write the tag.
+pub trait AEADEncryptor: IVInit + InputOutputUpdate + OutputFinish {
+    /// Adds associated data. Only allowed before the first call of `update`.
+    fn update_aad(&mut self, aad: &[u8]) -> Result<Success, Error>;
+}
+
+/// Streaming decryption: `init` with the nonce, `update_aad` for all associated
+/// data, `update` for the message and `verify` to check the tag.
+///
+/// Plaintext is handed out before the tag has been checked. Callers must not use
+/// any of it unless `verify` succeeds; use `AEAD::open` where that cannot be
+/// guaranteed.
+pub trait AEADDecryptor: IVInit + InputOutputUpdate {
+    /// Adds associated data. Only allowed before the first call of `update`.
+    fn update_aad(&mut self, aad: &[u8]) -> Result<Success, Error>;
+
+    /// Checks the tag of a decrypted message.
+    fn verify(&mut self, tag: &[u8]) -> Result<Success, Error>;
+}
@@ -3,6 +3,24 @@ pub mod api;
 #[derive(Debug, PartialEq)]
 pub enum Error {
     Err,
+    /* A block counter would have to wrap to produce the requested output. */
+    CounterOverflow,
+    /* The DRBG has to be reseeded before it can produce more output. */
+    ReseedRequired,
+    /* The entropy source could not deliver the requested amount of entropy. */
+    EntropyFailure,
+    /* A continuous health test on the noise source tripped, see SP 800-90B. */
+    HealthTest,
+    /* Tag verification of an authenticated encryption failed. */
+    AuthFailed,
+    /* The integrity check of an unwrapped key failed (SP 800-38F). */
+    IntegrityCheck,
+    /* A segmented stream ended before its final segment. */
+    Truncated,
+    /* A segment of a stream is authentic but not at its position. */
+    OutOfOrder,
+    /* The input contains a block of a cryptanalytic collision attack on SHA-1. */
+    CollisionDetected,
 }
 
 #[derive(Debug, PartialEq)]
@@ -0,0 +1,257 @@
+use crate::cipher::{AES, AES_BLOCKSIZE};
+use crate::common::api::BlockCipher;
+use crate::common::{Success, Error};
+use crate::drbg::{DRBG, DRBGDataCtx, Mechanism};
+
+/* seedlen for AES-256, the largest one. */
+const CTR_DRBG_MAX_SEEDLEN: usize = 32 + AES_BLOCKSIZE;
+
+/// CTR_DRBG (SP 800-90A, section 10.2.1) over AES with a key of `KEYSIZE` bytes,
+/// with or without the block cipher derivation function.
+///
+/// Without the derivation function the entropy input has to be full entropy of
+/// exactly seedlen (`KEYSIZE` + 16) bytes and the nonce is not used.
+#[derive(Debug, Clone)]
+pub struct CTRDRBG<const KEYSIZE: usize> {
+    data: DRBGDataCtx,
+    aes: AES<KEYSIZE>,
+    v: [u8; AES_BLOCKSIZE],
+    use_df: bool,
+}
+
+pub type CTRDRBG128 = CTRDRBG<16>;
+pub type CTRDRBG192 = CTRDRBG<24>;
+pub type CTRDRBG256 = CTRDRBG<32>;
+
+impl<const KEYSIZE: usize> CTRDRBG<KEYSIZE> {
+    const SEEDLEN: usize = KEYSIZE + AES_BLOCKSIZE;
+
+    pub fn new(use_df: bool) -> Result<Self, Error> {
+        Ok(CTRDRBG {
+            data: DRBGDataCtx::new(),
+            aes: AES::new(&[0; KEYSIZE])?,
+            v: [0; AES_BLOCKSIZE],
+            use_df,
+        })
+    }
+
+    /* V = (V + 1) mod 2^128 */
+    fn _increment_v(&mut self) {
+        for b in self.v.iter_mut().rev() {
+            *b = b.wrapping_add(1);
+            if *b != 0 {
+                break;
+            }
+        }
+    }
+
+    /* CTR_DRBG_Update (section 10.2.1.2). */
+    fn _update(&mut self, provided: &[u8; CTR_DRBG_MAX_SEEDLEN]) -> Result<Success, Error> {
+        let mut temp = [0u8; CTR_DRBG_MAX_SEEDLEN];
+
+        for chunk in temp[..Self::SEEDLEN].chunks_mut(AES_BLOCKSIZE) {
+            self._increment_v();
+            let mut block = self.v;
+            self.aes.encrypt_block(&mut block);
+            chunk.copy_from_slice(&block[..chunk.len()]);
+        }
+
+        for (t, p) in temp.iter_mut().zip(provided.iter()) {
+            *t ^= p;
+        }
+
+        self.aes = AES::new(&temp[..KEYSIZE])?;
+        self.v.copy_from_slice(&temp[KEYSIZE..Self::SEEDLEN]);
+        temp.fill(0);
+
+        Ok(Success::OK)
+    }
+
+    /* Turns the concatenation of `input` into seedlen bytes of seed material, either
+     * through Block_Cipher_df or by padding it with zeros. */
+    fn _seed_material(&self, input: &[&[u8]], seed: &mut [u8; CTR_DRBG_MAX_SEEDLEN]) -> Result<Success, Error> {
+        let len: usize = input.iter().map(|i| i.len()).sum();
+
+        if self.use_df {
+            return _block_cipher_df::<KEYSIZE>(input, len, &mut seed[..Self::SEEDLEN]);
+        }
+
+        if len > Self::SEEDLEN {
+            return Err(Error::Err);
+        }
+
+        seed.fill(0);
+        let mut pos = 0;
+        for i in input {
+            seed[pos..pos + i.len()].copy_from_slice(i);
+            pos += i.len();
+        }
+
+        Ok(Success::OK)
+    }
+}
+
+/* BCC (section 10.3.3) over `prefix` followed by the blocks of `s`, the string S
+ * of Block_Cipher_df. */
+fn _bcc<const KEYSIZE: usize>(
+    aes: &AES<KEYSIZE>,
+    prefix: &[u8; AES_BLOCKSIZE],
+    s: &[&[u8]],
+    output: &mut [u8; AES_BLOCKSIZE],
+) {
+    let mut chaining = *prefix;
+    aes.encrypt_block(&mut chaining);
+
+    let mut pos = 0;
+    for part in s {
+        for b in part.iter() {
+            chaining[pos] ^= b;
+            pos += 1;
+            if pos == AES_BLOCKSIZE {
+                aes.encrypt_block(&mut chaining);
+                pos = 0;
+            }
+        }
+    }
+
+    /* S is padded with 0x80 followed by zeros to a multiple of the block size. */
+    chaining[pos] ^= 0x80;
+    aes.encrypt_block(&mut chaining);
+
+    *output = chaining;
+}
+
+/* Block_Cipher_df (section 10.3.2). */
+fn _block_cipher_df<const KEYSIZE: usize>(
+    input: &[&[u8]],
+    len: usize,
+    output: &mut [u8],
+) -> Result<Success, Error> {
+    let l = (len as u32).to_be_bytes();
+    let n = (output.len() as u32).to_be_bytes();
+
+    let mut k = [0u8; KEYSIZE];
+    for (i, b) in k.iter_mut().enumerate() {
+        *b = i as u8;
+    }
+    let aes = AES::<KEYSIZE>::new(&k)?;
+
+    let mut temp = [0u8; CTR_DRBG_MAX_SEEDLEN];
+    let mut block = [0u8; AES_BLOCKSIZE];
+    for (i, chunk) in temp[..KEYSIZE + AES_BLOCKSIZE].chunks_mut(AES_BLOCKSIZE).enumerate() {
+        let mut iv = [0u8; AES_BLOCKSIZE];
+        iv[..4].copy_from_slice(&(i as u32).to_be_bytes());
+
+        let mut s: [&[u8]; 8] = [&l, &n, &[], &[], &[], &[], &[], &[]];
+        s[2..2 + input.len()].copy_from_slice(input);
+        _bcc(&aes, &iv, &s, &mut block);
+        chunk.copy_from_slice(&block[..chunk.len()]);
+    }
+
+    let aes = AES::<KEYSIZE>::new(&temp[..KEYSIZE])?;
+    let mut x = [0u8; AES_BLOCKSIZE];
+    x.copy_from_slice(&temp[KEYSIZE..KEYSIZE + AES_BLOCKSIZE]);
+    temp.fill(0);
+
+    for chunk in output.chunks_mut(AES_BLOCKSIZE) {
+        aes.encrypt_block(&mut x);
+        chunk.copy_from_slice(&x[..chunk.len()]);
+    }
+
+    Ok(Success::OK)
+}
+
+impl<const KEYSIZE: usize> DRBG for CTRDRBG<KEYSIZE> {
+    fn security_strength(&self) -> usize {
+        KEYSIZE * 8
+    }
+}
+
+impl<const KEYSIZE: usize> Mechanism for CTRDRBG<KEYSIZE> {
+    fn _data(&mut self) -> &mut DRBGDataCtx {
+        &mut self.data
+    }
+
+    fn _entropy_len(&self) -> usize {
+        if self.use_df {
+            KEYSIZE
+        } else {
+            Self::SEEDLEN
+        }
+    }
+
+    fn _nonce_len(&self) -> usize {
+        if self.use_df {
+            KEYSIZE / 2
+        } else {
+            0
+        }
+    }
+
+    fn _instantiate(&mut self, entropy: &[u8], nonce: &[u8], personalization: &[u8]) -> Result<Success, Error> {
+        let mut seed = [0u8; CTR_DRBG_MAX_SEEDLEN];
+
+        if self.use_df {
+            if nonce.len() * 16 < self.security_strength() {
+                return Err(Error::Err);
+            }
+            self._seed_material(&[entropy, nonce, personalization], &mut seed)?;
+        } else {
+            if entropy.len() != Self::SEEDLEN {
+                return Err(Error::Err);
+            }
+            /* seed_material = entropy_input ^ personalization_string */
+            self._seed_material(&[personalization], &mut seed)?;
+            for (s, e) in seed.iter_mut().zip(entropy.iter()) {
+                *s ^= e;
+            }
+        }
+
+        self.aes = AES::new(&[0; KEYSIZE])?;
+        self.v.fill(0);
+        self._update(&seed)
+    }
+
+    fn _reseed(&mut self, entropy: &[u8], additional: &[u8]) -> Result<Success, Error> {
+        let mut seed = [0u8; CTR_DRBG_MAX_SEEDLEN];
+
+        if self.use_df {
+            self._seed_material(&[entropy, additional], &mut seed)?;
+        } else {
+            if entropy.len() != Self::SEEDLEN {
+                return Err(Error::Err);
+            }
+            self._seed_material(&[additional], &mut seed)?;
+            for (s, e) in seed.iter_mut().zip(entropy.iter()) {
+                *s ^= e;
+            }
+        }
+
+        self._update(&seed)
+    }
+
+    fn _generate(&mut self, output: &mut [u8], additional: &[u8]) -> Result<Success, Error> {
+        let mut seed = [0u8; CTR_DRBG_MAX_SEEDLEN];
+
+        if !additional.is_empty() {
+            self._seed_material(&[additional], &mut seed)?;
+            self._update(&seed)?;
+        }
+
+        for chunk in output.chunks_mut(AES_BLOCKSIZE) {
+            self._increment_v();
+            let mut block = self.v;
+            self.aes.encrypt_block(&mut block);
+            chunk.copy_from_slice(&block[..chunk.len()]);
+        }
+
+        self._update(&seed)
+    }
+
+    fn _clear(&mut self) {
+        self.v.fill(0);
+        if let Ok(aes) = AES::new(&[0; KEYSIZE]) {
+            self.aes = aes;
+        }
+    }
+}
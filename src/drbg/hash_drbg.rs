@@ -0,0 +1,172 @@
+use crate::common::api::{DefaultInit, SingleInputUpdate, SingleOutputFinish};
+use crate::common::{Success, Error};
+use crate::drbg::{DRBG, DRBGDataCtx, Mechanism, _hash_security_strength};
+use crate::hash::{SHA, MAX_DIGEST_SIZE};
+
+/* seedlen from SP 800-90A, table 2. */
+const HASH_DRBG_SEEDLEN_SHORT: usize = 440 / 8;
+const HASH_DRBG_SEEDLEN_LONG: usize = 888 / 8;
+
+/// Hash_DRBG (SP 800-90A, section 10.1.1).
+#[derive(Debug, Clone)]
+pub struct HashDRBG {
+    data: DRBGDataCtx,
+    hash: SHA,
+    v: [u8; HASH_DRBG_SEEDLEN_LONG],
+    c: [u8; HASH_DRBG_SEEDLEN_LONG],
+    seedlen: usize,
+}
+
+impl HashDRBG {
+    pub fn new(mut hash: SHA) -> Result<Self, Error> {
+        /* The context may come in the middle of a message. */
+        hash.init()?;
+
+        let seedlen = if hash.digest_size() > 32 {
+            HASH_DRBG_SEEDLEN_LONG
+        } else {
+            HASH_DRBG_SEEDLEN_SHORT
+        };
+
+        Ok(HashDRBG {
+            data: DRBGDataCtx::new(),
+            hash,
+            v: [0; HASH_DRBG_SEEDLEN_LONG],
+            c: [0; HASH_DRBG_SEEDLEN_LONG],
+            seedlen,
+        })
+    }
+
+    /* Hash_df (section 10.3.1). The input string is the concatenation of `input`. */
+    fn _hash_df(&mut self, input: &[&[u8]], output: &mut [u8]) -> Result<Success, Error> {
+        let outlen = self.hash.digest_size();
+        let nbits = (output.len() as u32 * 8).to_be_bytes();
+        let mut block = [0u8; MAX_DIGEST_SIZE];
+
+        for (counter, chunk) in (1u8..).zip(output.chunks_mut(outlen)) {
+            self.hash.init()?;
+            self.hash.update(&[counter])?;
+            self.hash.update(&nbits)?;
+            for i in input {
+                self.hash.update(i)?;
+            }
+            self.hash.finish(&mut block[..outlen])?;
+
+            chunk.copy_from_slice(&block[..chunk.len()]);
+        }
+
+        Ok(Success::OK)
+    }
+
+    /* Sets V to `seed` and derives C = Hash_df(0x00 || V). */
+    fn _set_seed(&mut self, seed: &[u8; HASH_DRBG_SEEDLEN_LONG]) -> Result<Success, Error> {
+        let seedlen = self.seedlen;
+        let mut c = [0u8; HASH_DRBG_SEEDLEN_LONG];
+
+        self.v = *seed;
+        self._hash_df(&[&[0x00], &seed[..seedlen]], &mut c[..seedlen])?;
+        self.c = c;
+
+        Ok(Success::OK)
+    }
+
+    /* V = (V + Hash(prefix || V || additional)) mod 2^seedlen */
+    fn _hash_v_add(&mut self, prefix: u8, additional: &[u8]) -> Result<Success, Error> {
+        let outlen = self.hash.digest_size();
+        let mut w = [0u8; MAX_DIGEST_SIZE];
+
+        self.hash.init()?;
+        self.hash.update(&[prefix])?;
+        self.hash.update(&self.v[..self.seedlen])?;
+        self.hash.update(additional)?;
+        self.hash.finish(&mut w[..outlen])?;
+
+        _add_be(&mut self.v[..self.seedlen], &w[..outlen]);
+
+        Ok(Success::OK)
+    }
+}
+
+/* a = (a + b) mod 2^(8 * a.len()), both big endian. */
+fn _add_be(a: &mut [u8], b: &[u8]) {
+    let mut carry: u16 = 0;
+    let mut j = b.len();
+
+    for x in a.iter_mut().rev() {
+        let mut sum = *x as u16 + carry;
+        if j > 0 {
+            j -= 1;
+            sum += b[j] as u16;
+        }
+        *x = sum as u8;
+        carry = sum >> 8;
+    }
+}
+
+impl DRBG for HashDRBG {
+    fn security_strength(&self) -> usize {
+        _hash_security_strength(&self.hash)
+    }
+}
+
+impl Mechanism for HashDRBG {
+    fn _data(&mut self) -> &mut DRBGDataCtx {
+        &mut self.data
+    }
+
+    fn _instantiate(&mut self, entropy: &[u8], nonce: &[u8], personalization: &[u8]) -> Result<Success, Error> {
+        if nonce.len() * 16 < self.security_strength() {
+            return Err(Error::Err);
+        }
+
+        let seedlen = self.seedlen;
+        let mut seed = [0u8; HASH_DRBG_SEEDLEN_LONG];
+        self._hash_df(&[entropy, nonce, personalization], &mut seed[..seedlen])?;
+
+        self._set_seed(&seed)
+    }
+
+    fn _reseed(&mut self, entropy: &[u8], additional: &[u8]) -> Result<Success, Error> {
+        let seedlen = self.seedlen;
+        let v = self.v;
+        let mut seed = [0u8; HASH_DRBG_SEEDLEN_LONG];
+        self._hash_df(&[&[0x01], &v[..seedlen], entropy, additional], &mut seed[..seedlen])?;
+
+        self._set_seed(&seed)
+    }
+
+    fn _generate(&mut self, output: &mut [u8], additional: &[u8]) -> Result<Success, Error> {
+        let seedlen = self.seedlen;
+        let outlen = self.hash.digest_size();
+
+        if !additional.is_empty() {
+            self._hash_v_add(0x02, additional)?;
+        }
+
+        /* Hashgen */
+        let mut data = self.v;
+        let mut block = [0u8; MAX_DIGEST_SIZE];
+        for chunk in output.chunks_mut(outlen) {
+            self.hash.init()?;
+            self.hash.update(&data[..seedlen])?;
+            self.hash.finish(&mut block[..outlen])?;
+
+            chunk.copy_from_slice(&block[..chunk.len()]);
+            _add_be(&mut data[..seedlen], &[1]);
+        }
+
+        /* V = (V + H + C + reseed_counter) mod 2^seedlen */
+        let c = self.c;
+        let counter = self.data.reseed_counter.to_be_bytes();
+        self._hash_v_add(0x03, &[])?;
+        _add_be(&mut self.v[..seedlen], &c[..seedlen]);
+        _add_be(&mut self.v[..seedlen], &counter);
+
+        Ok(Success::OK)
+    }
+
+    fn _clear(&mut self) {
+        self.v.fill(0);
+        self.c.fill(0);
+    }
+}
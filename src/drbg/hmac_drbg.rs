@@ -0,0 +1,119 @@
+use crate::common::api::{DefaultInit, SingleInputUpdate, SingleOutputFinish};
+use crate::common::{Success, Error};
+use crate::drbg::{DRBG, DRBGDataCtx, Mechanism, _hash_security_strength};
+use crate::hash::{SHA, MAX_DIGEST_SIZE};
+use crate::mac::HMAC;
+
+/// HMAC_DRBG (SP 800-90A, section 10.1.2).
+#[derive(Debug, Clone)]
+pub struct HMACDRBG {
+    data: DRBGDataCtx,
+    hmac: HMAC,
+    k: [u8; MAX_DIGEST_SIZE],
+    v: [u8; MAX_DIGEST_SIZE],
+    outlen: usize,
+    strength: usize,
+}
+
+impl HMACDRBG {
+    pub fn new(hash: SHA) -> Result<Self, Error> {
+        let outlen = hash.digest_size();
+        let strength = _hash_security_strength(&hash);
+
+        Ok(HMACDRBG {
+            data: DRBGDataCtx::new(),
+            hmac: HMAC::new(hash, &[])?,
+            k: [0; MAX_DIGEST_SIZE],
+            v: [0; MAX_DIGEST_SIZE],
+            outlen,
+            strength,
+        })
+    }
+
+    /* V = HMAC(K, V) */
+    fn _next_v(&mut self) -> Result<Success, Error> {
+        let outlen = self.outlen;
+        let v = self.v;
+
+        self.hmac.init()?;
+        self.hmac.update(&v[..outlen])?;
+        self.hmac.finish(&mut self.v[..outlen])
+    }
+
+    /* HMAC_DRBG_Update (section 10.1.2.2). The provided data is the concatenation of `provided`. */
+    fn _update(&mut self, provided: &[&[u8]]) -> Result<Success, Error> {
+        let outlen = self.outlen;
+        let empty = provided.iter().all(|p| p.is_empty());
+
+        for round in [0x00u8, 0x01] {
+            if round == 0x01 && empty {
+                break;
+            }
+
+            /* K = HMAC(K, V || round || provided_data) */
+            let v = self.v;
+            self.hmac.init()?;
+            self.hmac.update(&v[..outlen])?;
+            self.hmac.update(&[round])?;
+            for p in provided {
+                self.hmac.update(p)?;
+            }
+            self.hmac.finish(&mut self.k[..outlen])?;
+            self.hmac.set_key(&self.k[..outlen])?;
+
+            self._next_v()?;
+        }
+
+        Ok(Success::OK)
+    }
+}
+
+impl DRBG for HMACDRBG {
+    fn security_strength(&self) -> usize {
+        self.strength
+    }
+}
+
+impl Mechanism for HMACDRBG {
+    fn _data(&mut self) -> &mut DRBGDataCtx {
+        &mut self.data
+    }
+
+    fn _instantiate(&mut self, entropy: &[u8], nonce: &[u8], personalization: &[u8]) -> Result<Success, Error> {
+        if nonce.len() * 16 < self.security_strength() {
+            return Err(Error::Err);
+        }
+
+        let outlen = self.outlen;
+        self.k[..outlen].fill(0x00);
+        self.v[..outlen].fill(0x01);
+        self.hmac.set_key(&self.k[..outlen])?;
+
+        self._update(&[entropy, nonce, personalization])
+    }
+
+    fn _reseed(&mut self, entropy: &[u8], additional: &[u8]) -> Result<Success, Error> {
+        self._update(&[entropy, additional])
+    }
+
+    fn _generate(&mut self, output: &mut [u8], additional: &[u8]) -> Result<Success, Error> {
+        let outlen = self.outlen;
+
+        if !additional.is_empty() {
+            self._update(&[additional])?;
+        }
+
+        for chunk in output.chunks_mut(outlen) {
+            self._next_v()?;
+            chunk.copy_from_slice(&self.v[..chunk.len()]);
+        }
+
+        self._update(&[additional])
+    }
+
+    fn _clear(&mut self) {
+        self.k.fill(0);
+        self.v.fill(0);
+        let _ = self.hmac.set_key(&[]);
+    }
+}
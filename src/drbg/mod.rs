@@ -0,0 +1,235 @@
+mod hash_drbg;
+mod hmac_drbg;
+mod ctr_drbg;
+
+pub use crate::drbg::hash_drbg::HashDRBG;
+pub use crate::drbg::hmac_drbg::HMACDRBG;
+pub use crate::drbg::ctr_drbg::{CTRDRBG, CTRDRBG128, CTRDRBG192, CTRDRBG256};
+
+use crate::common::api::EntropySource;
+use crate::common::{Success, Error};
+use crate::hash::SHA;
+
+/* Limits from SP 800-90A, table 2 and 3. */
+pub const DRBG_MAX_RESEED_INTERVAL: u64 = 1 << 48;
+pub const DRBG_MAX_REQUEST_SIZE: usize = (1 << 19) / 8;
+
+/* Entropy input plus nonce requested from an entropy source. The largest is a
+ * CTR_DRBG with AES-256 without derivation function (seedlen = 48 bytes). */
+const DRBG_MAX_ENTROPY_INPUT: usize = 64;
+
+/// State shared by all DRBG mechanisms.
+#[derive(Debug, Clone)]
+pub struct DRBGDataCtx {
+    reseed_counter: u64,
+    reseed_interval: u64,
+    prediction_resistance: bool,
+    instantiated: bool,
+}
+
+impl DRBGDataCtx {
+    fn new() -> Self {
+        DRBGDataCtx {
+            reseed_counter: 0,
+            reseed_interval: DRBG_MAX_RESEED_INTERVAL,
+            prediction_resistance: false,
+            instantiated: false,
+        }
+    }
+}
+
+/* The mechanism specific algorithms behind the state machine of `DRBG`. The trait
+ * is public in a private module, so it can be a supertrait of `DRBG` without
+ * anything outside the crate being able to call or implement it. */
+mod sealed {
+    use crate::common::{Success, Error};
+    use crate::drbg::{DRBG, DRBGDataCtx};
+
+    pub trait Mechanism {
+        fn _data(&mut self) -> &mut DRBGDataCtx;
+
+        fn _instantiate(&mut self, entropy: &[u8], nonce: &[u8], personalization: &[u8]) -> Result<Success, Error>;
+
+        fn _reseed(&mut self, entropy: &[u8], additional: &[u8]) -> Result<Success, Error>;
+
+        fn _generate(&mut self, output: &mut [u8], additional: &[u8]) -> Result<Success, Error>;
+
+        fn _clear(&mut self);
+
+        /* Number of bytes of entropy input and nonce taken from an entropy source. */
+        fn _entropy_len(&self) -> usize where Self: DRBG {
+            self.security_strength() / 8
+        }
+
+        fn _nonce_len(&self) -> usize where Self: DRBG {
+            self.security_strength() / 16
+        }
+    }
+}
+
+use crate::drbg::sealed::Mechanism;
+
+/// Deterministic random bit generators as specified in NIST SP 800-90A.
+///
+/// The functions enforce the checks of the instantiate/reseed/generate/uninstantiate
+/// state machine; the mechanism specific algorithms are internal to the crate, so
+/// the trait can't be implemented outside of it.
+///
+/// Entropy input and nonce are either passed in by the caller or pulled from an
+/// `EntropySource` by the `*_from` variants. If the DRBG was instantiated with
+/// prediction resistance, `generate` refuses to produce output and `generate_pr`
+/// (or `generate_from`) has to be used instead, which reseeds with fresh entropy
+/// before every request.
+pub trait DRBG: Mechanism {
+    /// Security strength in bits.
+    fn security_strength(&self) -> usize;
+
+    fn instantiate(
+        &mut self,
+        entropy: &[u8],
+        nonce: &[u8],
+        personalization: &[u8],
+        prediction_resistance: bool,
+    ) -> Result<Success, Error> {
+        if entropy.len() * 8 < self.security_strength() {
+            return Err(Error::Err);
+        }
+
+        self._instantiate(entropy, nonce, personalization)?;
+
+        let data = self._data();
+        data.reseed_counter = 1;
+        data.prediction_resistance = prediction_resistance;
+        data.instantiated = true;
+
+        Ok(Success::OK)
+    }
+
+    fn reseed(&mut self, entropy: &[u8], additional: &[u8]) -> Result<Success, Error> {
+        if !self._data().instantiated || entropy.len() * 8 < self.security_strength() {
+            return Err(Error::Err);
+        }
+
+        self._reseed(entropy, additional)?;
+        self._data().reseed_counter = 1;
+
+        Ok(Success::OK)
+    }
+
+    fn generate(&mut self, output: &mut [u8], additional: &[u8]) -> Result<Success, Error> {
+        let data = self._data();
+        if !data.instantiated || output.len() > DRBG_MAX_REQUEST_SIZE {
+            return Err(Error::Err);
+        }
+        if data.prediction_resistance || data.reseed_counter > data.reseed_interval {
+            return Err(Error::ReseedRequired);
+        }
+
+        self._generate(output, additional)?;
+        self._data().reseed_counter += 1;
+
+        Ok(Success::OK)
+    }
+
+    /// Reseeds with `entropy` and `additional` and generates output afterwards.
+    /// This is the generate function of SP 800-90A with the prediction resistance
+    /// flag set.
+    fn generate_pr(&mut self, entropy: &[u8], output: &mut [u8], additional: &[u8]) -> Result<Success, Error> {
+        if output.len() > DRBG_MAX_REQUEST_SIZE {
+            return Err(Error::Err);
+        }
+
+        self.reseed(entropy, additional)?;
+        self._generate(output, &[])?;
+        self._data().reseed_counter += 1;
+
+        Ok(Success::OK)
+    }
+
+    fn uninstantiate(&mut self) {
+        self._clear();
+
+        let data = self._data();
+        data.reseed_counter = 0;
+        data.prediction_resistance = false;
+        data.instantiated = false;
+    }
+
+    /// Like `instantiate`, but takes entropy input and nonce from `source`.
+    fn instantiate_from(
+        &mut self,
+        source: &mut dyn EntropySource,
+        personalization: &[u8],
+        prediction_resistance: bool,
+    ) -> Result<Success, Error> {
+        let (elen, nlen) = (self._entropy_len(), self._nonce_len());
+        let mut entropy = [0u8; DRBG_MAX_ENTROPY_INPUT];
+        source.fill_entropy(&mut entropy[..elen + nlen])?;
+
+        let ret = self.instantiate(&entropy[..elen], &entropy[elen..elen + nlen], personalization, prediction_resistance);
+        entropy.fill(0);
+
+        ret
+    }
+
+    /// Like `reseed`, but takes the entropy input from `source`.
+    fn reseed_from(&mut self, source: &mut dyn EntropySource, additional: &[u8]) -> Result<Success, Error> {
+        let elen = self._entropy_len();
+        let mut entropy = [0u8; DRBG_MAX_ENTROPY_INPUT];
+        source.fill_entropy(&mut entropy[..elen])?;
+
+        let ret = self.reseed(&entropy[..elen], additional);
+        entropy.fill(0);
+
+        ret
+    }
+
+    /// Generates output and reseeds from `source` whenever that is required, i.e.
+    /// before every request with prediction resistance or once the reseed interval
+    /// is exhausted.
+    fn generate_from(
+        &mut self,
+        source: &mut dyn EntropySource,
+        output: &mut [u8],
+        additional: &[u8],
+    ) -> Result<Success, Error> {
+        if self._data().prediction_resistance {
+            let elen = self._entropy_len();
+            let mut entropy = [0u8; DRBG_MAX_ENTROPY_INPUT];
+            source.fill_entropy(&mut entropy[..elen])?;
+
+            let ret = self.generate_pr(&entropy[..elen], output, additional);
+            entropy.fill(0);
+
+            return ret;
+        }
+
+        match self.generate(output, additional) {
+            Err(Error::ReseedRequired) => {
+                self.reseed_from(source, additional)?;
+                self.generate(output, &[])
+            },
+            ret => ret,
+        }
+    }
+
+    /// Lowers the number of generate requests allowed between two reseeds.
+    fn set_reseed_interval(&mut self, interval: u64) -> Result<Success, Error> {
+        if interval == 0 || interval > DRBG_MAX_RESEED_INTERVAL {
+            return Err(Error::Err);
+        }
+
+        self._data().reseed_interval = interval;
+
+        Ok(Success::OK)
+    }
+}
+
+/* Highest security strength (in bits) a hash function supports in a DRBG, see SP 800-57. */
+fn _hash_security_strength(hash: &SHA) -> usize {
+    match hash.digest_size() {
+        20 => 128,
+        28 => 192,
+        _ => 256,
+    }
+}
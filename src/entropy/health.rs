@@ -0,0 +1,165 @@
+use crate::common::api::EntropySource;
+use crate::common::{Success, Error};
+
+/* Window size of the Adaptive Proportion Test for non-binary samples (SP 800-90B, 4.4.2). */
+pub const APT_WINDOW_SIZE: usize = 512;
+
+/* Cutoffs for a false positive probability of 2^-20 and 1 to 8 bits of min-entropy
+ * per sample: RCT C = 1 + ceil(20 / H), APT C = 1 + CRITBINOM(512, 2^-H, 1 - 2^-20). */
+static RCT_CUTOFFS: [usize; 8] = [21, 11, 8, 6, 5, 5, 4, 4];
+static APT_CUTOFFS: [usize; 8] = [311, 177, 103, 62, 39, 25, 18, 13];
+
+/// Repetition Count Test (SP 800-90B, section 4.4.1). Trips once the same sample
+/// is seen `cutoff` times in a row.
+#[derive(Debug, Clone)]
+pub struct RepetitionCountTest {
+    cutoff: usize,
+    last: u8,
+    count: usize,
+}
+
+impl RepetitionCountTest {
+    pub fn new(cutoff: usize) -> Result<Self, Error> {
+        if cutoff < 2 {
+            return Err(Error::Err);
+        }
+
+        Ok(RepetitionCountTest { cutoff, last: 0, count: 0 })
+    }
+
+    /// Feeds the next sample to the test.
+    pub fn update(&mut self, sample: u8) -> Result<Success, Error> {
+        if self.count > 0 && sample == self.last {
+            self.count += 1;
+            if self.count >= self.cutoff {
+                return Err(Error::HealthTest);
+            }
+        } else {
+            self.last = sample;
+            self.count = 1;
+        }
+
+        Ok(Success::OK)
+    }
+}
+
+/// Adaptive Proportion Test (SP 800-90B, section 4.4.2). Trips once the first
+/// sample of a window of `window` samples occurs `cutoff` times within it.
+#[derive(Debug, Clone)]
+pub struct AdaptiveProportionTest {
+    cutoff: usize,
+    window: usize,
+    reference: u8,
+    count: usize,
+    seen: usize,
+}
+
+impl AdaptiveProportionTest {
+    pub fn new(cutoff: usize, window: usize) -> Result<Self, Error> {
+        if cutoff < 2 || cutoff > window {
+            return Err(Error::Err);
+        }
+
+        Ok(AdaptiveProportionTest { cutoff, window, reference: 0, count: 0, seen: 0 })
+    }
+
+    /// Feeds the next sample to the test.
+    pub fn update(&mut self, sample: u8) -> Result<Success, Error> {
+        if self.seen == 0 {
+            self.reference = sample;
+            self.count = 1;
+        } else if sample == self.reference {
+            self.count += 1;
+            if self.count >= self.cutoff {
+                return Err(Error::HealthTest);
+            }
+        }
+
+        self.seen += 1;
+        if self.seen == self.window {
+            self.seen = 0;
+        }
+
+        Ok(Success::OK)
+    }
+}
+
+/// Cutoffs of the continuous health tests.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HealthTestConfig {
+    pub rct_cutoff: usize,
+    pub apt_cutoff: usize,
+    pub apt_window: usize,
+}
+
+impl HealthTestConfig {
+    /// Cutoffs for a source that is claimed to deliver `bits` (1 to 8) bits of
+    /// min-entropy per byte, with a false positive probability of 2^-20.
+    pub fn for_min_entropy(bits: usize) -> Result<Self, Error> {
+        if !(1..=8).contains(&bits) {
+            return Err(Error::Err);
+        }
+
+        Ok(HealthTestConfig {
+            rct_cutoff: RCT_CUTOFFS[bits - 1],
+            apt_cutoff: APT_CUTOFFS[bits - 1],
+            apt_window: APT_WINDOW_SIZE,
+        })
+    }
+}
+
+/// Wraps an `EntropySource` whose output bytes are raw noise samples and runs the
+/// Repetition Count and Adaptive Proportion Tests on everything it delivers.
+///
+/// Fails closed: once a test trips the output is wiped, `Error::HealthTest` is
+/// returned and every later request fails as well.
+#[derive(Debug, Clone)]
+pub struct HealthTested<E: EntropySource> {
+    source: E,
+    rct: RepetitionCountTest,
+    apt: AdaptiveProportionTest,
+    failed: bool,
+}
+
+impl<E: EntropySource> HealthTested<E> {
+    pub fn new(source: E, config: HealthTestConfig) -> Result<Self, Error> {
+        Ok(HealthTested {
+            source,
+            rct: RepetitionCountTest::new(config.rct_cutoff)?,
+            apt: AdaptiveProportionTest::new(config.apt_cutoff, config.apt_window)?,
+            failed: false,
+        })
+    }
+
+    /// True once a health test has tripped.
+    pub fn failed(&self) -> bool {
+        self.failed
+    }
+
+    fn _test(&mut self, output: &[u8]) -> Result<Success, Error> {
+        for &sample in output {
+            self.rct.update(sample)?;
+            self.apt.update(sample)?;
+        }
+
+        Ok(Success::OK)
+    }
+}
+
+impl<E: EntropySource> EntropySource for HealthTested<E> {
+    fn fill_entropy(&mut self, output: &mut [u8]) -> Result<Success, Error> {
+        if self.failed {
+            return Err(Error::HealthTest);
+        }
+
+        let ret = self.source.fill_entropy(output).and_then(|_| self._test(output));
+        if ret.is_err() {
+            output.fill(0);
+        }
+        if ret == Err(Error::HealthTest) {
+            self.failed = true;
+        }
+
+        ret
+    }
+}
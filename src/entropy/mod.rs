@@ -0,0 +1,72 @@
+mod health;
+
+pub use crate::entropy::health::{
+    AdaptiveProportionTest, HealthTestConfig, HealthTested, RepetitionCountTest, APT_WINDOW_SIZE,
+};
+
+use crate::common::api::EntropySource;
+use crate::common::{Success, Error};
+
+/// Entropy from the Linux kernel through the getrandom(2) syscall.
+///
+/// Blocks until the kernel's entropy pool has been initialized, afterwards it
+/// never blocks.
+#[cfg(all(feature = "std", target_os = "linux"))]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct GetRandom;
+
+#[cfg(all(feature = "std", target_os = "linux"))]
+impl EntropySource for GetRandom {
+    fn fill_entropy(&mut self, output: &mut [u8]) -> Result<Success, Error> {
+        let mut pos = 0;
+
+        while pos < output.len() {
+            let rest = &mut output[pos..];
+            let ret = unsafe { libc::getrandom(rest.as_mut_ptr() as *mut libc::c_void, rest.len(), 0) };
+
+            if ret < 0 {
+                /* Interrupted by a signal before any byte was written, try again. */
+                if std::io::Error::last_os_error().raw_os_error() == Some(libc::EINTR) {
+                    continue;
+                }
+                return Err(Error::EntropyFailure);
+            }
+            pos += ret as usize;
+        }
+
+        Ok(Success::OK)
+    }
+}
+
+/// Deterministic entropy source that hands out the bytes of a fixed buffer in
+/// order. Fails once the buffer is exhausted. Only meant for tests and known
+/// answer self-tests.
+#[derive(Debug, Clone)]
+pub struct TestEntropy<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> TestEntropy<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        TestEntropy { data, pos: 0 }
+    }
+
+    /// Number of bytes that have not been handed out yet.
+    pub fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
+}
+
+impl EntropySource for TestEntropy<'_> {
+    fn fill_entropy(&mut self, output: &mut [u8]) -> Result<Success, Error> {
+        if output.len() > self.remaining() {
+            return Err(Error::EntropyFailure);
+        }
+
+        output.copy_from_slice(&self.data[self.pos..self.pos + output.len()]);
+        self.pos += output.len();
+
+        Ok(Success::OK)
+    }
+}
@@ -0,0 +1,284 @@
+use crate::hash::Operations;
+use crate::hash::sha1::{SHA1Ctx, SHA1_STATE_SIZE};
+use crate::hash::sha2::{SHA256Ctx, SHA2_STATE_SIZE};
+
+/// Implementation of the SHA-1 and SHA-256/SHA-224 block function.
+///
+/// All backends compute the same digests. `SHABackend::detect()` picks the fastest
+/// one the CPU supports. With the `std` feature the CPU is queried at runtime,
+/// without it only extensions enabled at compile time (e.g. with
+/// `-C target-feature=+sha,+sse4.1`) are used.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SHABackend {
+    /// Portable Rust, available everywhere.
+    Scalar,
+    /// The x86 SHA extensions (SHA-NI).
+    SHANI,
+    /// The SHA instructions of the ARMv8 cryptography extensions.
+    ARMv8,
+}
+
+impl SHABackend {
+    /// The fastest backend supported by the CPU.
+    pub fn detect() -> Self {
+        if SHABackend::SHANI.is_supported() {
+            SHABackend::SHANI
+        } else if SHABackend::ARMv8.is_supported() {
+            SHABackend::ARMv8
+        } else {
+            SHABackend::Scalar
+        }
+    }
+
+    /// Whether the backend can be used on this CPU.
+    pub fn is_supported(&self) -> bool {
+        match self {
+            SHABackend::Scalar => true,
+            SHABackend::SHANI  => _shani_supported(),
+            SHABackend::ARMv8  => _armv8_supported(),
+        }
+    }
+
+    /* Callers only hold backends that passed `is_supported`, which makes the calls
+     * of the intrinsics safe. */
+    pub(crate) fn _sha256(&self, state: &mut [u32; SHA2_STATE_SIZE], input: &[u8]) {
+        match self {
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            SHABackend::SHANI => unsafe { x86::_sha256(state, input) },
+            #[cfg(target_arch = "aarch64")]
+            SHABackend::ARMv8 => unsafe { aarch64::_sha256(state, input) },
+            _ => SHA256Ctx::_transform(state, input),
+        }
+    }
+
+    pub(crate) fn _sha1(&self, state: &mut [u32; SHA1_STATE_SIZE], input: &[u8]) {
+        match self {
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            SHABackend::SHANI => unsafe { x86::_sha1(state, input) },
+            #[cfg(target_arch = "aarch64")]
+            SHABackend::ARMv8 => unsafe { aarch64::_sha1(state, input) },
+            _ => SHA1Ctx::_transform(state, input),
+        }
+    }
+}
+
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
+fn _shani_supported() -> bool {
+    std::is_x86_feature_detected!("sha")
+        && std::is_x86_feature_detected!("ssse3")
+        && std::is_x86_feature_detected!("sse4.1")
+}
+
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), not(feature = "std")))]
+fn _shani_supported() -> bool {
+    cfg!(all(target_feature = "sha", target_feature = "ssse3", target_feature = "sse4.1"))
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+fn _shani_supported() -> bool {
+    false
+}
+
+#[cfg(all(target_arch = "aarch64", feature = "std"))]
+fn _armv8_supported() -> bool {
+    std::arch::is_aarch64_feature_detected!("sha2")
+}
+
+#[cfg(all(target_arch = "aarch64", not(feature = "std")))]
+fn _armv8_supported() -> bool {
+    cfg!(target_feature = "sha2")
+}
+
+#[cfg(not(target_arch = "aarch64"))]
+fn _armv8_supported() -> bool {
+    false
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod x86 {
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::*;
+    use crate::hash::sha1::SHA1_BLOCKSIZE;
+    use crate::hash::sha2::{SHA256_BLOCKSIZE, K256};
+
+    /* The state is kept as ABEF and CDGH, the layout of sha256rnds2. Each call of it
+     * does two rounds with the message words plus constants in the low half of the
+     * third operand. */
+    #[target_feature(enable = "sha,sse2,ssse3,sse4.1")]
+    pub(super) unsafe fn _sha256(state: &mut [u32; 8], input: &[u8]) {
+        /* Byte swap of each word. */
+        let bswap = _mm_set_epi64x(0x0c0d0e0f08090a0b, 0x0405060700010203);
+
+        let dcba = _mm_loadu_si128(state.as_ptr() as *const __m128i);
+        let hgfe = _mm_loadu_si128(state.as_ptr().add(4) as *const __m128i);
+        let cdab = _mm_shuffle_epi32(dcba, 0xb1);
+        let efgh = _mm_shuffle_epi32(hgfe, 0x1b);
+        let mut abef = _mm_alignr_epi8(cdab, efgh, 8);
+        let mut cdgh = _mm_blend_epi16(efgh, cdab, 0xf0);
+
+        for block in input.chunks_exact(SHA256_BLOCKSIZE) {
+            let (abef_save, cdgh_save) = (abef, cdgh);
+
+            let mut w = [_mm_setzero_si128(); 4];
+            for (i, wi) in w.iter_mut().enumerate() {
+                *wi = _mm_shuffle_epi8(_mm_loadu_si128(block.as_ptr().add(16 * i) as *const __m128i), bswap);
+            }
+
+            /* Four rounds per iteration, W[4i..4i + 4] replaces W[4i - 16..4i - 12]. */
+            for i in 0..16 {
+                if i >= 4 {
+                    let t = _mm_add_epi32(_mm_sha256msg1_epu32(w[i % 4], w[(i + 1) % 4]),
+                                          _mm_alignr_epi8(w[(i + 3) % 4], w[(i + 2) % 4], 4));
+                    w[i % 4] = _mm_sha256msg2_epu32(t, w[(i + 3) % 4]);
+                }
+
+                let wk = _mm_add_epi32(w[i % 4], _mm_loadu_si128(K256.as_ptr().add(4 * i) as *const __m128i));
+                cdgh = _mm_sha256rnds2_epu32(cdgh, abef, wk);
+                abef = _mm_sha256rnds2_epu32(abef, cdgh, _mm_shuffle_epi32(wk, 0x0e));
+            }
+
+            abef = _mm_add_epi32(abef, abef_save);
+            cdgh = _mm_add_epi32(cdgh, cdgh_save);
+        }
+
+        let feba = _mm_shuffle_epi32(abef, 0x1b);
+        let dchg = _mm_shuffle_epi32(cdgh, 0xb1);
+        _mm_storeu_si128(state.as_mut_ptr() as *mut __m128i, _mm_blend_epi16(feba, dchg, 0xf0));
+        _mm_storeu_si128(state.as_mut_ptr().add(4) as *mut __m128i, _mm_alignr_epi8(dchg, feba, 8));
+    }
+
+    /* ABCD is kept with A in the highest lane, and E only exists added to the message
+     * words: sha1nexte derives it from A four rounds earlier. */
+    #[target_feature(enable = "sha,sse2,ssse3,sse4.1")]
+    pub(super) unsafe fn _sha1(state: &mut [u32; 5], input: &[u8]) {
+        /* Byte swap of the whole vector, W[0] ends up in the highest lane. */
+        let bswap = _mm_set_epi64x(0x0001020304050607, 0x08090a0b0c0d0e0f);
+
+        let mut abcd = _mm_set_epi32(state[0] as i32, state[1] as i32, state[2] as i32, state[3] as i32);
+        let mut e = _mm_set_epi32(state[4] as i32, 0, 0, 0);
+
+        for block in input.chunks_exact(SHA1_BLOCKSIZE) {
+            let abcd_save = abcd;
+
+            let mut w = [_mm_setzero_si128(); 4];
+            for (i, wi) in w.iter_mut().enumerate() {
+                *wi = _mm_shuffle_epi8(_mm_loadu_si128(block.as_ptr().add(16 * i) as *const __m128i), bswap);
+            }
+
+            let mut wk = _mm_add_epi32(e, w[0]);
+            for i in 0..20 {
+                let prev = abcd;
+                abcd = match i / 5 {
+                    0 => _mm_sha1rnds4_epu32(abcd, wk, 0),
+                    1 => _mm_sha1rnds4_epu32(abcd, wk, 1),
+                    2 => _mm_sha1rnds4_epu32(abcd, wk, 2),
+                    _ => _mm_sha1rnds4_epu32(abcd, wk, 3),
+                };
+
+                /* W[4i + 4..4i + 8] replaces W[4i - 12..4i - 8]. */
+                if i < 19 {
+                    let j = i + 1;
+                    if j >= 4 {
+                        let t = _mm_xor_si128(_mm_sha1msg1_epu32(w[j % 4], w[(j + 1) % 4]), w[(j + 2) % 4]);
+                        w[j % 4] = _mm_sha1msg2_epu32(t, w[(j + 3) % 4]);
+                    }
+                    wk = _mm_sha1nexte_epu32(prev, w[j % 4]);
+                } else {
+                    e = _mm_sha1nexte_epu32(prev, e);
+                }
+            }
+
+            abcd = _mm_add_epi32(abcd, abcd_save);
+        }
+
+        state[0] = _mm_extract_epi32(abcd, 3) as u32;
+        state[1] = _mm_extract_epi32(abcd, 2) as u32;
+        state[2] = _mm_extract_epi32(abcd, 1) as u32;
+        state[3] = _mm_extract_epi32(abcd, 0) as u32;
+        state[4] = _mm_extract_epi32(e, 3) as u32;
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+mod aarch64 {
+    use core::arch::aarch64::*;
+    use crate::hash::sha1::SHA1_BLOCKSIZE;
+    use crate::hash::sha2::{SHA256_BLOCKSIZE, K256};
+
+    static K1: [u32; 4] = [0x5a827999, 0x6ed9eba1, 0x8f1bbcdc, 0xca62c1d6];
+
+    #[target_feature(enable = "neon,sha2")]
+    pub(super) unsafe fn _sha256(state: &mut [u32; 8], input: &[u8]) {
+        let mut abcd = vld1q_u32(state.as_ptr());
+        let mut efgh = vld1q_u32(state.as_ptr().add(4));
+
+        for block in input.chunks_exact(SHA256_BLOCKSIZE) {
+            let (abcd_save, efgh_save) = (abcd, efgh);
+
+            let mut w = [vdupq_n_u32(0); 4];
+            for (i, wi) in w.iter_mut().enumerate() {
+                *wi = vreinterpretq_u32_u8(vrev32q_u8(vld1q_u8(block.as_ptr().add(16 * i))));
+            }
+
+            /* Four rounds per iteration, W[4i..4i + 4] replaces W[4i - 16..4i - 12]. */
+            for i in 0..16 {
+                if i >= 4 {
+                    w[i % 4] = vsha256su1q_u32(vsha256su0q_u32(w[i % 4], w[(i + 1) % 4]),
+                                               w[(i + 2) % 4], w[(i + 3) % 4]);
+                }
+
+                let wk = vaddq_u32(w[i % 4], vld1q_u32(K256.as_ptr().add(4 * i)));
+                let abcd_prev = abcd;
+                abcd = vsha256hq_u32(abcd_prev, efgh, wk);
+                efgh = vsha256h2q_u32(efgh, abcd_prev, wk);
+            }
+
+            abcd = vaddq_u32(abcd, abcd_save);
+            efgh = vaddq_u32(efgh, efgh_save);
+        }
+
+        vst1q_u32(state.as_mut_ptr(), abcd);
+        vst1q_u32(state.as_mut_ptr().add(4), efgh);
+    }
+
+    /* E of the next four rounds is A rotated by 30, computed by sha1h. */
+    #[target_feature(enable = "neon,sha2")]
+    pub(super) unsafe fn _sha1(state: &mut [u32; 5], input: &[u8]) {
+        let mut abcd = vld1q_u32(state.as_ptr());
+        let mut e = state[4];
+
+        for block in input.chunks_exact(SHA1_BLOCKSIZE) {
+            let (abcd_save, e_save) = (abcd, e);
+
+            let mut w = [vdupq_n_u32(0); 4];
+            for (i, wi) in w.iter_mut().enumerate() {
+                *wi = vreinterpretq_u32_u8(vrev32q_u8(vld1q_u8(block.as_ptr().add(16 * i))));
+            }
+
+            /* Four rounds per iteration, W[4i..4i + 4] replaces W[4i - 16..4i - 12]. */
+            for i in 0..20 {
+                if i >= 4 {
+                    w[i % 4] = vsha1su1q_u32(vsha1su0q_u32(w[i % 4], w[(i + 1) % 4], w[(i + 2) % 4]),
+                                             w[(i + 3) % 4]);
+                }
+
+                let wk = vaddq_u32(w[i % 4], vdupq_n_u32(K1[i / 5]));
+                let e_next = vsha1h_u32(vgetq_lane_u32(abcd, 0));
+                abcd = match i / 5 {
+                    0     => vsha1cq_u32(abcd, e, wk),
+                    2     => vsha1mq_u32(abcd, e, wk),
+                    _     => vsha1pq_u32(abcd, e, wk),
+                };
+                e = e_next;
+            }
+
+            abcd = vaddq_u32(abcd, abcd_save);
+            e = e.wrapping_add(e_save);
+        }
+
+        vst1q_u32(state.as_mut_ptr(), abcd);
+        state[4] = e;
+    }
+}
@@ -0,0 +1,403 @@
+use crate::common::api::{MAC, StreamingAPI, DefaultInit, SingleInputUpdate, SingleOutputFinish};
+use crate::common::{Success, Error};
+use num::traits::WrappingAdd;
+use num_traits::PrimInt;
+
+pub const BLAKE2B_BLOCKSIZE: usize = 128;
+pub const BLAKE2B_DIGEST_SIZE: usize = 64;
+pub const BLAKE2B_KEY_SIZE: usize = 64;
+pub const BLAKE2B_SALT_SIZE: usize = 16;
+pub const BLAKE2B_PERSONAL_SIZE: usize = 16;
+
+pub const BLAKE2S_BLOCKSIZE: usize = 64;
+pub const BLAKE2S_DIGEST_SIZE: usize = 32;
+pub const BLAKE2S_KEY_SIZE: usize = 32;
+pub const BLAKE2S_SALT_SIZE: usize = 8;
+pub const BLAKE2S_PERSONAL_SIZE: usize = 8;
+
+const SIGMA: [[usize; 16]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+/// Parameter block of BLAKE2 (RFC 7693 and section 2.8 of the BLAKE2 paper).
+///
+/// `new` gives sequential hashing without key, salt and personalization. Salt and
+/// personalization shorter than their field are padded with zeros. The tree
+/// parameters are only needed to hash the nodes of a tree: `last_node` marks the
+/// last node of a layer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BLAKE2Params<'a> {
+    pub digest_len: usize,
+    pub key: &'a [u8],
+    pub salt: &'a [u8],
+    pub personal: &'a [u8],
+    pub fanout: u8,
+    pub depth: u8,
+    pub leaf_length: u32,
+    pub node_offset: u64,
+    pub node_depth: u8,
+    pub inner_length: u8,
+    pub last_node: bool,
+}
+
+impl<'a> BLAKE2Params<'a> {
+    pub fn new(digest_len: usize) -> Self {
+        BLAKE2Params {
+            digest_len,
+            key: &[],
+            salt: &[],
+            personal: &[],
+            fanout: 1,
+            depth: 1,
+            leaf_length: 0,
+            node_offset: 0,
+            node_depth: 0,
+            inner_length: 0,
+            last_node: false,
+        }
+    }
+}
+
+/* Only u32 and u64 implement `BLAKE2Word`. The supertrait is public in a private
+ * module, so the bound can't be met outside the crate. */
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for u32 {}
+    impl Sealed for u64 {}
+}
+
+/// The parts in which BLAKE2b and BLAKE2s differ, by word size: `u64` for BLAKE2b
+/// and `u32` for BLAKE2s. Implemented for these two only.
+pub trait BLAKE2Word: sealed::Sealed + PrimInt + WrappingAdd {
+    const BYTES: usize;
+    const ROUNDS: usize;
+    const ROTATIONS: [u32; 4];
+    const IV: [Self; 8];
+
+    fn load(bytes: &[u8]) -> Self;
+
+    fn store(self, bytes: &mut [u8]);
+
+    fn truncate(v: u128) -> Self;
+}
+
+impl BLAKE2Word for u64 {
+    const BYTES: usize = 8;
+    const ROUNDS: usize = 12;
+    const ROTATIONS: [u32; 4] = [32, 24, 16, 63];
+    const IV: [u64; 8] = [
+        0x6a09e667f3bcc908, 0xbb67ae8584caa73b, 0x3c6ef372fe94f82b, 0xa54ff53a5f1d36f1,
+        0x510e527fade682d1, 0x9b05688c2b3e6c1f, 0x1f83d9abfb41bd6b, 0x5be0cd19137e2179,
+    ];
+
+    fn load(bytes: &[u8]) -> Self {
+        u64::from_le_bytes(bytes[..8].try_into().unwrap())
+    }
+
+    fn store(self, bytes: &mut [u8]) {
+        bytes.copy_from_slice(&self.to_le_bytes()[..bytes.len()]);
+    }
+
+    fn truncate(v: u128) -> Self {
+        v as u64
+    }
+}
+
+impl BLAKE2Word for u32 {
+    const BYTES: usize = 4;
+    const ROUNDS: usize = 10;
+    const ROTATIONS: [u32; 4] = [16, 12, 8, 7];
+    const IV: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+    ];
+
+    fn load(bytes: &[u8]) -> Self {
+        u32::from_le_bytes(bytes[..4].try_into().unwrap())
+    }
+
+    fn store(self, bytes: &mut [u8]) {
+        bytes.copy_from_slice(&self.to_le_bytes()[..bytes.len()]);
+    }
+
+    fn truncate(v: u128) -> Self {
+        v as u32
+    }
+}
+
+/// State of BLAKE2b (`W` = u64) or BLAKE2s (`W` = u32).
+///
+/// The key is kept so that `init` can start the next message. The last block is
+/// only compressed in `finish` since it carries the finalization flag.
+#[derive(Debug, Clone)]
+pub struct BLAKE2Ctx<W: BLAKE2Word, const BLOCKSIZE: usize> {
+    h0: [W; 8],
+    h: [W; 8],
+    /* Number of message bytes compressed so far, including the key block. */
+    t: u128,
+    buffer: [u8; BLOCKSIZE],
+    rem_pos: usize,
+    key: [u8; BLOCKSIZE],
+    key_len: usize,
+    digest_len: usize,
+    last_node: bool,
+}
+
+pub type BLAKE2bCtx = BLAKE2Ctx<u64, BLAKE2B_BLOCKSIZE>;
+pub type BLAKE2sCtx = BLAKE2Ctx<u32, BLAKE2S_BLOCKSIZE>;
+
+impl<W: BLAKE2Word, const BLOCKSIZE: usize> BLAKE2Ctx<W, BLOCKSIZE> {
+    pub(crate) fn _new(params: &BLAKE2Params) -> Result<Self, Error> {
+        /* Digest and key are at most 8 words, salt and personalization 2 words each. */
+        let max_digest = 8 * W::BYTES;
+        if !(1..=max_digest).contains(&params.digest_len)
+            || params.key.len() > max_digest
+            || params.salt.len() > 2 * W::BYTES
+            || params.personal.len() > 2 * W::BYTES
+            || params.inner_length as usize > max_digest
+            || (W::BYTES == 4 && params.node_offset >> 48 != 0)
+        {
+            return Err(Error::Err);
+        }
+
+        /* BLAKE2b: node offset in bytes 8-15, node depth and inner length in 16 and 17,
+         * salt and personalization from byte 32 on. BLAKE2s: 48-bit node offset in
+         * bytes 8-13, node depth and inner length in 14 and 15, salt and
+         * personalization from byte 16 on. */
+        let mut block = [0u8; 64];
+        let offset_len = if W::BYTES == 8 { 8 } else { 6 };
+        block[0] = params.digest_len as u8;
+        block[1] = params.key.len() as u8;
+        block[2] = params.fanout;
+        block[3] = params.depth;
+        block[4..8].copy_from_slice(&params.leaf_length.to_le_bytes());
+        block[8..8 + offset_len].copy_from_slice(&params.node_offset.to_le_bytes()[..offset_len]);
+        block[8 + offset_len] = params.node_depth;
+        block[9 + offset_len] = params.inner_length;
+        block[4 * W::BYTES..4 * W::BYTES + params.salt.len()].copy_from_slice(params.salt);
+        block[6 * W::BYTES..6 * W::BYTES + params.personal.len()].copy_from_slice(params.personal);
+
+        let mut h0 = W::IV;
+        for (h, chunk) in h0.iter_mut().zip(block.chunks_exact(W::BYTES)) {
+            *h = *h ^ W::load(chunk);
+        }
+
+        let mut key = [0u8; BLOCKSIZE];
+        key[..params.key.len()].copy_from_slice(params.key);
+
+        let mut ctx = BLAKE2Ctx {
+            h0,
+            h: h0,
+            t: 0,
+            buffer: [0; BLOCKSIZE],
+            rem_pos: 0,
+            key,
+            key_len: params.key.len(),
+            digest_len: params.digest_len,
+            last_node: params.last_node,
+        };
+        ctx._init();
+
+        Ok(ctx)
+    }
+
+    pub(crate) fn _digest_len(&self) -> usize {
+        self.digest_len
+    }
+
+    /* A key is hashed as a first block of its own, padded with zeros. */
+    pub(crate) fn _init(&mut self) {
+        self.h = self.h0;
+        self.t = 0;
+        self.buffer.fill(0);
+        self.rem_pos = 0;
+        if self.key_len != 0 {
+            self.buffer = self.key;
+            self.rem_pos = BLOCKSIZE;
+        }
+    }
+
+    pub(crate) fn _process(&mut self, mut input: &[u8]) -> Result<Success, Error> {
+        while !input.is_empty() {
+            if self.rem_pos == BLOCKSIZE {
+                self.t += BLOCKSIZE as u128;
+                let block = self.buffer;
+                self._compress(&block, false);
+                self.rem_pos = 0;
+            }
+
+            let take = usize::min(BLOCKSIZE - self.rem_pos, input.len());
+            self.buffer[self.rem_pos..self.rem_pos + take].copy_from_slice(&input[..take]);
+            self.rem_pos += take;
+            input = &input[take..];
+        }
+
+        Ok(Success::OK)
+    }
+
+    /* Writes the digest truncated to the length of the output buffer. */
+    pub(crate) fn _finish(&mut self, output: &mut [u8]) -> Result<Success, Error> {
+        if output.len() > self.digest_len {
+            return Err(Error::Err);
+        }
+
+        self.t += self.rem_pos as u128;
+        self.buffer[self.rem_pos..].fill(0);
+        let block = self.buffer;
+        self._compress(&block, true);
+
+        let mut digest = [0u8; 64];
+        for (h, chunk) in self.h.iter().zip(digest.chunks_exact_mut(W::BYTES)) {
+            h.store(chunk);
+        }
+        let len = usize::min(output.len(), self.digest_len);
+        output[..len].copy_from_slice(&digest[..len]);
+        digest.fill(0);
+        self.buffer.fill(0);
+
+        Ok(Success::OK)
+    }
+
+    fn _compress(&mut self, block: &[u8; BLOCKSIZE], last: bool) {
+        let mut m = [W::zero(); 16];
+        for (w, chunk) in m.iter_mut().zip(block.chunks_exact(W::BYTES)) {
+            *w = W::load(chunk);
+        }
+
+        let mut v = [W::zero(); 16];
+        v[..8].copy_from_slice(&self.h);
+        v[8..].copy_from_slice(&W::IV);
+        v[12] = v[12] ^ W::truncate(self.t);
+        v[13] = v[13] ^ W::truncate(self.t >> (8 * W::BYTES));
+        if last {
+            v[14] = !v[14];
+            if self.last_node {
+                v[15] = !v[15];
+            }
+        }
+
+        for r in 0..W::ROUNDS {
+            let s = &SIGMA[r % 10];
+            _g(&mut v, 0, 4, 8, 12, m[s[0]], m[s[1]]);
+            _g(&mut v, 1, 5, 9, 13, m[s[2]], m[s[3]]);
+            _g(&mut v, 2, 6, 10, 14, m[s[4]], m[s[5]]);
+            _g(&mut v, 3, 7, 11, 15, m[s[6]], m[s[7]]);
+            _g(&mut v, 0, 5, 10, 15, m[s[8]], m[s[9]]);
+            _g(&mut v, 1, 6, 11, 12, m[s[10]], m[s[11]]);
+            _g(&mut v, 2, 7, 8, 13, m[s[12]], m[s[13]]);
+            _g(&mut v, 3, 4, 9, 14, m[s[14]], m[s[15]]);
+        }
+
+        for i in 0..8 {
+            self.h[i] = self.h[i] ^ v[i] ^ v[i + 8];
+        }
+        m.fill(W::zero());
+        v.fill(W::zero());
+    }
+}
+
+fn _g<W: BLAKE2Word>(v: &mut [W; 16], a: usize, b: usize, c: usize, d: usize, x: W, y: W) {
+    let [r1, r2, r3, r4] = W::ROTATIONS;
+    v[a] = v[a].wrapping_add(&v[b]).wrapping_add(&x);
+    v[d] = (v[d] ^ v[a]).rotate_right(r1);
+    v[c] = v[c].wrapping_add(&v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(r2);
+    v[a] = v[a].wrapping_add(&v[b]).wrapping_add(&y);
+    v[d] = (v[d] ^ v[a]).rotate_right(r3);
+    v[c] = v[c].wrapping_add(&v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(r4);
+}
+
+/// BLAKE2b and BLAKE2s (RFC 7693), with the streaming interface of `SHA`.
+///
+/// The digest length is a parameter and part of the hash, a shorter digest is not
+/// a prefix of a longer one; `finish` truncates to a shorter output buffer and fails
+/// on a longer one. With a key BLAKE2 is a MAC. Contexts are ready for a message on
+/// construction and `init` starts the next one with the same parameters.
+#[derive(Debug, Clone)]
+pub enum BLAKE2 {
+    BLAKE2b(BLAKE2bCtx),
+    BLAKE2s(BLAKE2sCtx),
+}
+
+impl BLAKE2 {
+    /// Unkeyed BLAKE2b with a digest of 1 to 64 bytes.
+    pub fn new_blake2b(digest_len: usize) -> Result<Self, Error> {
+        Self::blake2b_with_params(&BLAKE2Params::new(digest_len))
+    }
+
+    /// Unkeyed BLAKE2s with a digest of 1 to 32 bytes.
+    pub fn new_blake2s(digest_len: usize) -> Result<Self, Error> {
+        Self::blake2s_with_params(&BLAKE2Params::new(digest_len))
+    }
+
+    pub fn blake2b_with_params(params: &BLAKE2Params) -> Result<Self, Error> {
+        Ok(BLAKE2::BLAKE2b(BLAKE2bCtx::_new(params)?))
+    }
+
+    pub fn blake2s_with_params(params: &BLAKE2Params) -> Result<Self, Error> {
+        Ok(BLAKE2::BLAKE2s(BLAKE2sCtx::_new(params)?))
+    }
+
+    /// Size of the digest in bytes as set by the parameters.
+    pub fn digest_size(&self) -> usize {
+        match self {
+            BLAKE2::BLAKE2b(ctx) => ctx._digest_len(),
+            BLAKE2::BLAKE2s(ctx) => ctx._digest_len(),
+        }
+    }
+
+    /// Size of the internal message block in bytes.
+    pub fn block_size(&self) -> usize {
+        match self {
+            BLAKE2::BLAKE2b(_) => BLAKE2B_BLOCKSIZE,
+            BLAKE2::BLAKE2s(_) => BLAKE2S_BLOCKSIZE,
+        }
+    }
+}
+
+impl StreamingAPI for BLAKE2 {}
+
+impl MAC for BLAKE2 {
+    fn mac_size(&self) -> usize {
+        self.digest_size()
+    }
+}
+
+impl DefaultInit for BLAKE2 {
+    fn init(&mut self) -> Result<Success, Error> {
+        match self {
+            BLAKE2::BLAKE2b(ctx) => ctx._init(),
+            BLAKE2::BLAKE2s(ctx) => ctx._init(),
+        }
+
+        Ok(Success::OK)
+    }
+}
+
+impl SingleInputUpdate for BLAKE2 {
+    fn update(&mut self, input: &[u8]) -> Result<Success, Error> {
+        match self {
+            BLAKE2::BLAKE2b(ctx) => ctx._process(input),
+            BLAKE2::BLAKE2s(ctx) => ctx._process(input),
+        }
+    }
+}
+
+impl SingleOutputFinish for BLAKE2 {
+    fn finish(&mut self, output: &mut [u8]) -> Result<Success, Error> {
+        match self {
+            BLAKE2::BLAKE2b(ctx) => ctx._finish(output),
+            BLAKE2::BLAKE2s(ctx) => ctx._finish(output),
+        }
+    }
+}
@@ -0,0 +1,400 @@
+use crate::common::api::{MAC, StreamingAPI, DefaultInit, SingleInputUpdate, SingleOutputFinish};
+use crate::common::{Success, Error};
+
+pub const BLAKE3_DIGEST_SIZE: usize = 32;
+pub const BLAKE3_KEY_SIZE: usize = 32;
+pub const BLAKE3_BLOCKSIZE: usize = 64;
+pub const BLAKE3_CHUNK_SIZE: usize = 1024;
+
+const CHUNK_START: u32 = 1 << 0;
+const CHUNK_END: u32 = 1 << 1;
+const PARENT: u32 = 1 << 2;
+const ROOT: u32 = 1 << 3;
+const KEYED_HASH: u32 = 1 << 4;
+const DERIVE_KEY_CONTEXT: u32 = 1 << 5;
+const DERIVE_KEY_MATERIAL: u32 = 1 << 6;
+
+const IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+const MSG_PERMUTATION: [usize; 16] = [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8];
+
+/* One chaining value per level of the tree: 2^54 chunks of 1 KiB exhaust the 64-bit
+ * byte count, which `update` checks. The stack would overflow with 2^55 - 1 chunks. */
+const MAX_DEPTH: usize = 54;
+
+fn _g(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize, x: u32, y: u32) {
+    state[a] = state[a].wrapping_add(state[b]).wrapping_add(x);
+    state[d] = (state[d] ^ state[a]).rotate_right(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_right(12);
+    state[a] = state[a].wrapping_add(state[b]).wrapping_add(y);
+    state[d] = (state[d] ^ state[a]).rotate_right(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_right(7);
+}
+
+/* The compression function: seven rounds over the chaining value, the IV, the
+ * counter, the block length and the flags, with the message words permuted
+ * between rounds. Returns the full 16 words for extended output. */
+fn _compress(cv: &[u32; 8], block: &[u32; 16], counter: u64, block_len: u32, flags: u32) -> [u32; 16] {
+    let mut state = [
+        cv[0], cv[1], cv[2], cv[3], cv[4], cv[5], cv[6], cv[7],
+        IV[0], IV[1], IV[2], IV[3], counter as u32, (counter >> 32) as u32, block_len, flags,
+    ];
+    let mut m = *block;
+
+    for round in 0..7 {
+        _g(&mut state, 0, 4, 8, 12, m[0], m[1]);
+        _g(&mut state, 1, 5, 9, 13, m[2], m[3]);
+        _g(&mut state, 2, 6, 10, 14, m[4], m[5]);
+        _g(&mut state, 3, 7, 11, 15, m[6], m[7]);
+        _g(&mut state, 0, 5, 10, 15, m[8], m[9]);
+        _g(&mut state, 1, 6, 11, 12, m[10], m[11]);
+        _g(&mut state, 2, 7, 8, 13, m[12], m[13]);
+        _g(&mut state, 3, 4, 9, 14, m[14], m[15]);
+        if round < 6 {
+            let prev = m;
+            for (w, p) in m.iter_mut().zip(MSG_PERMUTATION.iter()) {
+                *w = prev[*p];
+            }
+        }
+    }
+    m.fill(0);
+
+    for i in 0..8 {
+        state[i] ^= state[i + 8];
+        state[i + 8] ^= cv[i];
+    }
+
+    state
+}
+
+fn _words<const N: usize>(bytes: &[u8]) -> [u32; N] {
+    let mut words = [0u32; N];
+    for (w, chunk) in words.iter_mut().zip(bytes.chunks_exact(4)) {
+        *w = u32::from_le_bytes(chunk.try_into().unwrap());
+    }
+
+    words
+}
+
+fn _first8(words: &[u32; 16]) -> [u32; 8] {
+    words[..8].try_into().unwrap()
+}
+
+/* The input of a compression that is either a chaining value or, with ROOT, the
+ * output of the hash. */
+#[derive(Debug, Clone)]
+struct Node {
+    cv: [u32; 8],
+    block: [u32; 16],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+}
+
+impl Node {
+    fn _parent(left: &[u32; 8], right: &[u32; 8], key: &[u32; 8], flags: u32) -> Self {
+        let mut block = [0u32; 16];
+        block[..8].copy_from_slice(left);
+        block[8..].copy_from_slice(right);
+
+        Node { cv: *key, block, counter: 0, block_len: BLAKE3_BLOCKSIZE as u32, flags: PARENT | flags }
+    }
+
+    fn _chaining_value(&self) -> [u32; 8] {
+        _first8(&_compress(&self.cv, &self.block, self.counter, self.block_len, self.flags))
+    }
+
+    /* Block `counter` of the extended output. */
+    fn _root_block(&self, counter: u64) -> [u8; BLAKE3_BLOCKSIZE] {
+        let words = _compress(&self.cv, &self.block, counter, self.block_len, self.flags | ROOT);
+        let mut out = [0u8; BLAKE3_BLOCKSIZE];
+        for (o, w) in out.chunks_exact_mut(4).zip(words.iter()) {
+            o.copy_from_slice(&w.to_le_bytes());
+        }
+
+        out
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ChunkState {
+    cv: [u32; 8],
+    counter: u64,
+    block: [u8; BLAKE3_BLOCKSIZE],
+    block_len: usize,
+    blocks_compressed: usize,
+    flags: u32,
+}
+
+impl ChunkState {
+    fn new(key: &[u32; 8], counter: u64, flags: u32) -> Self {
+        ChunkState { cv: *key, counter, block: [0; BLAKE3_BLOCKSIZE], block_len: 0, blocks_compressed: 0, flags }
+    }
+
+    fn _len(&self) -> usize {
+        BLAKE3_BLOCKSIZE * self.blocks_compressed + self.block_len
+    }
+
+    fn _start_flag(&self) -> u32 {
+        if self.blocks_compressed == 0 { CHUNK_START } else { 0 }
+    }
+
+    /* A full block is only compressed once more input follows, the last block of
+     * the chunk carries CHUNK_END. */
+    fn _update(&mut self, mut input: &[u8]) {
+        while !input.is_empty() {
+            if self.block_len == BLAKE3_BLOCKSIZE {
+                let words = _words::<16>(&self.block);
+                self.cv = _first8(&_compress(&self.cv, &words, self.counter, BLAKE3_BLOCKSIZE as u32,
+                    self.flags | self._start_flag()));
+                self.blocks_compressed += 1;
+                self.block.fill(0);
+                self.block_len = 0;
+            }
+
+            let take = usize::min(BLAKE3_BLOCKSIZE - self.block_len, input.len());
+            self.block[self.block_len..self.block_len + take].copy_from_slice(&input[..take]);
+            self.block_len += take;
+            input = &input[take..];
+        }
+    }
+
+    fn _node(&self) -> Node {
+        Node {
+            cv: self.cv,
+            block: _words::<16>(&self.block),
+            counter: self.counter,
+            block_len: self.block_len as u32,
+            flags: self.flags | self._start_flag() | CHUNK_END,
+        }
+    }
+}
+
+/// BLAKE3 with the streaming interface of `SHA`.
+///
+/// The input is split into 1 KiB chunks that form the leaves of a binary tree;
+/// completed subtrees are merged right away, so the state is a fixed stack of
+/// chaining values and no memory is allocated. `finish` writes as many bytes of
+/// output as the buffer holds, the first 32 bytes are the default digest.
+/// `finish_xof` returns a reader for extended output with random access.
+///
+/// Keyed contexts are a MAC: like any other `MAC` their `finish` writes at most
+/// `mac_size` bytes, longer output comes from `finish_xof`. `derive_key` contexts
+/// hash key material for the given context string, which should be hardcoded,
+/// globally unique and application specific.
+#[derive(Debug, Clone)]
+pub struct BLAKE3 {
+    key: [u32; 8],
+    flags: u32,
+    chunk: ChunkState,
+    cv_stack: [[u32; 8]; MAX_DEPTH],
+    cv_stack_len: usize,
+}
+
+impl Default for BLAKE3 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BLAKE3 {
+    pub fn new() -> Self {
+        Self::_with_key(IV, 0)
+    }
+
+    pub fn new_keyed(key: &[u8]) -> Result<Self, Error> {
+        if key.len() != BLAKE3_KEY_SIZE {
+            return Err(Error::Err);
+        }
+
+        Ok(Self::_with_key(_words::<8>(key), KEYED_HASH))
+    }
+
+    /* The context string is hashed on its own to the key of the key material. */
+    pub fn new_derive_key(context: &str) -> Self {
+        let mut ctx = Self::_with_key(IV, DERIVE_KEY_CONTEXT);
+        ctx._update(context.as_bytes());
+        let mut context_key = [0u8; BLAKE3_KEY_SIZE];
+        context_key.copy_from_slice(&ctx._root()._root_block(0)[..BLAKE3_KEY_SIZE]);
+
+        let ctx = Self::_with_key(_words::<8>(&context_key), DERIVE_KEY_MATERIAL);
+        context_key.fill(0);
+
+        ctx
+    }
+
+    /// Hashes `input` into `output` of any length.
+    pub fn hash(input: &[u8], output: &mut [u8]) {
+        let mut ctx = Self::new();
+        ctx._update(input);
+        ctx.finish_xof().read(output);
+    }
+
+    /// MAC of `input` under a 32-byte key, of any length.
+    pub fn keyed_hash(key: &[u8], input: &[u8], output: &mut [u8]) -> Result<Success, Error> {
+        let mut ctx = Self::new_keyed(key)?;
+        ctx._update(input);
+        ctx.finish_xof().read(output);
+
+        Ok(Success::OK)
+    }
+
+    /// Derives `output.len()` bytes of key from `key_material` for `context`.
+    pub fn derive_key(context: &str, key_material: &[u8], output: &mut [u8]) {
+        let mut ctx = Self::new_derive_key(context);
+        ctx._update(key_material);
+        ctx.finish_xof().read(output);
+    }
+
+    /// Reader for the extended output of the message so far, at position 0.
+    pub fn finish_xof(&self) -> BLAKE3XOF {
+        BLAKE3XOF { node: self._root(), counter: 0, buffer: [0; BLAKE3_BLOCKSIZE], rem_pos: BLAKE3_BLOCKSIZE }
+    }
+
+    fn _with_key(key: [u32; 8], flags: u32) -> Self {
+        BLAKE3 {
+            key,
+            flags,
+            chunk: ChunkState::new(&key, 0, flags),
+            cv_stack: [[0; 8]; MAX_DEPTH],
+            cv_stack_len: 0,
+        }
+    }
+
+    /* Merges the chaining value of a completed chunk with all completed subtrees
+     * of the same size: there are as many as trailing zero bits of the number of
+     * chunks so far. */
+    fn _push_chunk(&mut self, mut cv: [u32; 8], mut total_chunks: u64) {
+        while total_chunks & 1 == 0 {
+            self.cv_stack_len -= 1;
+            cv = Node::_parent(&self.cv_stack[self.cv_stack_len], &cv, &self.key, self.flags)._chaining_value();
+            total_chunks >>= 1;
+        }
+        self.cv_stack[self.cv_stack_len] = cv;
+        self.cv_stack_len += 1;
+    }
+
+    fn _update(&mut self, mut input: &[u8]) {
+        while !input.is_empty() {
+            /* A full chunk is only finalized once more input follows, the last one
+             * may be the root. */
+            if self.chunk._len() == BLAKE3_CHUNK_SIZE {
+                let cv = self.chunk._node()._chaining_value();
+                let total_chunks = self.chunk.counter + 1;
+                self._push_chunk(cv, total_chunks);
+                self.chunk = ChunkState::new(&self.key, total_chunks, self.flags);
+            }
+
+            let take = usize::min(BLAKE3_CHUNK_SIZE - self.chunk._len(), input.len());
+            self.chunk._update(&input[..take]);
+            input = &input[take..];
+        }
+    }
+
+    /* Folds the stack from the right into the root node. */
+    fn _root(&self) -> Node {
+        let mut node = self.chunk._node();
+        for cv in self.cv_stack[..self.cv_stack_len].iter().rev() {
+            node = Node::_parent(cv, &node._chaining_value(), &self.key, self.flags);
+        }
+
+        node
+    }
+}
+
+impl StreamingAPI for BLAKE3 {}
+
+impl MAC for BLAKE3 {
+    fn mac_size(&self) -> usize {
+        BLAKE3_DIGEST_SIZE
+    }
+}
+
+impl DefaultInit for BLAKE3 {
+    fn init(&mut self) -> Result<Success, Error> {
+        self.chunk = ChunkState::new(&self.key, 0, self.flags);
+        self.cv_stack.iter_mut().for_each(|cv| cv.fill(0));
+        self.cv_stack_len = 0;
+
+        Ok(Success::OK)
+    }
+}
+
+impl SingleInputUpdate for BLAKE3 {
+    /// Fails if the message would get longer than 2^64 - 1 bytes.
+    fn update(&mut self, input: &[u8]) -> Result<Success, Error> {
+        let len = self.chunk.counter * BLAKE3_CHUNK_SIZE as u64 + self.chunk._len() as u64;
+        if len.checked_add(input.len() as u64).is_none() {
+            return Err(Error::Err);
+        }
+
+        self._update(input);
+
+        Ok(Success::OK)
+    }
+}
+
+impl SingleOutputFinish for BLAKE3 {
+    /// Writes `output.len()` bytes of output, at most `BLAKE3_DIGEST_SIZE` for keyed
+    /// contexts; the message can still be extended.
+    fn finish(&mut self, output: &mut [u8]) -> Result<Success, Error> {
+        if self.flags & KEYED_HASH != 0 && output.len() > BLAKE3_DIGEST_SIZE {
+            return Err(Error::Err);
+        }
+
+        self.finish_xof().read(output);
+
+        Ok(Success::OK)
+    }
+}
+
+/// Extended output of BLAKE3, 2^64 blocks of 64 bytes. Any block can be computed
+/// on its own, so `seek` is cheap.
+#[derive(Debug, Clone)]
+pub struct BLAKE3XOF {
+    node: Node,
+    /* Block of the output that follows the buffer. */
+    counter: u64,
+    buffer: [u8; BLAKE3_BLOCKSIZE],
+    rem_pos: usize,
+}
+
+impl BLAKE3XOF {
+    /// Fills `output` with the next bytes of output.
+    pub fn read(&mut self, output: &mut [u8]) {
+        let mut n = 0;
+        while n < output.len() {
+            if self.rem_pos == BLAKE3_BLOCKSIZE {
+                self.buffer = self.node._root_block(self.counter);
+                self.counter = self.counter.wrapping_add(1);
+                self.rem_pos = 0;
+            }
+
+            let take = usize::min(BLAKE3_BLOCKSIZE - self.rem_pos, output.len() - n);
+            output[n..n + take].copy_from_slice(&self.buffer[self.rem_pos..self.rem_pos + take]);
+            self.rem_pos += take;
+            n += take;
+        }
+    }
+
+    /// Moves to byte `pos` of the output.
+    pub fn seek(&mut self, pos: u64) {
+        self.counter = pos / BLAKE3_BLOCKSIZE as u64;
+        self.rem_pos = BLAKE3_BLOCKSIZE;
+        let offset = pos as usize % BLAKE3_BLOCKSIZE;
+        if offset != 0 {
+            self.buffer = self.node._root_block(self.counter);
+            self.counter += 1;
+            self.rem_pos = offset;
+        }
+    }
+
+    /// Current byte position in the output.
+    pub fn position(&self) -> u64 {
+        self.counter.wrapping_mul(BLAKE3_BLOCKSIZE as u64).wrapping_add(self.rem_pos as u64).wrapping_sub(BLAKE3_BLOCKSIZE as u64)
+    }
+}
@@ -0,0 +1,87 @@
+use crate::common::api::{StreamingAPI, DefaultInit, SingleInputUpdate, SingleOutputFinish};
+use crate::common::{Success, Error};
+use crate::hash::{Operations, HashDataCtx};
+use crate::hash::md5::{MD5Ctx, MD5_BLOCKSIZE, MD5_DIGEST_SIZE, MD5_STATE_SIZE};
+use crate::hash::ripemd160::{RIPEMD160Ctx, RIPEMD160_BLOCKSIZE, RIPEMD160_DIGEST_SIZE, RIPEMD160_STATE_SIZE};
+
+/// Hash functions that are only kept for compatibility, behind the `legacy` feature:
+/// MD5 to verify old artifacts and RIPEMD-160 for Bitcoin-style addresses. MD5 must
+/// not be relied on for collision resistance.
+///
+/// Same interface as `SHA`, `init` has to be called before the first message.
+#[derive(Debug, Clone)]
+pub enum LegacyHash {
+    MD5(MD5Ctx),
+    RIPEMD160(RIPEMD160Ctx),
+}
+
+impl LegacyHash {
+    pub fn new_md5() -> Self {
+        LegacyHash::MD5(MD5Ctx {
+            data: HashDataCtx {
+                count: 0,
+                buffer: [0; MD5_BLOCKSIZE],
+                rem_pos: 0,
+                state: [0; MD5_STATE_SIZE],
+            }
+        })
+    }
+
+    pub fn new_ripemd160() -> Self {
+        LegacyHash::RIPEMD160(RIPEMD160Ctx {
+            data: HashDataCtx {
+                count: 0,
+                buffer: [0; RIPEMD160_BLOCKSIZE],
+                rem_pos: 0,
+                state: [0; RIPEMD160_STATE_SIZE],
+            }
+        })
+    }
+
+    /// Size of the (untruncated) digest in bytes.
+    pub fn digest_size(&self) -> usize {
+        match self {
+            LegacyHash::MD5(_)       => MD5_DIGEST_SIZE,
+            LegacyHash::RIPEMD160(_) => RIPEMD160_DIGEST_SIZE,
+        }
+    }
+
+    /// Size of the internal message block in bytes.
+    pub fn block_size(&self) -> usize {
+        match self {
+            LegacyHash::MD5(_)       => MD5_BLOCKSIZE,
+            LegacyHash::RIPEMD160(_) => RIPEMD160_BLOCKSIZE,
+        }
+    }
+}
+
+impl StreamingAPI for LegacyHash {}
+
+impl DefaultInit for LegacyHash {
+    fn init(&mut self) -> Result<Success, Error> {
+        match self {
+            LegacyHash::MD5(ctx)       => MD5Ctx::_init(&mut ctx.data),
+            LegacyHash::RIPEMD160(ctx) => RIPEMD160Ctx::_init(&mut ctx.data),
+        }
+
+        Ok(Success::OK)
+    }
+}
+
+impl SingleInputUpdate for LegacyHash {
+    fn update(&mut self, input: &[u8]) -> Result<Success, Error> {
+        match self {
+            LegacyHash::MD5(ctx)       => MD5Ctx::_process(&mut ctx.data, input),
+            LegacyHash::RIPEMD160(ctx) => RIPEMD160Ctx::_process(&mut ctx.data, input),
+        }
+    }
+}
+
+impl SingleOutputFinish for LegacyHash {
+    fn finish(&mut self, output: &mut [u8]) -> Result<Success, Error> {
+        match self {
+            LegacyHash::MD5(ctx)       => MD5Ctx::_finish(&mut ctx.data, output),
+            LegacyHash::RIPEMD160(ctx) => RIPEMD160Ctx::_finish(&mut ctx.data, output),
+        }
+    }
+}
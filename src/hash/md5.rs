@@ -0,0 +1,73 @@
+use crate::hash::Operations;
+use crate::hash::HashDataCtx;
+
+pub const MD5_DIGEST_SIZE: usize = 16;
+pub const MD5_BLOCKSIZE: usize = 64;
+pub const MD5_STATE_SIZE: usize = 4;
+
+/* Rotation amounts, four per round. */
+const S: [[u32; 4]; 4] = [[7, 12, 17, 22], [5, 9, 14, 20], [4, 11, 16, 23], [6, 10, 15, 21]];
+
+/* K[i] = floor(abs(sin(i + 1)) * 2^32) */
+const K: [u32; 64] = [
+    0xd76aa478, 0xe8c7b756, 0x242070db, 0xc1bdceee, 0xf57c0faf, 0x4787c62a, 0xa8304613, 0xfd469501,
+    0x698098d8, 0x8b44f7af, 0xffff5bb1, 0x895cd7be, 0x6b901122, 0xfd987193, 0xa679438e, 0x49b40821,
+    0xf61e2562, 0xc040b340, 0x265e5a51, 0xe9b6c7aa, 0xd62f105d, 0x02441453, 0xd8a1e681, 0xe7d3fbc8,
+    0x21e1cde6, 0xc33707d6, 0xf4d50d87, 0x455a14ed, 0xa9e3e905, 0xfcefa3f8, 0x676f02d9, 0x8d2a4c8a,
+    0xfffa3942, 0x8771f681, 0x6d9d6122, 0xfde5380c, 0xa4beea44, 0x4bdecfa9, 0xf6bb4b60, 0xbebfbc70,
+    0x289b7ec6, 0xeaa127fa, 0xd4ef3085, 0x04881d05, 0xd9d4d039, 0xe6db99e5, 0x1fa27cf8, 0xc4ac5665,
+    0xf4292244, 0x432aff97, 0xab9423a7, 0xfc93a039, 0x655b59c3, 0x8f0ccc92, 0xffeff47d, 0x85845dd1,
+    0x6fa87e4f, 0xfe2ce6e0, 0xa3014314, 0x4e0811a1, 0xf7537e82, 0xbd3af235, 0x2ad7d2bb, 0xeb86d391,
+];
+
+/// MD5 (RFC 1321). Broken for collision resistance, only for verifying existing data.
+#[derive(Debug, Clone)]
+pub struct MD5Ctx { pub data: HashDataCtx<MD5_BLOCKSIZE, MD5_STATE_SIZE, u32> }
+
+impl Operations<MD5_BLOCKSIZE, MD5_DIGEST_SIZE, MD5_STATE_SIZE, u32> for MD5Ctx {
+    const LITTLE_ENDIAN: bool = true;
+
+    fn _transform(state: &mut [u32; MD5_STATE_SIZE], input: &[u8]) {
+        let mut m = [0u32; 16];
+
+        for block in input.chunks_exact(MD5_BLOCKSIZE) {
+            for (w, b) in m.iter_mut().zip(block.chunks_exact(4)) {
+                *w = u32::from_le_bytes([b[0], b[1], b[2], b[3]]);
+            }
+
+            let (mut a, mut b, mut c, mut d) = (state[0], state[1], state[2], state[3]);
+
+            for i in 0..64 {
+                let (f, g) = match i / 16 {
+                    0 => ((b & c) | (!b & d), i),
+                    1 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+                    2 => (b ^ c ^ d, (3 * i + 5) % 16),
+                    _ => (c ^ (b | !d), (7 * i) % 16),
+                };
+
+                let t = d;
+                d = c;
+                c = b;
+                b = b.wrapping_add(a.wrapping_add(f).wrapping_add(K[i]).wrapping_add(m[g]).rotate_left(S[i / 16][i % 4]));
+                a = t;
+            }
+
+            state[0] = state[0].wrapping_add(a);
+            state[1] = state[1].wrapping_add(b);
+            state[2] = state[2].wrapping_add(c);
+            state[3] = state[3].wrapping_add(d);
+        }
+        m.fill(0);
+    }
+
+    fn _init(ctx: &mut HashDataCtx<MD5_BLOCKSIZE, MD5_STATE_SIZE, u32>) {
+        ctx.count = 0;
+        ctx.rem_pos = 0;
+        ctx.buffer.fill(0);
+
+        ctx.state[0] = 0x67452301;
+        ctx.state[1] = 0xefcdab89;
+        ctx.state[2] = 0x98badcfe;
+        ctx.state[3] = 0x10325476;
+    }
+}
@@ -1,5 +1,16 @@
 mod sha2;
 mod sha1;
+mod sm3;
+mod blake2;
+mod blake3;
+mod multi;
+mod backend;
+#[cfg(feature = "legacy")]
+mod md5;
+#[cfg(feature = "legacy")]
+mod ripemd160;
+#[cfg(feature = "legacy")]
+mod legacy;
 
 use crate::common::api::{StreamingAPI, DefaultInit, SingleInputUpdate, SingleOutputFinish};
 use crate::utils::Cast;
@@ -10,9 +21,18 @@ use crate::hash::sha2::SHA384Ctx;
 use crate::hash::sha2::SHA2_STATE_SIZE;
 use crate::hash::sha2::SHA256_BLOCKSIZE;
 use crate::hash::sha2::SHA512_BLOCKSIZE;
+use crate::hash::sha2::SHA256_DIGEST_SIZE;
+use crate::hash::sha2::SHA224_DIGEST_SIZE;
+use crate::hash::sha2::SHA512_DIGEST_SIZE;
+use crate::hash::sha2::SHA384_DIGEST_SIZE;
 use crate::hash::sha1::SHA1Ctx;
 use crate::hash::sha1::SHA1_STATE_SIZE;
 use crate::hash::sha1::SHA1_BLOCKSIZE;
+use crate::hash::sha1::SHA1_DIGEST_SIZE;
+use crate::hash::sm3::SM3Ctx;
+use crate::hash::sm3::SM3_STATE_SIZE;
+use crate::hash::sm3::SM3_BLOCKSIZE;
+use crate::hash::sm3::SM3_DIGEST_SIZE;
 use num::traits::WrappingAdd;
 use num::Zero;
 use num_traits::PrimInt;
@@ -23,8 +43,24 @@ use core::ops::Not;
 use core::mem::size_of;
 use crate::common::{Success, Error};
 
-
-#[derive(Debug)]
+pub use crate::hash::sha1::SHA1Mode;
+pub use crate::hash::backend::SHABackend;
+pub use crate::hash::blake2::{BLAKE2, BLAKE2Params, BLAKE2Ctx, BLAKE2Word, BLAKE2bCtx, BLAKE2sCtx, BLAKE2B_BLOCKSIZE, BLAKE2B_DIGEST_SIZE,
+    BLAKE2B_KEY_SIZE, BLAKE2B_SALT_SIZE, BLAKE2B_PERSONAL_SIZE, BLAKE2S_BLOCKSIZE, BLAKE2S_DIGEST_SIZE, BLAKE2S_KEY_SIZE,
+    BLAKE2S_SALT_SIZE, BLAKE2S_PERSONAL_SIZE};
+pub use crate::hash::blake3::{BLAKE3, BLAKE3XOF, BLAKE3_BLOCKSIZE, BLAKE3_CHUNK_SIZE, BLAKE3_DIGEST_SIZE, BLAKE3_KEY_SIZE};
+#[cfg(feature = "legacy")]
+pub use crate::hash::legacy::LegacyHash;
+#[cfg(feature = "legacy")]
+pub use crate::hash::md5::{MD5Ctx, MD5_BLOCKSIZE, MD5_DIGEST_SIZE};
+#[cfg(feature = "legacy")]
+pub use crate::hash::ripemd160::{RIPEMD160Ctx, RIPEMD160_BLOCKSIZE, RIPEMD160_DIGEST_SIZE};
+
+/* Largest digest and block size of all variants of `SHA`. Useful for sizing buffers. */
+pub const MAX_DIGEST_SIZE: usize = SHA512_DIGEST_SIZE;
+pub const MAX_BLOCKSIZE: usize = SHA512_BLOCKSIZE;
+
+#[derive(Debug, Clone)]
 pub struct HashDataCtx<const BLOCKSIZE: usize, const STATE_SIZE: usize, S> {
     count: u64,
     buffer: [u8; BLOCKSIZE],
@@ -41,6 +77,10 @@ trait Operations<
     T: PrimInt + BitAnd<Output=T> + Not<Output=T> + BitXor<Output=T> +
         Zero + WrappingAdd + Copy + BitOrAssign + Cast<u8>> {
 
+    /* Byte order of the length in the padding and of the state words in the
+     * digest: big-endian for SHA, little-endian for MD5 and RIPEMD-160. */
+    const LITTLE_ENDIAN: bool = false;
+
     fn _transform(state: &mut [T; STATE_SIZE], input: &[u8]);
 
     fn _init(ctx: &mut HashDataCtx<BLOCKSIZE, STATE_SIZE, T>);
@@ -48,15 +88,24 @@ trait Operations<
     fn _finish(
         ctx: &mut HashDataCtx<BLOCKSIZE, STATE_SIZE, T>,
         output: &mut [u8],
+    ) -> Result<Success, Error> {
+        Self::_finish_with(ctx, output, Self::_transform)
+    }
+
+    /* `_finish` with another function to process the blocks. */
+    fn _finish_with<F: FnMut(&mut [T; STATE_SIZE], &[u8])>(
+        ctx: &mut HashDataCtx<BLOCKSIZE, STATE_SIZE, T>,
+        output: &mut [u8],
+        mut transform: F,
     ) -> Result<Success, Error> {
         /* Apply padding to last block and proccess it. */
         if let Success::Again = Self::_padding(&mut ctx.buffer, ctx.count, true)? {
-                Self::_transform(&mut ctx.state, &ctx.buffer);
+                transform(&mut ctx.state, &ctx.buffer);
                 ctx.buffer.fill(0);
-                Self::_padding(&mut ctx.buffer, ctx.count, false);
+                Self::_padding(&mut ctx.buffer, ctx.count, false)?;
         }
 
-        Self::_transform(&mut ctx.state, &ctx.buffer);
+        transform(&mut ctx.state, &ctx.buffer);
 
         /* Truncate output if needed */
         let mut digest_size = output.len();
@@ -71,7 +120,12 @@ trait Operations<
         for i in 0..digest_size {
             // Copy result from state array to output buffer.
             // T is a "wider" type than u8, so we have to shift and mask to get the proper result.
-            output[i] = (ctx.state[j / size_of::<T>()] >> (((size_of::<T>() - 1) * 8) - (i % size_of::<T>() * 8))).cast() & 0xFF;
+            let shift = if Self::LITTLE_ENDIAN {
+                i % size_of::<T>() * 8
+            } else {
+                ((size_of::<T>() - 1) * 8) - (i % size_of::<T>() * 8)
+            };
+            output[i] = (ctx.state[j / size_of::<T>()] >> shift).cast();
             j += 1;
         }
 
@@ -79,8 +133,17 @@ trait Operations<
     }
 
     fn _process(
+        ctx: &mut HashDataCtx<BLOCKSIZE, STATE_SIZE, T>,
+        input: &[u8],
+    ) -> Result<Success, Error> {
+        Self::_process_with(ctx, input, Self::_transform)
+    }
+
+    /* `_process` with another function to process the blocks. */
+    fn _process_with<F: FnMut(&mut [T; STATE_SIZE], &[u8])>(
         ctx: &mut HashDataCtx<BLOCKSIZE, STATE_SIZE, T>,
         mut input: &[u8],
+        mut transform: F,
     ) -> Result<Success, Error> {
         if input.len() == 0 {
             return Ok(Success::OK);
@@ -93,7 +156,7 @@ trait Operations<
         if ctx.rem_pos > 0 && (ctx.rem_pos + insize) >= BLOCKSIZE {
             // Fill buffer with input and process it.
             ctx.buffer[ctx.rem_pos..].copy_from_slice(&input[..BLOCKSIZE - ctx.rem_pos]);
-            Self::_transform(&mut ctx.state, &ctx.buffer);
+            transform(&mut ctx.state, &ctx.buffer);
 
             // Adjust input ref and size by the amount of bytes used to fill up the buffer
             input = &input[BLOCKSIZE - ctx.rem_pos..];
@@ -111,7 +174,7 @@ trait Operations<
         let nblocks = insize / BLOCKSIZE;
 
         // Process available blocks.
-        Self::_transform(&mut ctx.state, input);
+        transform(&mut ctx.state, input);
 
         // Copy residual bytes to buffer.
         if insize % BLOCKSIZE != 0 {
@@ -157,6 +220,11 @@ trait Operations<
 
                 i += BLOCKSIZE - 8 - i;
 
+                if Self::LITTLE_ENDIAN {
+                    msg[i..i + 8].copy_from_slice(&lbits.to_le_bytes());
+                    return Ok(Success::OK);
+                }
+
                 msg[i] = (lbits >> 56) as u8 & 0xFF;
                 msg[i + 1] = (lbits >> 48) as u8 & 0xFF;
                 msg[i + 2] = (lbits >> 40) as u8 & 0xFF;
@@ -175,7 +243,9 @@ trait Operations<
                 return Ok(Success::Again);
             }
         } else {
-            if BLOCKSIZE == 64 {
+            if Self::LITTLE_ENDIAN {
+                msg[BLOCKSIZE - 8..].copy_from_slice(&lbits.to_le_bytes());
+            } else if BLOCKSIZE == 64 {
                 msg[BLOCKSIZE - 4] = (lbits >> 24) as u8 & 0xFF;
                 msg[BLOCKSIZE - 3] = (lbits >> 16) as u8 & 0xFF;
                 msg[BLOCKSIZE - 2] = (lbits >> 8) as u8 & 0xFF;
@@ -199,25 +269,34 @@ trait Operations<
 }
 
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum SHA {
     SHA1(SHA1Ctx),
     SHA256(SHA256Ctx),
     SHA224(SHA224Ctx),
     SHA512(SHA512Ctx),
     SHA384(SHA384Ctx),
+    SM3(SM3Ctx),
 }
 
 impl SHA {
     pub fn new_sha1() -> Self {
-        return SHA::SHA1(SHA1Ctx {
+        Self::new_sha1_with_mode(SHA1Mode::Standard)
+    }
+
+    /// SHA-1 with collision detection, see `SHA1Mode`.
+    pub fn new_sha1_with_mode(mode: SHA1Mode) -> Self {
+        SHA::SHA1(SHA1Ctx {
             data: HashDataCtx {
                 count: 0,
                 buffer: [0; SHA1_BLOCKSIZE],
                 rem_pos: 0,
                 state: [0; SHA1_STATE_SIZE],
-            }
-        });
+            },
+            mode,
+            collision: false,
+            backend: SHABackend::detect(),
+        })
     }
     pub fn new_sha256() -> Self {
         return SHA::SHA256(SHA256Ctx {
@@ -226,7 +305,8 @@ impl SHA {
                 buffer: [0; SHA256_BLOCKSIZE],
                 rem_pos: 0,
                 state: [0; SHA2_STATE_SIZE],
-            }
+            },
+            backend: SHABackend::detect(),
         });
     }
 
@@ -237,7 +317,8 @@ impl SHA {
                 buffer: [0; SHA256_BLOCKSIZE],
                 rem_pos: 0,
                 state: [0; SHA2_STATE_SIZE],
-            }
+            },
+            backend: SHABackend::detect(),
         });
     }
 
@@ -262,6 +343,100 @@ impl SHA {
             }
         });
     }
+
+    /// SM3 (GB/T 32905-2016). Not a SHA but built the same way, so it is available
+    /// wherever `SHA` is accepted, e.g. for HMAC-SM3.
+    pub fn new_sm3() -> Self {
+        SHA::SM3(SM3Ctx {
+            data: HashDataCtx {
+                count: 0,
+                buffer: [0; SM3_BLOCKSIZE],
+                rem_pos: 0,
+                state: [0; SM3_STATE_SIZE],
+            }
+        })
+    }
+
+    /// SHA-256 of every message in `inputs`, one digest after another in `outputs`,
+    /// which must have room for exactly `inputs.len()` digests.
+    ///
+    /// Processes 4 (8 with compile-time AVX2) messages in lockstep, written so that
+    /// the compiler can vectorize it, which is faster than a context per message
+    /// when there are many short ones. If the CPU has SHA instructions (see
+    /// `SHABackend::detect`) the messages are hashed one after another with those
+    /// instead, which is faster still. The digests are the same.
+    pub fn sha256_many(inputs: &[&[u8]], outputs: &mut [u8]) -> Result<Success, Error> {
+        multi::_sha256_many(SHABackend::detect(), inputs, outputs)
+    }
+
+    /// `sha256_many` with the given backend: `SHABackend::Scalar` runs the messages
+    /// in lockstep, the others hash them one after another. Fails if the CPU does
+    /// not support `backend`.
+    pub fn sha256_many_with_backend(backend: SHABackend, inputs: &[&[u8]], outputs: &mut [u8]) -> Result<Success, Error> {
+        multi::_sha256_many(backend, inputs, outputs)
+    }
+
+    /// SHA-1 version of `sha256_many`, without collision detection.
+    pub fn sha1_many(inputs: &[&[u8]], outputs: &mut [u8]) -> Result<Success, Error> {
+        multi::_sha1_many(SHABackend::detect(), inputs, outputs)
+    }
+
+    /// SHA-1 version of `sha256_many_with_backend`.
+    pub fn sha1_many_with_backend(backend: SHABackend, inputs: &[&[u8]], outputs: &mut [u8]) -> Result<Success, Error> {
+        multi::_sha1_many(backend, inputs, outputs)
+    }
+
+    /// Selects the implementation of the block function, `SHABackend::detect()` by
+    /// default. Fails if the CPU does not support `backend`, and for SHA-384, SHA-512
+    /// and SM3 that only have the scalar one. The collision detection of the hardened
+    /// SHA-1 modes always runs on the scalar one.
+    pub fn set_backend(&mut self, backend: SHABackend) -> Result<Success, Error> {
+        if !backend.is_supported() {
+            return Err(Error::Err);
+        }
+
+        match self {
+            SHA::SHA1(ctx)   => ctx.backend = backend,
+            SHA::SHA256(ctx) => ctx.backend = backend,
+            SHA::SHA224(ctx) => ctx.backend = backend,
+            _ if backend == SHABackend::Scalar => {},
+            _ => return Err(Error::Err),
+        }
+
+        Ok(Success::OK)
+    }
+
+    /// The implementation of the block function in use.
+    pub fn backend(&self) -> SHABackend {
+        match self {
+            SHA::SHA1(ctx)   => ctx.backend,
+            SHA::SHA256(ctx) => ctx.backend,
+            SHA::SHA224(ctx) => ctx.backend,
+            _ => SHABackend::Scalar,
+        }
+    }
+
+    /// Size of the (untruncated) digest in bytes.
+    pub fn digest_size(&self) -> usize {
+        match self {
+            SHA::SHA1(_)   => SHA1_DIGEST_SIZE,
+            SHA::SHA256(_) => SHA256_DIGEST_SIZE,
+            SHA::SHA224(_) => SHA224_DIGEST_SIZE,
+            SHA::SHA384(_) => SHA384_DIGEST_SIZE,
+            SHA::SHA512(_) => SHA512_DIGEST_SIZE,
+            SHA::SM3(_)    => SM3_DIGEST_SIZE
+        }
+    }
+
+    /// Size of the internal message block in bytes.
+    pub fn block_size(&self) -> usize {
+        match self {
+            SHA::SHA1(_)   => SHA1_BLOCKSIZE,
+            SHA::SHA256(_) | SHA::SHA224(_) => SHA256_BLOCKSIZE,
+            SHA::SHA384(_) | SHA::SHA512(_) => SHA512_BLOCKSIZE,
+            SHA::SM3(_)    => SM3_BLOCKSIZE
+        }
+    }
 }
 
 
@@ -270,11 +445,12 @@ impl StreamingAPI for SHA {}
 impl DefaultInit for SHA {
     fn init(&mut self) -> Result<Success, Error>{
         match &mut *self {
-            SHA::SHA1(ctx)   => SHA1Ctx::_init(&mut ctx.data),
+            SHA::SHA1(ctx)   => ctx._reset(),
             SHA::SHA256(ctx) => SHA256Ctx::_init(&mut ctx.data),
             SHA::SHA224(ctx) => SHA224Ctx::_init(&mut ctx.data),
             SHA::SHA384(ctx) => SHA384Ctx::_init(&mut ctx.data),
-            SHA::SHA512(ctx) => SHA512Ctx::_init(&mut ctx.data)
+            SHA::SHA512(ctx) => SHA512Ctx::_init(&mut ctx.data),
+            SHA::SM3(ctx)    => SM3Ctx::_init(&mut ctx.data)
         }
 
         return Ok(Success::OK);
@@ -284,11 +460,12 @@ impl DefaultInit for SHA {
 impl SingleInputUpdate for SHA {
     fn update(&mut self, input: &[u8]) -> Result<Success, Error> {
         let ret = match &mut *self {
-            SHA::SHA1(ctx)   => SHA1Ctx::_process(&mut ctx.data, input),
-            SHA::SHA256(ctx) => SHA256Ctx::_process(&mut ctx.data, input),
-            SHA::SHA224(ctx) => SHA224Ctx::_process(&mut ctx.data, input),
+            SHA::SHA1(ctx)   => ctx._update(input),
+            SHA::SHA256(ctx) => SHA256Ctx::_process_with(&mut ctx.data, input, |state, blocks| ctx.backend._sha256(state, blocks)),
+            SHA::SHA224(ctx) => SHA224Ctx::_process_with(&mut ctx.data, input, |state, blocks| ctx.backend._sha256(state, blocks)),
             SHA::SHA384(ctx) => SHA384Ctx::_process(&mut ctx.data, input),
-            SHA::SHA512(ctx) => SHA512Ctx::_process(&mut ctx.data, input)
+            SHA::SHA512(ctx) => SHA512Ctx::_process(&mut ctx.data, input),
+            SHA::SM3(ctx)    => SM3Ctx::_process(&mut ctx.data, input)
         };
 
         return ret;
@@ -298,11 +475,12 @@ impl SingleInputUpdate for SHA {
 impl SingleOutputFinish for SHA {
     fn finish(&mut self, output: &mut [u8]) -> Result<Success, Error> {
         let ret = match &mut *self {
-            SHA::SHA1(ctx)   => SHA1Ctx::_finish(&mut ctx.data, output),
-            SHA::SHA256(ctx) => SHA256Ctx::_finish(&mut ctx.data, output),
-            SHA::SHA224(ctx) => SHA224Ctx::_finish(&mut ctx.data, output),
+            SHA::SHA1(ctx)   => ctx._digest(output),
+            SHA::SHA256(ctx) => SHA256Ctx::_finish_with(&mut ctx.data, output, |state, blocks| ctx.backend._sha256(state, blocks)),
+            SHA::SHA224(ctx) => SHA224Ctx::_finish_with(&mut ctx.data, output, |state, blocks| ctx.backend._sha256(state, blocks)),
             SHA::SHA384(ctx) => SHA384Ctx::_finish(&mut ctx.data, output),
-            SHA::SHA512(ctx) => SHA512Ctx::_finish(&mut ctx.data, output)
+            SHA::SHA512(ctx) => SHA512Ctx::_finish(&mut ctx.data, output),
+            SHA::SM3(ctx)    => SM3Ctx::_finish(&mut ctx.data, output)
         };
 
         return ret;
@@ -0,0 +1,284 @@
+use crate::hash::{Operations, HashDataCtx};
+use crate::hash::sha1::{SHA1Ctx, SHA1_BLOCKSIZE, SHA1_DIGEST_SIZE, SHA1_STATE_SIZE, sha1k};
+use crate::hash::sha2::{SHA256Ctx, SHA256_BLOCKSIZE, SHA256_DIGEST_SIZE, SHA2_STATE_SIZE, K256};
+use crate::hash::backend::SHABackend;
+use crate::hash::SHA;
+use crate::common::api::{DefaultInit, SingleInputUpdate, SingleOutputFinish};
+use crate::common::{Success, Error};
+
+/* Number of messages hashed in lockstep, fixed at compile time. Every operation on
+ * `Lanes` is a loop over the lanes without dependencies between them, which the
+ * compiler can turn into SIMD instructions: u32x8 with AVX2, u32x4 with SSE2 and
+ * NEON. More lanes than the vectors hold only spill registers. */
+#[cfg(target_feature = "avx2")]
+const LANES: usize = 8;
+#[cfg(not(target_feature = "avx2"))]
+const LANES: usize = 4;
+
+/* Both hashes use 64 byte blocks. */
+const BLOCKSIZE: usize = 64;
+
+type Lanes = [u32; LANES];
+
+/* Message `msg` and the next of its blocks a lane processes. */
+#[derive(Clone, Copy)]
+struct Job {
+    msg: usize,
+    block: usize,
+}
+
+/* The lanes are the scalar backend, the SHA instructions on a single message are
+ * faster than them. */
+pub(crate) fn _sha256_many(backend: SHABackend, inputs: &[&[u8]], outputs: &mut [u8]) -> Result<Success, Error> {
+    if backend != SHABackend::Scalar {
+        return _hash_each(SHA::new_sha256(), backend, inputs, outputs);
+    }
+
+    let mut ctx = HashDataCtx {
+        count: 0,
+        buffer: [0; SHA256_BLOCKSIZE],
+        rem_pos: 0,
+        state: [0; SHA2_STATE_SIZE],
+    };
+    SHA256Ctx::_init(&mut ctx);
+
+    _hash_many::<SHA2_STATE_SIZE, SHA256_DIGEST_SIZE>(inputs, outputs, &ctx.state, _compress_sha256)
+}
+
+pub(crate) fn _sha1_many(backend: SHABackend, inputs: &[&[u8]], outputs: &mut [u8]) -> Result<Success, Error> {
+    if backend != SHABackend::Scalar {
+        return _hash_each(SHA::new_sha1(), backend, inputs, outputs);
+    }
+
+    let mut ctx = HashDataCtx {
+        count: 0,
+        buffer: [0; SHA1_BLOCKSIZE],
+        rem_pos: 0,
+        state: [0; SHA1_STATE_SIZE],
+    };
+    SHA1Ctx::_init(&mut ctx);
+
+    _hash_many::<SHA1_STATE_SIZE, SHA1_DIGEST_SIZE>(inputs, outputs, &ctx.state, _compress_sha1)
+}
+
+fn _hash_each(mut sha: SHA, backend: SHABackend, inputs: &[&[u8]], outputs: &mut [u8]) -> Result<Success, Error> {
+    sha.set_backend(backend)?;
+    let digest_size = sha.digest_size();
+    if outputs.len() != inputs.len() * digest_size {
+        return Err(Error::Err);
+    }
+
+    for (input, digest) in inputs.iter().zip(outputs.chunks_exact_mut(digest_size)) {
+        sha.init()?;
+        sha.update(input)?;
+        sha.finish(digest)?;
+    }
+
+    Ok(Success::OK)
+}
+
+/* Feeds the messages through the lanes. A lane that is done with its message
+ * writes the digest and takes the next one, so messages of different lengths
+ * keep all lanes busy until the last few. */
+fn _hash_many<const STATE_SIZE: usize, const DIGEST_SIZE: usize>(
+    inputs: &[&[u8]],
+    outputs: &mut [u8],
+    iv: &[u32; STATE_SIZE],
+    compress: fn(&mut [Lanes; STATE_SIZE], &[Lanes; 16]),
+) -> Result<Success, Error> {
+    if outputs.len() != inputs.len() * DIGEST_SIZE {
+        return Err(Error::Err);
+    }
+
+    let mut state = [[0u32; LANES]; STATE_SIZE];
+    let mut jobs: [Option<Job>; LANES] = [None; LANES];
+    let mut w = [[0u32; LANES]; 16];
+    let mut buf = [0u8; BLOCKSIZE];
+    let mut next = 0;
+
+    for (lane, job) in jobs.iter_mut().enumerate() {
+        *job = _next_job(&mut state, lane, iv, &mut next, inputs.len());
+    }
+
+    while jobs.iter().any(|job| job.is_some()) {
+        for (lane, job) in jobs.iter().enumerate() {
+            let words = match job {
+                Some(job) => _block(inputs[job.msg], job.block, &mut buf)?,
+                None => &[0u8; BLOCKSIZE][..],
+            };
+            for (wt, b) in w.iter_mut().zip(words.chunks_exact(4)) {
+                wt[lane] = u32::from_be_bytes([b[0], b[1], b[2], b[3]]);
+            }
+        }
+
+        compress(&mut state, &w);
+
+        for (lane, slot) in jobs.iter_mut().enumerate() {
+            if let Some(job) = slot {
+                job.block += 1;
+                if job.block < _nblocks(inputs[job.msg].len()) {
+                    continue;
+                }
+
+                let digest = &mut outputs[job.msg * DIGEST_SIZE..(job.msg + 1) * DIGEST_SIZE];
+                for (out, s) in digest.chunks_exact_mut(4).zip(state.iter()) {
+                    out.copy_from_slice(&s[lane].to_be_bytes());
+                }
+                *slot = _next_job(&mut state, lane, iv, &mut next, inputs.len());
+            }
+        }
+    }
+
+    w.iter_mut().for_each(|wt| wt.fill(0));
+    buf.fill(0);
+    state.iter_mut().for_each(|s| s.fill(0));
+
+    Ok(Success::OK)
+}
+
+fn _next_job<const STATE_SIZE: usize>(
+    state: &mut [Lanes; STATE_SIZE],
+    lane: usize,
+    iv: &[u32; STATE_SIZE],
+    next: &mut usize,
+    count: usize,
+) -> Option<Job> {
+    if *next == count {
+        return None;
+    }
+
+    for (s, v) in state.iter_mut().zip(iv) {
+        s[lane] = *v;
+    }
+    *next += 1;
+
+    Some(Job { msg: *next - 1, block: 0 })
+}
+
+/* Number of blocks of the padded message: the 0x80 byte and the 64-bit length
+ * must fit after the message. */
+fn _nblocks(len: usize) -> usize {
+    (len + 8) / BLOCKSIZE + 1
+}
+
+/* Block `i` of the padded message. Full blocks are used in place, the ones with
+ * the padding are built in `buf`. */
+fn _block<'a>(msg: &'a [u8], i: usize, buf: &'a mut [u8; BLOCKSIZE]) -> Result<&'a [u8], Error> {
+    let start = i * BLOCKSIZE;
+    if start + BLOCKSIZE <= msg.len() {
+        return Ok(&msg[start..start + BLOCKSIZE]);
+    }
+
+    buf.fill(0);
+    if start <= msg.len() {
+        let rem = msg.len() - start;
+        buf[..rem].copy_from_slice(&msg[start..]);
+        buf[rem] = 0x80;
+    }
+    if i == _nblocks(msg.len()) - 1 {
+        let lbits = (msg.len() as u64).checked_mul(8).ok_or(Error::Err)?;
+        buf[BLOCKSIZE - 8..].copy_from_slice(&lbits.to_be_bytes());
+    }
+
+    Ok(&buf[..])
+}
+
+fn _add(x: Lanes, y: Lanes) -> Lanes {
+    let mut r = x;
+    for (r, y) in r.iter_mut().zip(y) {
+        *r = r.wrapping_add(y);
+    }
+
+    r
+}
+
+fn _compress_sha256(state: &mut [Lanes; SHA2_STATE_SIZE], block: &[Lanes; 16]) {
+    let mut w = [[0u32; LANES]; 64];
+    w[..16].copy_from_slice(block);
+    for t in 16..64 {
+        let mut wt = [0u32; LANES];
+        for (l, x) in wt.iter_mut().enumerate() {
+            let s0 = w[t - 15][l].rotate_right(7) ^ w[t - 15][l].rotate_right(18) ^ (w[t - 15][l] >> 3);
+            let s1 = w[t - 2][l].rotate_right(17) ^ w[t - 2][l].rotate_right(19) ^ (w[t - 2][l] >> 10);
+            *x = s1.wrapping_add(w[t - 7][l]).wrapping_add(s0).wrapping_add(w[t - 16][l]);
+        }
+        w[t] = wt;
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    for t in 0..64 {
+        let mut t1 = [0u32; LANES];
+        let mut t2 = [0u32; LANES];
+        for l in 0..LANES {
+            let bsig0 = a[l].rotate_right(2) ^ a[l].rotate_right(13) ^ a[l].rotate_right(22);
+            let bsig1 = e[l].rotate_right(6) ^ e[l].rotate_right(11) ^ e[l].rotate_right(25);
+            let ch = (e[l] & f[l]) ^ (!e[l] & g[l]);
+            let maj = (a[l] & b[l]) ^ (a[l] & c[l]) ^ (b[l] & c[l]);
+
+            t1[l] = h[l].wrapping_add(bsig1).wrapping_add(ch).wrapping_add(K256[t]).wrapping_add(w[t][l]);
+            t2[l] = bsig0.wrapping_add(maj);
+        }
+        h = g;
+        g = f;
+        f = e;
+        e = _add(d, t1);
+        d = c;
+        c = b;
+        b = a;
+        a = _add(t1, t2);
+    }
+
+    for (s, v) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *s = _add(*s, v);
+    }
+    w.iter_mut().for_each(|wt| wt.fill(0));
+}
+
+fn _compress_sha1(state: &mut [Lanes; SHA1_STATE_SIZE], block: &[Lanes; 16]) {
+    let mut w = [[0u32; LANES]; 80];
+    w[..16].copy_from_slice(block);
+    for t in 16..80 {
+        let mut wt = [0u32; LANES];
+        for (l, x) in wt.iter_mut().enumerate() {
+            *x = (w[t - 3][l] ^ w[t - 8][l] ^ w[t - 14][l] ^ w[t - 16][l]).rotate_left(1);
+        }
+        w[t] = wt;
+    }
+
+    /* Round function and constant change every 20 rounds, not per lane. */
+    let mut v = *state;
+    for (i, ws) in w.chunks_exact(20).enumerate() {
+        let k = sha1k(20 * i);
+        match i {
+            0 => _rounds_sha1(&mut v, ws, k, |x, y, z| (x & y) | (!x & z)),
+            2 => _rounds_sha1(&mut v, ws, k, |x, y, z| (x & y) | (x & z) | (y & z)),
+            _ => _rounds_sha1(&mut v, ws, k, |x, y, z| x ^ y ^ z),
+        }
+    }
+
+    for (s, v) in state.iter_mut().zip(v) {
+        *s = _add(*s, v);
+    }
+    w.iter_mut().for_each(|wt| wt.fill(0));
+}
+
+fn _rounds_sha1<F: Fn(u32, u32, u32) -> u32>(v: &mut [Lanes; SHA1_STATE_SIZE], w: &[Lanes], k: u32, f: F) {
+    let [mut a, mut b, mut c, mut d, mut e] = *v;
+    for wt in w {
+        let mut tmp = [0u32; LANES];
+        for l in 0..LANES {
+            tmp[l] = a[l].rotate_left(5)
+                .wrapping_add(f(b[l], c[l], d[l]))
+                .wrapping_add(e[l])
+                .wrapping_add(wt[l])
+                .wrapping_add(k);
+        }
+        e = d;
+        d = c;
+        c = b.map(|x| x.rotate_left(30));
+        b = a;
+        a = tmp;
+    }
+
+    *v = [a, b, c, d, e];
+}
@@ -0,0 +1,114 @@
+use crate::hash::Operations;
+use crate::hash::HashDataCtx;
+
+pub const RIPEMD160_DIGEST_SIZE: usize = 20;
+pub const RIPEMD160_BLOCKSIZE: usize = 64;
+pub const RIPEMD160_STATE_SIZE: usize = 5;
+
+/* Message word selection and rotation amounts of the left and the right line. */
+const RL: [usize; 80] = [
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15,
+    7, 4, 13, 1, 10, 6, 15, 3, 12, 0, 9, 5, 2, 14, 11, 8,
+    3, 10, 14, 4, 9, 15, 8, 1, 2, 7, 0, 6, 13, 11, 5, 12,
+    1, 9, 11, 10, 0, 8, 12, 4, 13, 3, 7, 15, 14, 5, 6, 2,
+    4, 0, 5, 9, 7, 12, 2, 10, 14, 1, 3, 8, 11, 6, 15, 13,
+];
+
+const RR: [usize; 80] = [
+    5, 14, 7, 0, 9, 2, 11, 4, 13, 6, 15, 8, 1, 10, 3, 12,
+    6, 11, 3, 7, 0, 13, 5, 10, 14, 15, 8, 12, 4, 9, 1, 2,
+    15, 5, 1, 3, 7, 14, 6, 9, 11, 8, 12, 2, 10, 0, 4, 13,
+    8, 6, 4, 1, 3, 11, 15, 0, 5, 12, 2, 13, 9, 7, 10, 14,
+    12, 15, 10, 4, 1, 5, 8, 7, 6, 2, 13, 14, 0, 3, 9, 11,
+];
+
+const SL: [u32; 80] = [
+    11, 14, 15, 12, 5, 8, 7, 9, 11, 13, 14, 15, 6, 7, 9, 8,
+    7, 6, 8, 13, 11, 9, 7, 15, 7, 12, 15, 9, 11, 7, 13, 12,
+    11, 13, 6, 7, 14, 9, 13, 15, 14, 8, 13, 6, 5, 12, 7, 5,
+    11, 12, 14, 15, 14, 15, 9, 8, 9, 14, 5, 6, 8, 6, 5, 12,
+    9, 15, 5, 11, 6, 8, 13, 12, 5, 12, 13, 14, 11, 8, 5, 6,
+];
+
+const SR: [u32; 80] = [
+    8, 9, 9, 11, 13, 15, 15, 5, 7, 7, 8, 11, 14, 14, 12, 6,
+    9, 13, 15, 7, 12, 8, 9, 11, 7, 7, 12, 7, 6, 15, 13, 11,
+    9, 7, 15, 11, 8, 6, 6, 14, 12, 13, 5, 14, 13, 13, 7, 5,
+    15, 5, 8, 11, 14, 14, 6, 14, 6, 9, 12, 9, 12, 5, 15, 8,
+    8, 5, 12, 9, 12, 5, 14, 6, 8, 13, 6, 5, 15, 13, 11, 11,
+];
+
+const KL: [u32; 5] = [0x00000000, 0x5a827999, 0x6ed9eba1, 0x8f1bbcdc, 0xa953fd4e];
+const KR: [u32; 5] = [0x50a28be6, 0x5c4dd124, 0x6d703ef3, 0x7a6d76e9, 0x00000000];
+
+/// RIPEMD-160 (Dobbertin, Bosselaers, Preneel), as used for Bitcoin addresses.
+#[derive(Debug, Clone)]
+pub struct RIPEMD160Ctx { pub data: HashDataCtx<RIPEMD160_BLOCKSIZE, RIPEMD160_STATE_SIZE, u32> }
+
+impl Operations<RIPEMD160_BLOCKSIZE, RIPEMD160_DIGEST_SIZE, RIPEMD160_STATE_SIZE, u32> for RIPEMD160Ctx {
+    const LITTLE_ENDIAN: bool = true;
+
+    fn _transform(state: &mut [u32; RIPEMD160_STATE_SIZE], input: &[u8]) {
+        let mut x = [0u32; 16];
+
+        for block in input.chunks_exact(RIPEMD160_BLOCKSIZE) {
+            for (w, b) in x.iter_mut().zip(block.chunks_exact(4)) {
+                *w = u32::from_le_bytes([b[0], b[1], b[2], b[3]]);
+            }
+
+            let (mut al, mut bl, mut cl, mut dl, mut el) = (state[0], state[1], state[2], state[3], state[4]);
+            let (mut ar, mut br, mut cr, mut dr, mut er) = (al, bl, cl, dl, el);
+
+            /* Both lines run the same rounds with the boolean functions in opposite order. */
+            for j in 0..80 {
+                let round = j / 16;
+
+                let t = al.wrapping_add(ripemd_f(round, bl, cl, dl)).wrapping_add(x[RL[j]]).wrapping_add(KL[round])
+                    .rotate_left(SL[j]).wrapping_add(el);
+                al = el;
+                el = dl;
+                dl = cl.rotate_left(10);
+                cl = bl;
+                bl = t;
+
+                let t = ar.wrapping_add(ripemd_f(4 - round, br, cr, dr)).wrapping_add(x[RR[j]]).wrapping_add(KR[round])
+                    .rotate_left(SR[j]).wrapping_add(er);
+                ar = er;
+                er = dr;
+                dr = cr.rotate_left(10);
+                cr = br;
+                br = t;
+            }
+
+            let t = state[1].wrapping_add(cl).wrapping_add(dr);
+            state[1] = state[2].wrapping_add(dl).wrapping_add(er);
+            state[2] = state[3].wrapping_add(el).wrapping_add(ar);
+            state[3] = state[4].wrapping_add(al).wrapping_add(br);
+            state[4] = state[0].wrapping_add(bl).wrapping_add(cr);
+            state[0] = t;
+        }
+        x.fill(0);
+    }
+
+    fn _init(ctx: &mut HashDataCtx<RIPEMD160_BLOCKSIZE, RIPEMD160_STATE_SIZE, u32>) {
+        ctx.count = 0;
+        ctx.rem_pos = 0;
+        ctx.buffer.fill(0);
+
+        ctx.state[0] = 0x67452301;
+        ctx.state[1] = 0xefcdab89;
+        ctx.state[2] = 0x98badcfe;
+        ctx.state[3] = 0x10325476;
+        ctx.state[4] = 0xc3d2e1f0;
+    }
+}
+
+fn ripemd_f(round: usize, x: u32, y: u32, z: u32) -> u32 {
+    match round {
+        0 => x ^ y ^ z,
+        1 => (x & y) | (!x & z),
+        2 => (x | !y) ^ z,
+        3 => (x & z) | (y & !z),
+        _ => x ^ (y | !z),
+    }
+}
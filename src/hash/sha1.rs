@@ -1,12 +1,97 @@
 use crate::hash::Operations;
 use crate::hash::HashDataCtx;
+use crate::hash::backend::SHABackend;
+use crate::common::{Success, Error};
 
 pub const SHA1_DIGEST_SIZE: usize = 20;
 pub const SHA1_BLOCKSIZE: usize = 64;
 pub const SHA1_STATE_SIZE: usize = 5;
 
-#[derive(Debug)]
-pub struct SHA1Ctx { pub data: HashDataCtx<SHA1_BLOCKSIZE, SHA1_STATE_SIZE, u32> }
+/// Collision detection of SHA-1 (counter-cryptanalysis, Stevens 2013).
+///
+/// In the hardened modes every block is checked for being one half of a near
+/// collision built from one of the 32 disturbance vectors used by all known practical
+/// attacks (SHAttered, SHA-1 is a Shambles), as in sha1collisiondetection. `finish`
+/// then returns `Error::CollisionDetected` for messages containing such a block,
+/// while the probability of a false positive is below 2^-90. The check recomputes
+/// the block once per disturbance vector, which makes hashing a lot slower.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SHA1Mode {
+    /// Plain SHA-1.
+    Standard,
+    /// Detection of collision attacks. The digest is the SHA-1 digest.
+    DetectCollisions,
+    /// Detection, and a detected block is compressed three times, so the digest of
+    /// an attacked message is the "safe hash" that differs from the SHA-1 digest and
+    /// from that of its colliding twin. Unaffected messages get the SHA-1 digest.
+    SafeHash,
+}
+
+#[derive(Debug, Clone)]
+pub struct SHA1Ctx {
+    pub data: HashDataCtx<SHA1_BLOCKSIZE, SHA1_STATE_SIZE, u32>,
+    pub(crate) mode: SHA1Mode,
+    /* A collision block has been found in the current message. */
+    pub(crate) collision: bool,
+    /* Only set to supported backends, see `SHA::set_backend`. The hardened modes
+     * always use the scalar code. */
+    pub(crate) backend: SHABackend,
+}
+
+impl SHA1Ctx {
+    pub fn mode(&self) -> SHA1Mode {
+        self.mode
+    }
+
+    /// Changes the mode for the next message. Fails once `update` has been called
+    /// since `init`, a message is hashed in one mode.
+    pub fn set_mode(&mut self, mode: SHA1Mode) -> Result<Success, Error> {
+        if self.data.count != 0 {
+            return Err(Error::Err);
+        }
+
+        self.mode = mode;
+
+        Ok(Success::OK)
+    }
+
+    /// Whether a collision block has been found in the current message. It is also
+    /// reported by `finish` and stays set until `init`.
+    pub fn collision(&self) -> bool {
+        self.collision
+    }
+
+    pub(crate) fn _reset(&mut self) {
+        Self::_init(&mut self.data);
+        self.collision = false;
+    }
+
+    pub(crate) fn _update(&mut self, input: &[u8]) -> Result<Success, Error> {
+        match self.mode {
+            SHA1Mode::Standard => Self::_process_with(&mut self.data, input, |state, blocks| self.backend._sha1(state, blocks)),
+            mode => {
+                let collision = &mut self.collision;
+                Self::_process_with(&mut self.data, input, |state, blocks| _transform_cd(state, blocks, mode, collision))
+            }
+        }
+    }
+
+    /* The digest is written in any case, the error reports a collision attack. */
+    pub(crate) fn _digest(&mut self, output: &mut [u8]) -> Result<Success, Error> {
+        match self.mode {
+            SHA1Mode::Standard => Self::_finish_with(&mut self.data, output, |state, blocks| self.backend._sha1(state, blocks)),
+            mode => {
+                let collision = &mut self.collision;
+                Self::_finish_with(&mut self.data, output, |state, blocks| _transform_cd(state, blocks, mode, collision))?;
+                if self.collision {
+                    return Err(Error::CollisionDetected);
+                }
+
+                Ok(Success::OK)
+            }
+        }
+    }
+}
 
 impl Operations<SHA1_BLOCKSIZE, SHA1_DIGEST_SIZE, SHA1_STATE_SIZE, u32> for SHA1Ctx {
 
@@ -89,7 +174,7 @@ impl Operations<SHA1_BLOCKSIZE, SHA1_DIGEST_SIZE, SHA1_STATE_SIZE, u32> for SHA1
     }
 }
 
-fn sha1f(x: u32, y: u32, z: u32, t: usize) -> u32 {
+pub(crate) fn sha1f(x: u32, y: u32, z: u32, t: usize) -> u32 {
         let mut res: u32 = 0;
 
         if 19 >= t{
@@ -105,7 +190,7 @@ fn sha1f(x: u32, y: u32, z: u32, t: usize) -> u32 {
         return res;
 }
 
-fn sha1k(t :usize) -> u32 {
+pub(crate) fn sha1k(t :usize) -> u32 {
         let mut res: u32 = 0;
 
         if 19 >= t {
@@ -123,3 +208,195 @@ fn sha1k(t :usize) -> u32 {
 
         return res;
 }
+
+/* The disturbance vectors of sha1collisiondetection with the step to recompress
+ * from and the first 16 words of the message difference they cause. The difference
+ * expands like a message, so the other 64 words follow from these. */
+static SHA1_DVS: [(usize, [u32; 16]); 32] = [
+    /* I(43,0) */
+    (58, [0x08000000, 0x9800000c, 0xd8000010, 0x08000010, 0xb8000010, 0x98000000, 0x60000000, 0x00000008,
+           0xc0000000, 0x90000014, 0x10000010, 0xb8000014, 0x28000000, 0x20000010, 0x48000000, 0x08000018]),
+    /* I(44,0) */
+    (58, [0xb4000008, 0x08000000, 0x9800000c, 0xd8000010, 0x08000010, 0xb8000010, 0x98000000, 0x60000000,
+           0x00000008, 0xc0000000, 0x90000014, 0x10000010, 0xb8000014, 0x28000000, 0x20000010, 0x48000000]),
+    /* I(45,0) */
+    (58, [0xf4000014, 0xb4000008, 0x08000000, 0x9800000c, 0xd8000010, 0x08000010, 0xb8000010, 0x98000000,
+           0x60000000, 0x00000008, 0xc0000000, 0x90000014, 0x10000010, 0xb8000014, 0x28000000, 0x20000010]),
+    /* I(46,0) */
+    (58, [0x2c000010, 0xf4000014, 0xb4000008, 0x08000000, 0x9800000c, 0xd8000010, 0x08000010, 0xb8000010,
+           0x98000000, 0x60000000, 0x00000008, 0xc0000000, 0x90000014, 0x10000010, 0xb8000014, 0x28000000]),
+    /* I(46,2) */
+    (58, [0xb0000040, 0xd0000053, 0xd0000022, 0x20000000, 0x60000032, 0x60000043, 0x20000040, 0xe0000042,
+           0x60000002, 0x80000001, 0x00000020, 0x00000003, 0x40000052, 0x40000040, 0xe0000052, 0xa0000000]),
+    /* I(47,0) */
+    (58, [0xc8000010, 0x2c000010, 0xf4000014, 0xb4000008, 0x08000000, 0x9800000c, 0xd8000010, 0x08000010,
+           0xb8000010, 0x98000000, 0x60000000, 0x00000008, 0xc0000000, 0x90000014, 0x10000010, 0xb8000014]),
+    /* I(47,2) */
+    (58, [0x20000043, 0xb0000040, 0xd0000053, 0xd0000022, 0x20000000, 0x60000032, 0x60000043, 0x20000040,
+           0xe0000042, 0x60000002, 0x80000001, 0x00000020, 0x00000003, 0x40000052, 0x40000040, 0xe0000052]),
+    /* I(48,0) */
+    (58, [0xb800000a, 0xc8000010, 0x2c000010, 0xf4000014, 0xb4000008, 0x08000000, 0x9800000c, 0xd8000010,
+           0x08000010, 0xb8000010, 0x98000000, 0x60000000, 0x00000008, 0xc0000000, 0x90000014, 0x10000010]),
+    /* I(48,2) */
+    (58, [0xe000002a, 0x20000043, 0xb0000040, 0xd0000053, 0xd0000022, 0x20000000, 0x60000032, 0x60000043,
+           0x20000040, 0xe0000042, 0x60000002, 0x80000001, 0x00000020, 0x00000003, 0x40000052, 0x40000040]),
+    /* I(49,0) */
+    (58, [0x18000000, 0xb800000a, 0xc8000010, 0x2c000010, 0xf4000014, 0xb4000008, 0x08000000, 0x9800000c,
+           0xd8000010, 0x08000010, 0xb8000010, 0x98000000, 0x60000000, 0x00000008, 0xc0000000, 0x90000014]),
+    /* I(49,2) */
+    (58, [0x60000000, 0xe000002a, 0x20000043, 0xb0000040, 0xd0000053, 0xd0000022, 0x20000000, 0x60000032,
+           0x60000043, 0x20000040, 0xe0000042, 0x60000002, 0x80000001, 0x00000020, 0x00000003, 0x40000052]),
+    /* I(50,0) */
+    (65, [0x0800000c, 0x18000000, 0xb800000a, 0xc8000010, 0x2c000010, 0xf4000014, 0xb4000008, 0x08000000,
+           0x9800000c, 0xd8000010, 0x08000010, 0xb8000010, 0x98000000, 0x60000000, 0x00000008, 0xc0000000]),
+    /* I(50,2) */
+    (65, [0x20000030, 0x60000000, 0xe000002a, 0x20000043, 0xb0000040, 0xd0000053, 0xd0000022, 0x20000000,
+           0x60000032, 0x60000043, 0x20000040, 0xe0000042, 0x60000002, 0x80000001, 0x00000020, 0x00000003]),
+    /* I(51,0) */
+    (65, [0xe8000000, 0x0800000c, 0x18000000, 0xb800000a, 0xc8000010, 0x2c000010, 0xf4000014, 0xb4000008,
+           0x08000000, 0x9800000c, 0xd8000010, 0x08000010, 0xb8000010, 0x98000000, 0x60000000, 0x00000008]),
+    /* I(51,2) */
+    (65, [0xa0000003, 0x20000030, 0x60000000, 0xe000002a, 0x20000043, 0xb0000040, 0xd0000053, 0xd0000022,
+           0x20000000, 0x60000032, 0x60000043, 0x20000040, 0xe0000042, 0x60000002, 0x80000001, 0x00000020]),
+    /* I(52,0) */
+    (65, [0x04000010, 0xe8000000, 0x0800000c, 0x18000000, 0xb800000a, 0xc8000010, 0x2c000010, 0xf4000014,
+           0xb4000008, 0x08000000, 0x9800000c, 0xd8000010, 0x08000010, 0xb8000010, 0x98000000, 0x60000000]),
+    /* II(45,0) */
+    (58, [0xec000014, 0x0c000002, 0xc0000010, 0xb400001c, 0x2c000004, 0xbc000018, 0xb0000010, 0x0000000c,
+           0xb8000010, 0x08000018, 0x78000010, 0x08000014, 0x70000010, 0xb800001c, 0xe8000000, 0xb0000004]),
+    /* II(46,0) */
+    (58, [0x2400001c, 0xec000014, 0x0c000002, 0xc0000010, 0xb400001c, 0x2c000004, 0xbc000018, 0xb0000010,
+           0x0000000c, 0xb8000010, 0x08000018, 0x78000010, 0x08000014, 0x70000010, 0xb800001c, 0xe8000000]),
+    /* II(46,2) */
+    (58, [0x90000070, 0xb0000053, 0x30000008, 0x00000043, 0xd0000072, 0xb0000010, 0xf0000062, 0xc0000042,
+           0x00000030, 0xe0000042, 0x20000060, 0xe0000041, 0x20000050, 0xc0000041, 0xe0000072, 0xa0000003]),
+    /* II(47,0) */
+    (58, [0x20000010, 0x2400001c, 0xec000014, 0x0c000002, 0xc0000010, 0xb400001c, 0x2c000004, 0xbc000018,
+           0xb0000010, 0x0000000c, 0xb8000010, 0x08000018, 0x78000010, 0x08000014, 0x70000010, 0xb800001c]),
+    /* II(48,0) */
+    (58, [0xbc00001a, 0x20000010, 0x2400001c, 0xec000014, 0x0c000002, 0xc0000010, 0xb400001c, 0x2c000004,
+           0xbc000018, 0xb0000010, 0x0000000c, 0xb8000010, 0x08000018, 0x78000010, 0x08000014, 0x70000010]),
+    /* II(49,0) */
+    (58, [0x3c000004, 0xbc00001a, 0x20000010, 0x2400001c, 0xec000014, 0x0c000002, 0xc0000010, 0xb400001c,
+           0x2c000004, 0xbc000018, 0xb0000010, 0x0000000c, 0xb8000010, 0x08000018, 0x78000010, 0x08000014]),
+    /* II(49,2) */
+    (58, [0xf0000010, 0xf000006a, 0x80000040, 0x90000070, 0xb0000053, 0x30000008, 0x00000043, 0xd0000072,
+           0xb0000010, 0xf0000062, 0xc0000042, 0x00000030, 0xe0000042, 0x20000060, 0xe0000041, 0x20000050]),
+    /* II(50,0) */
+    (65, [0xb400001c, 0x3c000004, 0xbc00001a, 0x20000010, 0x2400001c, 0xec000014, 0x0c000002, 0xc0000010,
+           0xb400001c, 0x2c000004, 0xbc000018, 0xb0000010, 0x0000000c, 0xb8000010, 0x08000018, 0x78000010]),
+    /* II(50,2) */
+    (65, [0xd0000072, 0xf0000010, 0xf000006a, 0x80000040, 0x90000070, 0xb0000053, 0x30000008, 0x00000043,
+           0xd0000072, 0xb0000010, 0xf0000062, 0xc0000042, 0x00000030, 0xe0000042, 0x20000060, 0xe0000041]),
+    /* II(51,0) */
+    (65, [0xc0000010, 0xb400001c, 0x3c000004, 0xbc00001a, 0x20000010, 0x2400001c, 0xec000014, 0x0c000002,
+           0xc0000010, 0xb400001c, 0x2c000004, 0xbc000018, 0xb0000010, 0x0000000c, 0xb8000010, 0x08000018]),
+    /* II(51,2) */
+    (65, [0x00000043, 0xd0000072, 0xf0000010, 0xf000006a, 0x80000040, 0x90000070, 0xb0000053, 0x30000008,
+           0x00000043, 0xd0000072, 0xb0000010, 0xf0000062, 0xc0000042, 0x00000030, 0xe0000042, 0x20000060]),
+    /* II(52,0) */
+    (65, [0x0c000002, 0xc0000010, 0xb400001c, 0x3c000004, 0xbc00001a, 0x20000010, 0x2400001c, 0xec000014,
+           0x0c000002, 0xc0000010, 0xb400001c, 0x2c000004, 0xbc000018, 0xb0000010, 0x0000000c, 0xb8000010]),
+    /* II(53,0) */
+    (65, [0xcc000014, 0x0c000002, 0xc0000010, 0xb400001c, 0x3c000004, 0xbc00001a, 0x20000010, 0x2400001c,
+           0xec000014, 0x0c000002, 0xc0000010, 0xb400001c, 0x2c000004, 0xbc000018, 0xb0000010, 0x0000000c]),
+    /* II(54,0) */
+    (65, [0x0400001c, 0xcc000014, 0x0c000002, 0xc0000010, 0xb400001c, 0x3c000004, 0xbc00001a, 0x20000010,
+           0x2400001c, 0xec000014, 0x0c000002, 0xc0000010, 0xb400001c, 0x2c000004, 0xbc000018, 0xb0000010]),
+    /* II(55,0) */
+    (65, [0x00000010, 0x0400001c, 0xcc000014, 0x0c000002, 0xc0000010, 0xb400001c, 0x3c000004, 0xbc00001a,
+           0x20000010, 0x2400001c, 0xec000014, 0x0c000002, 0xc0000010, 0xb400001c, 0x2c000004, 0xbc000018]),
+    /* II(56,0) */
+    (65, [0x2600001a, 0x00000010, 0x0400001c, 0xcc000014, 0x0c000002, 0xc0000010, 0xb400001c, 0x3c000004,
+           0xbc00001a, 0x20000010, 0x2400001c, 0xec000014, 0x0c000002, 0xc0000010, 0xb400001c, 0x2c000004]),
+];
+
+fn _expand(w: &mut [u32; 80]) {
+    for t in 16..80 {
+        w[t] = (w[t - 3] ^ w[t - 8] ^ w[t - 14] ^ w[t - 16]).rotate_left(1);
+    }
+}
+
+/* Step t on the state (a, b, c, d, e) before it, and its inverse. */
+fn _step(s: &mut [u32; 5], w: &[u32; 80], t: usize) {
+    let tmp = s[0].rotate_left(5)
+        .wrapping_add(sha1f(s[1], s[2], s[3], t))
+        .wrapping_add(s[4])
+        .wrapping_add(w[t])
+        .wrapping_add(sha1k(t));
+    *s = [tmp, s[0], s[1].rotate_left(30), s[2], s[3]];
+}
+
+fn _step_back(s: &mut [u32; 5], w: &[u32; 80], t: usize) {
+    let (a, b, c, d) = (s[1], s[2].rotate_right(30), s[3], s[4]);
+    let e = s[0].wrapping_sub(a.rotate_left(5)
+        .wrapping_add(sha1f(b, c, d, t))
+        .wrapping_add(w[t])
+        .wrapping_add(sha1k(t)));
+    *s = [a, b, c, d, e];
+}
+
+fn _compress(ihv: &mut [u32; 5], w: &[u32; 80], mut states: Option<&mut [[u32; 5]; 2]>) {
+    let mut s = *ihv;
+    for t in 0..80 {
+        match (t, states.as_deref_mut()) {
+            (58, Some(st)) => st[0] = s,
+            (65, Some(st)) => st[1] = s,
+            _ => {}
+        }
+        _step(&mut s, w, t);
+    }
+
+    for (h, x) in ihv.iter_mut().zip(s.iter()) {
+        *h = h.wrapping_add(*x);
+    }
+}
+
+/* SHA-1 compression with collision detection: for every disturbance vector the
+ * block with the message difference applied is computed backwards and forwards
+ * from the state at the step where the difference vanishes. If it ends in the same
+ * chaining value from another one, the block is half of a near collision. */
+fn _transform_cd(state: &mut [u32; SHA1_STATE_SIZE], input: &[u8], mode: SHA1Mode, collision: &mut bool) {
+    let mut m1 = [0u32; 80];
+    let mut m2 = [0u32; 80];
+    let mut states = [[0u32; 5]; 2];
+
+    for block in input.chunks_exact(SHA1_BLOCKSIZE) {
+        for (w, b) in m1.iter_mut().zip(block.chunks_exact(4)) {
+            *w = u32::from_be_bytes([b[0], b[1], b[2], b[3]]);
+        }
+        _expand(&mut m1);
+        _compress(state, &m1, Some(&mut states));
+
+        for (step, dm) in SHA1_DVS.iter() {
+            for i in 0..16 {
+                m2[i] = m1[i] ^ dm[i];
+            }
+            _expand(&mut m2);
+
+            let mid = if *step == 58 { states[0] } else { states[1] };
+            let mut ihv = mid;
+            for t in (0..*step).rev() {
+                _step_back(&mut ihv, &m2, t);
+            }
+            let mut s = mid;
+            for t in *step..80 {
+                _step(&mut s, &m2, t);
+            }
+            for (h, x) in ihv.iter_mut().zip(s.iter()) {
+                *h = h.wrapping_add(*x);
+            }
+
+            if ihv == *state {
+                *collision = true;
+                if mode == SHA1Mode::SafeHash {
+                    _compress(state, &m1, None);
+                    _compress(state, &m1, None);
+                }
+                break;
+            }
+        }
+    }
+    m1.fill(0);
+    m2.fill(0);
+}
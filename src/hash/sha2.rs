@@ -1,5 +1,6 @@
 use crate::hash::Operations;
 use crate::hash::HashDataCtx;
+use crate::hash::backend::SHABackend;
 use num::Zero;
 use num_traits::PrimInt;
 use core::ops::BitAnd;
@@ -26,13 +27,14 @@ pub const SHA2_STATE_SIZE: usize = 8;
 trait SHA512Like {}
 trait SHA256Like {}
 
-#[derive(Debug)]
-pub struct SHA256Ctx { pub data: HashDataCtx<SHA256_BLOCKSIZE, SHA2_STATE_SIZE, u32> }
-#[derive(Debug)]
-pub struct SHA224Ctx { pub data: HashDataCtx<SHA256_BLOCKSIZE, SHA2_STATE_SIZE, u32> }
-#[derive(Debug)]
+/* `backend` is only set to supported backends, see `SHA::set_backend`. */
+#[derive(Debug, Clone)]
+pub struct SHA256Ctx { pub data: HashDataCtx<SHA256_BLOCKSIZE, SHA2_STATE_SIZE, u32>, pub(crate) backend: SHABackend }
+#[derive(Debug, Clone)]
+pub struct SHA224Ctx { pub data: HashDataCtx<SHA256_BLOCKSIZE, SHA2_STATE_SIZE, u32>, pub(crate) backend: SHABackend }
+#[derive(Debug, Clone)]
 pub struct SHA512Ctx { pub data: HashDataCtx<SHA512_BLOCKSIZE, SHA2_STATE_SIZE, u64> }
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SHA384Ctx { pub data: HashDataCtx<SHA512_BLOCKSIZE, SHA2_STATE_SIZE, u64> }
 
 impl SHA256Like for SHA256Ctx {}
@@ -40,7 +42,7 @@ impl SHA256Like for SHA224Ctx {}
 impl SHA512Like for SHA512Ctx {}
 impl SHA512Like for SHA384Ctx {}
 
-static K256: [u32; 64] = [
+pub(crate) static K256: [u32; 64] = [
     0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
     0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
     0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
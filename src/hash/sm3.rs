@@ -0,0 +1,87 @@
+use crate::hash::Operations;
+use crate::hash::HashDataCtx;
+
+pub const SM3_DIGEST_SIZE: usize = 32;
+pub const SM3_BLOCKSIZE: usize = 64;
+pub const SM3_STATE_SIZE: usize = 8;
+
+/// SM3 (GB/T 32905-2016), the hash function of the Chinese commercial cryptography
+/// standards.
+#[derive(Debug, Clone)]
+pub struct SM3Ctx { pub data: HashDataCtx<SM3_BLOCKSIZE, SM3_STATE_SIZE, u32> }
+
+impl Operations<SM3_BLOCKSIZE, SM3_DIGEST_SIZE, SM3_STATE_SIZE, u32> for SM3Ctx {
+
+    fn _transform(state: &mut [u32; SM3_STATE_SIZE], input: &[u8]) {
+        let mut w = [0u32; 68];
+
+        for block in input.chunks_exact(SM3_BLOCKSIZE) {
+            /* Message expansion. W'[j] = W[j] ^ W[j + 4] is computed in the rounds. */
+            for (wj, b) in w.iter_mut().zip(block.chunks_exact(4)) {
+                *wj = u32::from_be_bytes([b[0], b[1], b[2], b[3]]);
+            }
+            for j in 16..68 {
+                w[j] = _p1(w[j - 16] ^ w[j - 9] ^ w[j - 3].rotate_left(15)) ^ w[j - 13].rotate_left(7) ^ w[j - 6];
+            }
+
+            let (mut a, mut b, mut c, mut d) = (state[0], state[1], state[2], state[3]);
+            let (mut e, mut f, mut g, mut h) = (state[4], state[5], state[6], state[7]);
+
+            for j in 0..64 {
+                let (t, ff, gg) = if j < 16 {
+                    (0x79cc4519u32, a ^ b ^ c, e ^ f ^ g)
+                } else {
+                    (0x7a879d8au32, (a & b) | (a & c) | (b & c), (e & f) | (!e & g))
+                };
+
+                let a12 = a.rotate_left(12);
+                let ss1 = a12.wrapping_add(e).wrapping_add(t.rotate_left(j as u32 % 32)).rotate_left(7);
+                let ss2 = ss1 ^ a12;
+                let tt1 = ff.wrapping_add(d).wrapping_add(ss2).wrapping_add(w[j] ^ w[j + 4]);
+                let tt2 = gg.wrapping_add(h).wrapping_add(ss1).wrapping_add(w[j]);
+
+                d = c;
+                c = b.rotate_left(9);
+                b = a;
+                a = tt1;
+                h = g;
+                g = f.rotate_left(19);
+                f = e;
+                e = _p0(tt2);
+            }
+
+            state[0] ^= a;
+            state[1] ^= b;
+            state[2] ^= c;
+            state[3] ^= d;
+            state[4] ^= e;
+            state[5] ^= f;
+            state[6] ^= g;
+            state[7] ^= h;
+        }
+        w.fill(0);
+    }
+
+    fn _init(ctx: &mut HashDataCtx<SM3_BLOCKSIZE, SM3_STATE_SIZE, u32>) {
+        ctx.count = 0;
+        ctx.rem_pos = 0;
+        ctx.buffer.fill(0);
+
+        ctx.state[0] = 0x7380166f;
+        ctx.state[1] = 0x4914b2b9;
+        ctx.state[2] = 0x172442d7;
+        ctx.state[3] = 0xda8a0600;
+        ctx.state[4] = 0xa96f30bc;
+        ctx.state[5] = 0x163138aa;
+        ctx.state[6] = 0xe38dee4d;
+        ctx.state[7] = 0xb0fb0e4e;
+    }
+}
+
+fn _p0(x: u32) -> u32 {
+    x ^ x.rotate_left(9) ^ x.rotate_left(17)
+}
+
+fn _p1(x: u32) -> u32 {
+    x ^ x.rotate_left(15) ^ x.rotate_left(23)
+}
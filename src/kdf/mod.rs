@@ -0,0 +1,98 @@
+use crate::common::api::{MAC, StreamingAPI};
+use crate::common::{Success, Error};
+use crate::hash::{SHA, MAX_DIGEST_SIZE};
+use crate::mac::HMAC;
+
+/* All KDFs use a 32-bit big-endian block counter starting at 1. */
+const MAX_REPS: u64 = u32::MAX as u64;
+
+/// ANSI X9.63 KDF (SEC 1 v2, section 3.6.1).
+///
+/// Fills `output` with `H(Z || counter || SharedInfo)` blocks.
+pub fn x963_kdf(
+    hash: &mut SHA,
+    z: &[u8],
+    shared_info: &[u8],
+    output: &mut [u8],
+) -> Result<Success, Error> {
+    let hlen = hash.digest_size();
+    _counter_kdf(hash, hlen, z, shared_info, output, false)
+}
+
+/// NIST SP 800-56C Rev. 2 One-Step KDF with a hash function as auxiliary function
+/// (option 1).
+///
+/// Fills `output` with `H(counter || Z || OtherInfo)` blocks.
+pub fn one_step_kdf(
+    hash: &mut SHA,
+    z: &[u8],
+    other_info: &[u8],
+    output: &mut [u8],
+) -> Result<Success, Error> {
+    let hlen = hash.digest_size();
+    _counter_kdf(hash, hlen, z, other_info, output, true)
+}
+
+/// NIST SP 800-56C Rev. 2 One-Step KDF with HMAC as auxiliary function (option 2).
+///
+/// The salt is the key of `hmac`. If no salt is agreed upon, SP 800-56C asks for an
+/// all zero string of the hash block length, which is what an empty HMAC key yields.
+pub fn one_step_kdf_hmac(
+    hmac: &mut HMAC,
+    z: &[u8],
+    other_info: &[u8],
+    output: &mut [u8],
+) -> Result<Success, Error> {
+    let hlen = hmac.mac_size();
+    _counter_kdf(hmac, hlen, z, other_info, output, true)
+}
+
+/// NIST SP 800-108 KDF in counter mode with any `MAC` as PRF, e.g. HMAC or CMAC.
+///
+/// The key derivation key is the key of `mac`. Fills `output` with
+/// `PRF(KI, counter || FixedInputData)` blocks, the counter is 32 bits wide. The
+/// caller encodes Label, Context and L into `fixed_input`.
+pub fn kbkdf_counter<M: MAC>(
+    mac: &mut M,
+    fixed_input: &[u8],
+    output: &mut [u8],
+) -> Result<Success, Error> {
+    let hlen = mac.mac_size();
+    _counter_kdf(mac, hlen, &[], fixed_input, output, true)
+}
+
+fn _counter_kdf<P: StreamingAPI>(
+    prim: &mut P,
+    hlen: usize,
+    z: &[u8],
+    info: &[u8],
+    output: &mut [u8],
+    counter_first: bool,
+) -> Result<Success, Error> {
+    let reps = output.len().div_ceil(hlen) as u64;
+    if reps > MAX_REPS {
+        return Err(Error::CounterOverflow);
+    }
+
+    let mut block = [0u8; MAX_DIGEST_SIZE];
+    let mut counter: u32 = 1;
+
+    for chunk in output.chunks_mut(hlen) {
+        prim.init()?;
+        if counter_first {
+            prim.update(&counter.to_be_bytes())?;
+            prim.update(z)?;
+        } else {
+            prim.update(z)?;
+            prim.update(&counter.to_be_bytes())?;
+        }
+        prim.update(info)?;
+        prim.finish(&mut block[..hlen])?;
+
+        chunk.copy_from_slice(&block[..chunk.len()]);
+        counter = counter.wrapping_add(1);
+    }
+    block.fill(0);
+
+    Ok(Success::OK)
+}
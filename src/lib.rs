@@ -1,4 +1,12 @@
 #![no_std]
+#[cfg(feature = "std")]
+extern crate std;
+
 pub mod hash;
+pub mod mac;
+pub mod cipher;
+pub mod kdf;
+pub mod drbg;
+pub mod entropy;
 pub mod utils;
 pub mod common;
@@ -0,0 +1,124 @@
+use crate::cipher::AES_BLOCKSIZE;
+use crate::common::api::{BlockCipher, MAC, StreamingAPI, DefaultInit, SingleInputUpdate, SingleOutputFinish};
+use crate::common::{Success, Error};
+
+/* R_128 from SP 800-38B, section 5.3. */
+const RB: u8 = 0x87;
+
+/// CMAC (SP 800-38B, RFC 4493) over a block cipher with 128-bit blocks.
+///
+/// As with `HMAC` the context is keyed on construction and `init` starts a new
+/// message. The output of `finish` is truncated to the length of the output buffer.
+#[derive(Debug, Clone)]
+pub struct CMAC<C: BlockCipher<AES_BLOCKSIZE>> {
+    cipher: C,
+    k1: [u8; AES_BLOCKSIZE],
+    k2: [u8; AES_BLOCKSIZE],
+    x: [u8; AES_BLOCKSIZE],
+    buffer: [u8; AES_BLOCKSIZE],
+    rem_pos: usize,
+}
+
+impl<C: BlockCipher<AES_BLOCKSIZE>> CMAC<C> {
+    pub fn new(key: &[u8]) -> Result<Self, Error> {
+        let cipher = C::new(key)?;
+
+        /* Subkeys K1 = dbl(E(0)) and K2 = dbl(K1). */
+        let mut l = [0u8; AES_BLOCKSIZE];
+        cipher.encrypt_block(&mut l);
+        let k1 = dbl(&l);
+        let k2 = dbl(&k1);
+        l.fill(0);
+
+        Ok(CMAC {
+            cipher,
+            k1,
+            k2,
+            x: [0; AES_BLOCKSIZE],
+            buffer: [0; AES_BLOCKSIZE],
+            rem_pos: 0,
+        })
+    }
+
+    fn _process_buffer(&mut self) {
+        for (x, b) in self.x.iter_mut().zip(self.buffer.iter()) {
+            *x ^= b;
+        }
+        self.cipher.encrypt_block(&mut self.x);
+    }
+}
+
+/// Doubling in GF(2^128) as used for the CMAC subkeys and by S2V: a left shift by
+/// one bit, reduced by x^128 + x^7 + x^2 + x + 1 without branching on the secret.
+pub(crate) fn dbl(block: &[u8; AES_BLOCKSIZE]) -> [u8; AES_BLOCKSIZE] {
+    let v = u128::from_be_bytes(*block);
+    let reduce = (v >> 127) as u8;
+
+    let mut out = (v << 1).to_be_bytes();
+    out[AES_BLOCKSIZE - 1] ^= reduce.wrapping_neg() & RB;
+
+    out
+}
+
+impl<C: BlockCipher<AES_BLOCKSIZE>> StreamingAPI for CMAC<C> {}
+
+impl<C: BlockCipher<AES_BLOCKSIZE>> MAC for CMAC<C> {
+    fn mac_size(&self) -> usize {
+        AES_BLOCKSIZE
+    }
+}
+
+impl<C: BlockCipher<AES_BLOCKSIZE>> DefaultInit for CMAC<C> {
+    fn init(&mut self) -> Result<Success, Error> {
+        self.x.fill(0);
+        self.buffer.fill(0);
+        self.rem_pos = 0;
+
+        Ok(Success::OK)
+    }
+}
+
+impl<C: BlockCipher<AES_BLOCKSIZE>> SingleInputUpdate for CMAC<C> {
+    /* The last block gets a subkey added, so a full buffer is only processed once
+     * more input arrives. */
+    fn update(&mut self, mut input: &[u8]) -> Result<Success, Error> {
+        while !input.is_empty() {
+            if self.rem_pos == AES_BLOCKSIZE {
+                self._process_buffer();
+                self.rem_pos = 0;
+            }
+
+            let take = usize::min(AES_BLOCKSIZE - self.rem_pos, input.len());
+            self.buffer[self.rem_pos..self.rem_pos + take].copy_from_slice(&input[..take]);
+            self.rem_pos += take;
+            input = &input[take..];
+        }
+
+        Ok(Success::OK)
+    }
+}
+
+impl<C: BlockCipher<AES_BLOCKSIZE>> SingleOutputFinish for CMAC<C> {
+    fn finish(&mut self, output: &mut [u8]) -> Result<Success, Error> {
+        if output.len() > AES_BLOCKSIZE {
+            return Err(Error::Err);
+        }
+
+        /* A complete last block is masked with K1, a partial one is padded with
+         * 10...0 and masked with K2. */
+        let subkey = if self.rem_pos == AES_BLOCKSIZE {
+            self.k1
+        } else {
+            self.buffer[self.rem_pos] = 0x80;
+            self.buffer[self.rem_pos + 1..].fill(0);
+            self.k2
+        };
+        for (b, k) in self.buffer.iter_mut().zip(subkey.iter()) {
+            *b ^= k;
+        }
+        self._process_buffer();
+
+        output.copy_from_slice(&self.x[..output.len()]);
+        self.init()
+    }
+}
@@ -0,0 +1,90 @@
+use crate::cipher::{AES_BLOCKSIZE, GCM, GHASH};
+use crate::common::api::{AEAD, BlockCipher};
+use crate::common::{Success, Error};
+
+/// GMAC (SP 800-38D), GCM with the whole message as associated data and an empty
+/// plaintext.
+///
+/// Every MAC needs a fresh IV: `init` takes the IV of the message and `finish`
+/// consumes it, so an IV cannot be reused by accident. GMAC is not a PRF and
+/// therefore does not implement `MAC`: it cannot back the KBKDF or S2V, which
+/// restart the PRF under the same key for every block.
+///
+/// ```compile_fail
+/// use rucola::cipher::AES128;
+/// use rucola::kdf::kbkdf_counter;
+/// use rucola::mac::GMAC;
+///
+/// let mut gmac = GMAC::<AES128>::new(&[0u8; 16]).unwrap();
+/// kbkdf_counter(&mut gmac, b"", &mut [0u8; 32]).unwrap();
+/// ```
+///
+/// ```compile_fail
+/// use rucola::cipher::{AES128, SIV};
+/// use rucola::mac::GMAC;
+///
+/// let gmac = GMAC::<AES128>::new(&[0u8; 16]).unwrap();
+/// SIV::<AES128, _>::with_mac(gmac, &[0u8; 16]).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct GMAC<C: BlockCipher<AES_BLOCKSIZE>> {
+    gcm: GCM<C>,
+    ghash: GHASH,
+    j0: [u8; AES_BLOCKSIZE],
+    msg_len: u64,
+    iv_set: bool,
+}
+
+impl<C: BlockCipher<AES_BLOCKSIZE>> GMAC<C> {
+    pub fn new(key: &[u8]) -> Result<Self, Error> {
+        let gcm = GCM::new(key)?;
+        let ghash = GHASH::new(&gcm.h);
+
+        Ok(GMAC {
+            gcm,
+            ghash,
+            j0: [0; AES_BLOCKSIZE],
+            msg_len: 0,
+            iv_set: false,
+        })
+    }
+
+    /// Size of the untruncated MAC in bytes.
+    pub fn mac_size(&self) -> usize {
+        AES_BLOCKSIZE
+    }
+
+    /// Starts a message under `iv`.
+    pub fn init(&mut self, iv: &[u8]) -> Result<Success, Error> {
+        self.j0 = self.gcm._j0(iv)?;
+        self.ghash = GHASH::new(&self.gcm.h);
+        self.msg_len = 0;
+        self.iv_set = true;
+
+        Ok(Success::OK)
+    }
+
+    pub fn update(&mut self, input: &[u8]) -> Result<Success, Error> {
+        if !self.iv_set {
+            return Err(Error::Err);
+        }
+
+        self.ghash.update(input);
+        self.msg_len += input.len() as u64;
+
+        Ok(Success::OK)
+    }
+
+    /// Writes the MAC truncated to the length of `output` and consumes the IV.
+    pub fn finish(&mut self, output: &mut [u8]) -> Result<Success, Error> {
+        if !self.iv_set || output.len() > AES_BLOCKSIZE {
+            return Err(Error::Err);
+        }
+
+        self.gcm._finish_tag(&self.j0, &mut self.ghash, self.msg_len, 0, output);
+        self.j0.fill(0);
+        self.iv_set = false;
+
+        Ok(Success::OK)
+    }
+}
@@ -0,0 +1,107 @@
+use crate::common::api::{MAC, StreamingAPI, DefaultInit, SingleInputUpdate, SingleOutputFinish};
+use crate::common::{Success, Error};
+use crate::hash::{SHA, MAX_BLOCKSIZE, MAX_DIGEST_SIZE};
+
+const IPAD: u8 = 0x36;
+const OPAD: u8 = 0x5c;
+
+/// HMAC (FIPS 198-1) over any variant of `SHA`.
+///
+/// The context is keyed once on construction (or via `set_key`) and can then be
+/// reused for any number of messages by calling `init` before each one. The output
+/// of `finish` is truncated to the length of the output buffer.
+#[derive(Debug, Clone)]
+pub struct HMAC {
+    hash: SHA,
+    /* K0 from FIPS 198-1, i.e. the key padded (or hashed and padded) to the block size. */
+    key: [u8; MAX_BLOCKSIZE],
+}
+
+impl HMAC {
+    pub fn new(hash: SHA, key: &[u8]) -> Result<Self, Error> {
+        let mut hmac = HMAC {
+            hash,
+            key: [0; MAX_BLOCKSIZE],
+        };
+        hmac.set_key(key)?;
+
+        Ok(hmac)
+    }
+
+    pub fn set_key(&mut self, key: &[u8]) -> Result<Success, Error> {
+        let bs = self.hash.block_size();
+        self.key.fill(0);
+
+        /* Keys longer than a block are hashed first. */
+        if key.len() > bs {
+            let ds = self.hash.digest_size();
+            self.hash.init()?;
+            self.hash.update(key)?;
+            self.hash.finish(&mut self.key[..ds])?;
+        } else {
+            self.key[..key.len()].copy_from_slice(key);
+        }
+
+        Ok(Success::OK)
+    }
+
+    fn _pad_key(&self, pad: u8, block: &mut [u8; MAX_BLOCKSIZE]) {
+        for (b, k) in block.iter_mut().zip(self.key.iter()) {
+            *b = k ^ pad;
+        }
+    }
+}
+
+impl StreamingAPI for HMAC {}
+
+impl MAC for HMAC {
+    fn mac_size(&self) -> usize {
+        self.hash.digest_size()
+    }
+}
+
+impl DefaultInit for HMAC {
+    fn init(&mut self) -> Result<Success, Error> {
+        let bs = self.hash.block_size();
+        let mut block = [0u8; MAX_BLOCKSIZE];
+        self._pad_key(IPAD, &mut block);
+
+        self.hash.init()?;
+        let res = self.hash.update(&block[..bs]);
+        block.fill(0);
+
+        res
+    }
+}
+
+impl SingleInputUpdate for HMAC {
+    fn update(&mut self, input: &[u8]) -> Result<Success, Error> {
+        self.hash.update(input)
+    }
+}
+
+impl SingleOutputFinish for HMAC {
+    fn finish(&mut self, output: &mut [u8]) -> Result<Success, Error> {
+        let bs = self.hash.block_size();
+        let ds = self.hash.digest_size();
+        if output.len() > ds {
+            return Err(Error::Err);
+        }
+
+        let mut inner = [0u8; MAX_DIGEST_SIZE];
+        let mut block = [0u8; MAX_BLOCKSIZE];
+
+        /* H((K0 ^ opad) || H((K0 ^ ipad) || text)) */
+        let res = self.hash.finish(&mut inner[..ds]).and_then(|_| {
+            self._pad_key(OPAD, &mut block);
+            self.hash.init()?;
+            self.hash.update(&block[..bs])?;
+            self.hash.update(&inner[..ds])?;
+            self.hash.finish(output)
+        });
+        block.fill(0);
+        inner.fill(0);
+
+        res
+    }
+}
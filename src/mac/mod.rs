@@ -0,0 +1,11 @@
+mod hmac;
+mod cmac;
+mod gmac;
+mod poly1305;
+
+pub use crate::mac::hmac::HMAC;
+pub use crate::mac::cmac::CMAC;
+pub use crate::mac::gmac::GMAC;
+pub use crate::mac::poly1305::{Poly1305, POLY1305_KEY_SIZE, POLY1305_TAG_SIZE};
+
+pub(crate) use crate::mac::cmac::dbl;
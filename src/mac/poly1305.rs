@@ -0,0 +1,184 @@
+use crate::common::api::{StreamingAPI, DefaultInit, SingleInputUpdate, SingleOutputFinish};
+use crate::common::{Success, Error};
+
+pub const POLY1305_KEY_SIZE: usize = 32;
+pub const POLY1305_TAG_SIZE: usize = 16;
+const POLY1305_BLOCKSIZE: usize = 16;
+
+/// Poly1305 one-time authenticator (RFC 8439, section 2.5).
+///
+/// The 32-byte key is r || s and must only authenticate a single message; `init`
+/// restarts the message under the same key. Arithmetic modulo 2^130 - 5 is done on
+/// five 26-bit limbs without secret dependent branches or table lookups. The output
+/// of `finish` is truncated to the length of the output buffer.
+#[derive(Debug, Clone)]
+pub struct Poly1305 {
+    r: [u32; 5],
+    s: [u32; 4],
+    h: [u32; 5],
+    buffer: [u8; POLY1305_BLOCKSIZE],
+    rem_pos: usize,
+}
+
+impl Poly1305 {
+    pub fn new(key: &[u8]) -> Result<Self, Error> {
+        if key.len() != POLY1305_KEY_SIZE {
+            return Err(Error::Err);
+        }
+
+        /* r is clamped: the top four bits of every fourth byte and the bottom two
+         * bits of bytes 4, 8 and 12 are cleared. */
+        let w = |i: usize| u32::from_le_bytes(key[i..i + 4].try_into().unwrap());
+        let r = [
+            w(0) & 0x3ffffff,
+            (w(3) >> 2) & 0x3ffff03,
+            (w(6) >> 4) & 0x3ffc0ff,
+            (w(9) >> 6) & 0x3f03fff,
+            (w(12) >> 8) & 0x00fffff,
+        ];
+        let s = [w(16), w(20), w(24), w(28)];
+
+        Ok(Poly1305 { r, s, h: [0; 5], buffer: [0; POLY1305_BLOCKSIZE], rem_pos: 0 })
+    }
+
+    /* h = (h + m) * r mod 2^130 - 5 for one 16-byte block, `hibit` is the 2^128 bit
+     * appended to full blocks. */
+    fn _block(&mut self, m: &[u8; POLY1305_BLOCKSIZE], hibit: u32) {
+        let [r0, r1, r2, r3, r4] = self.r;
+        let (s1, s2, s3, s4) = (r1 * 5, r2 * 5, r3 * 5, r4 * 5);
+        let w = |i: usize| u32::from_le_bytes(m[i..i + 4].try_into().unwrap());
+
+        let h0 = (self.h[0] + (w(0) & 0x3ffffff)) as u64;
+        let h1 = (self.h[1] + ((w(3) >> 2) & 0x3ffffff)) as u64;
+        let h2 = (self.h[2] + ((w(6) >> 4) & 0x3ffffff)) as u64;
+        let h3 = (self.h[3] + ((w(9) >> 6) & 0x3ffffff)) as u64;
+        let h4 = (self.h[4] + ((w(12) >> 8) | hibit)) as u64;
+        let (r0, r1, r2, r3, r4) = (r0 as u64, r1 as u64, r2 as u64, r3 as u64, r4 as u64);
+        let (s1, s2, s3, s4) = (s1 as u64, s2 as u64, s3 as u64, s4 as u64);
+
+        /* Limbs above 2^130 wrap around multiplied by 5. */
+        let d0 = h0 * r0 + h1 * s4 + h2 * s3 + h3 * s2 + h4 * s1;
+        let mut d1 = h0 * r1 + h1 * r0 + h2 * s4 + h3 * s3 + h4 * s2;
+        let mut d2 = h0 * r2 + h1 * r1 + h2 * r0 + h3 * s4 + h4 * s3;
+        let mut d3 = h0 * r3 + h1 * r2 + h2 * r1 + h3 * r0 + h4 * s4;
+        let mut d4 = h0 * r4 + h1 * r3 + h2 * r2 + h3 * r1 + h4 * r0;
+
+        /* Partial carry propagation, h stays below 2^131. */
+        let mut c = d0 >> 26;
+        let mut h0 = d0 as u32 & 0x3ffffff;
+        d1 += c;
+        c = d1 >> 26;
+        let h1 = d1 as u32 & 0x3ffffff;
+        d2 += c;
+        c = d2 >> 26;
+        let h2 = d2 as u32 & 0x3ffffff;
+        d3 += c;
+        c = d3 >> 26;
+        let h3 = d3 as u32 & 0x3ffffff;
+        d4 += c;
+        c = d4 >> 26;
+        let h4 = d4 as u32 & 0x3ffffff;
+        h0 += c as u32 * 5;
+        let c = h0 >> 26;
+        h0 &= 0x3ffffff;
+
+        self.h = [h0, h1 + c, h2, h3, h4];
+    }
+}
+
+impl StreamingAPI for Poly1305 {}
+
+impl DefaultInit for Poly1305 {
+    fn init(&mut self) -> Result<Success, Error> {
+        self.h = [0; 5];
+        self.buffer.fill(0);
+        self.rem_pos = 0;
+
+        Ok(Success::OK)
+    }
+}
+
+impl SingleInputUpdate for Poly1305 {
+    fn update(&mut self, mut input: &[u8]) -> Result<Success, Error> {
+        while !input.is_empty() {
+            let take = usize::min(POLY1305_BLOCKSIZE - self.rem_pos, input.len());
+            self.buffer[self.rem_pos..self.rem_pos + take].copy_from_slice(&input[..take]);
+            self.rem_pos += take;
+            input = &input[take..];
+
+            if self.rem_pos == POLY1305_BLOCKSIZE {
+                let block = self.buffer;
+                self._block(&block, 1 << 24);
+                self.rem_pos = 0;
+            }
+        }
+
+        Ok(Success::OK)
+    }
+}
+
+impl SingleOutputFinish for Poly1305 {
+    fn finish(&mut self, output: &mut [u8]) -> Result<Success, Error> {
+        if output.len() > POLY1305_TAG_SIZE {
+            return Err(Error::Err);
+        }
+
+        /* A partial last block is padded with a single 1 byte and zeros instead of
+         * the 2^128 bit. */
+        if self.rem_pos != 0 {
+            let mut block = [0u8; POLY1305_BLOCKSIZE];
+            block[..self.rem_pos].copy_from_slice(&self.buffer[..self.rem_pos]);
+            block[self.rem_pos] = 1;
+            self._block(&block, 0);
+            block.fill(0);
+        }
+
+        /* Full carry, then h - p is selected by mask if it does not borrow. */
+        let mut h = self.h;
+        let mut c = h[1] >> 26;
+        h[1] &= 0x3ffffff;
+        for limb in h[2..].iter_mut() {
+            *limb += c;
+            c = *limb >> 26;
+            *limb &= 0x3ffffff;
+        }
+        h[0] += c * 5;
+        c = h[0] >> 26;
+        h[0] &= 0x3ffffff;
+        h[1] += c;
+
+        let mut g = [0u32; 5];
+        let mut c = 5;
+        for i in 0..4 {
+            g[i] = h[i] + c;
+            c = g[i] >> 26;
+            g[i] &= 0x3ffffff;
+        }
+        g[4] = h[4].wrapping_add(c).wrapping_sub(1 << 26);
+
+        let mask = (g[4] >> 31).wrapping_sub(1);
+        for i in 0..5 {
+            h[i] = (h[i] & !mask) | (g[i] & mask);
+        }
+
+        /* h mod 2^128 + s. */
+        let words = [
+            h[0] | (h[1] << 26),
+            (h[1] >> 6) | (h[2] << 20),
+            (h[2] >> 12) | (h[3] << 14),
+            (h[3] >> 18) | (h[4] << 8),
+        ];
+        let mut tag = [0u8; POLY1305_TAG_SIZE];
+        let mut f = 0u64;
+        for (i, (w, s)) in words.iter().zip(self.s.iter()).enumerate() {
+            f = (f >> 32) + *w as u64 + *s as u64;
+            tag[4 * i..4 * i + 4].copy_from_slice(&(f as u32).to_le_bytes());
+        }
+
+        output.copy_from_slice(&tag[..output.len()]);
+        tag.fill(0);
+        h.fill(0);
+        g.fill(0);
+        self.init()
+    }
+}
@@ -13,3 +13,18 @@ impl Cast<u8> for u64 {
         return self as u8;
     }
 }
+
+/// Compares two byte strings in time that only depends on their length.
+pub fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut diff = 0u8;
+    for (x, y) in a.iter().zip(b.iter()) {
+        diff |= x ^ y;
+    }
+
+    /* Keep the compiler from turning the loop into an early exit. */
+    core::hint::black_box(diff) == 0
+}
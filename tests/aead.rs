@@ -0,0 +1,123 @@
+use rucola::cipher::{AES128, AES256, CCM, GCM, GCMSIV, SIV, ChaCha20Poly1305, XChaCha20Poly1305};
+use rucola::common::api::{AEAD, AEADDecryptor, AEADEncryptor, IVInit, InputOutputUpdate, OutputFinish, StreamingAEAD};
+use rucola::common::Error;
+
+/* The combined, detached and in-place forms agree and only differ in layout. */
+fn forms<A: AEAD>() {
+    let key: Vec<u8> = (0..A::KEY_SIZE).map(|i| i as u8).collect();
+    let nonce = vec![7u8; A::NONCE_SIZE];
+    let aead = A::new(&key).unwrap();
+    assert!(A::new(&key[1..]).is_err());
+    assert_eq!(aead.nonce_len(), A::NONCE_SIZE);
+    assert_eq!(aead.tag_len(), A::TAG_SIZE);
+
+    for len in [0, 1, 16, 63, 200] {
+        let pt: Vec<u8> = (0..len).map(|i| (i * 3) as u8).collect();
+        let mut sealed = vec![0u8; len + A::TAG_SIZE];
+        assert_eq!(aead.seal(&nonce, b"aad", &pt, &mut sealed), Ok(sealed.len()));
+
+        let mut ct = vec![0u8; len];
+        let mut tag = vec![0u8; A::TAG_SIZE];
+        aead.seal_detached(&nonce, b"aad", &pt, &mut ct, &mut tag).unwrap();
+        assert!(sealed == [ct.clone(), tag.clone()].concat() || sealed == [tag.clone(), ct.clone()].concat());
+
+        let mut buffer = pt.clone();
+        let mut tag2 = vec![0u8; A::TAG_SIZE];
+        aead.seal_in_place(&nonce, b"aad", &mut buffer, &mut tag2).unwrap();
+        assert_eq!((&buffer, &tag2), (&ct, &tag));
+
+        let mut out = vec![0u8; len];
+        assert_eq!(aead.open(&nonce, b"aad", &sealed, &mut out), Ok(len));
+        assert_eq!(out, pt);
+        out.fill(0);
+        aead.open_detached(&nonce, b"aad", &ct, &tag, &mut out).unwrap();
+        assert_eq!(out, pt);
+        aead.open_in_place(&nonce, b"aad", &mut buffer, &tag).unwrap();
+        assert_eq!(buffer, pt);
+
+        /* On failure no plaintext is released and the buffer keeps the ciphertext. */
+        tag[0] ^= 1;
+        let mut out = vec![0xa5u8; len];
+        assert_eq!(aead.open_detached(&nonce, b"aad", &ct, &tag, &mut out), Err(Error::AuthFailed));
+        assert!(out.iter().all(|&b| b == 0xa5) || out.iter().all(|&b| b == 0));
+        let mut buffer = ct.clone();
+        assert_eq!(aead.open_in_place(&nonce, b"aad", &mut buffer, &tag), Err(Error::AuthFailed));
+        assert_eq!(buffer, ct);
+        tag[0] ^= 1;
+        assert_eq!(aead.open_in_place(&nonce, b"bad", &mut buffer, &tag), Err(Error::AuthFailed));
+        assert_eq!(buffer, ct);
+
+        assert_eq!(aead.seal_in_place(&nonce, b"aad", &mut buffer, &mut tag[1..]), Err(Error::Err));
+        assert_eq!(aead.open_in_place(&nonce, b"aad", &mut buffer, &tag[1..]), Err(Error::Err));
+        assert_eq!(aead.seal(&nonce, b"aad", &pt, &mut sealed[1..]), Err(Error::Err));
+    }
+}
+
+#[test]
+fn aead_forms() {
+    forms::<GCM<AES128>>();
+    forms::<GCM<AES256>>();
+    forms::<CCM<AES128>>();
+    forms::<CCM<AES256>>();
+    forms::<SIV<AES128>>();
+    forms::<SIV<AES256>>();
+    forms::<GCMSIV<AES128>>();
+    forms::<GCMSIV<AES256>>();
+    forms::<ChaCha20Poly1305>();
+    forms::<XChaCha20Poly1305>();
+}
+
+/* Streaming in pieces of any size gives the output of `seal`. */
+fn streaming<A: StreamingAEAD>() {
+    let aead = A::new(&vec![5u8; A::KEY_SIZE]).unwrap();
+    let nonce = vec![9u8; A::NONCE_SIZE];
+    let aad: Vec<u8> = (0..37).collect();
+    let pt: Vec<u8> = (0..300).map(|i| i as u8).collect();
+    let mut sealed = vec![0u8; pt.len() + A::TAG_SIZE];
+    aead.seal(&nonce, &aad, &pt, &mut sealed).unwrap();
+    let (ct, tag) = sealed.split_at(pt.len());
+
+    for step in [1, 15, 64, 300] {
+        let mut enc = aead.encryptor();
+        let mut out = vec![0u8; pt.len()];
+        enc.init(&nonce).unwrap();
+        for chunk in aad.chunks(step) {
+            enc.update_aad(chunk).unwrap();
+        }
+        for (i, o) in pt.chunks(step).zip(out.chunks_mut(step)) {
+            assert_eq!(enc.update(i, o), Ok(i.len()));
+        }
+        let mut t = vec![0u8; A::TAG_SIZE];
+        assert_eq!(enc.finish(&mut t), Ok(A::TAG_SIZE));
+        assert_eq!((&out[..], &t[..]), (ct, tag));
+
+        let mut dec = aead.decryptor();
+        dec.init(&nonce).unwrap();
+        dec.update_aad(&aad).unwrap();
+        for (i, o) in ct.chunks(step).zip(out.chunks_mut(step)) {
+            dec.update(i, o).unwrap();
+        }
+        dec.verify(tag).unwrap();
+        assert_eq!(out, pt);
+    }
+
+    let mut dec = aead.decryptor();
+    let mut out = vec![0u8; pt.len()];
+    dec.init(&nonce).unwrap();
+    dec.update(ct, &mut out).unwrap();
+    assert_eq!(dec.verify(tag), Err(Error::AuthFailed));
+
+    let mut enc = aead.encryptor();
+    assert_eq!(enc.update_aad(&aad), Err(Error::Err));
+    enc.init(&nonce).unwrap();
+    enc.update(&pt, &mut out).unwrap();
+    assert_eq!(enc.update_aad(&aad), Err(Error::Err));
+}
+
+#[test]
+fn aead_streaming() {
+    streaming::<GCM<AES128>>();
+    streaming::<GCM<AES256>>();
+    streaming::<ChaCha20Poly1305>();
+    streaming::<XChaCha20Poly1305>();
+}
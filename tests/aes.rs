@@ -0,0 +1,97 @@
+use rucola::cipher::{AES128, AES192, AES256, AES_BLOCKSIZE};
+use rucola::common::api::BlockCipher;
+use utilities::testutils::RspVector;
+
+fn block(t: &RspVector, name: &str) -> [u8; AES_BLOCKSIZE] {
+    t.hex(name).try_into().unwrap()
+}
+
+/* Single block encryption or decryption as selected by the [ENCRYPT]/[DECRYPT] header. */
+fn kat<C: BlockCipher<AES_BLOCKSIZE>>(t: &RspVector) {
+    let aes = C::new(&t.hex("KEY")).unwrap();
+    let (pt, ct) = (block(t, "PLAINTEXT"), block(t, "CIPHERTEXT"));
+
+    let mut b = pt;
+    aes.encrypt_block(&mut b);
+    assert_eq!(b, ct);
+    aes.decrypt_block(&mut b);
+    assert_eq!(b, pt);
+}
+
+/* AESAVS Monte Carlo test for ECB: the output of 1000 chained operations. Every
+ * COUNT starts from the KEY and input given in the file, so `aes_mct` runs only the
+ * first `counts` of each direction to keep unoptimized test builds fast. */
+fn mct<C: BlockCipher<AES_BLOCKSIZE>>(t: &RspVector, counts: usize) {
+    if t.get("COUNT").parse::<usize>().unwrap() >= counts {
+        return;
+    }
+
+    let aes = C::new(&t.hex("KEY")).unwrap();
+
+    if t.params.contains_key("ENCRYPT") {
+        let mut b = block(t, "PLAINTEXT");
+        for _ in 0..1000 {
+            aes.encrypt_block(&mut b);
+        }
+        assert_eq!(b, block(t, "CIPHERTEXT"));
+    } else {
+        let mut b = block(t, "CIPHERTEXT");
+        for _ in 0..1000 {
+            aes.decrypt_block(&mut b);
+        }
+        assert_eq!(b, block(t, "PLAINTEXT"));
+    }
+}
+
+fn run(files: &[&str], test: fn(&RspVector, usize)) {
+    let tv = utilities::testutils::parse_rsp_vectors(files);
+    assert!(!tv.is_empty());
+
+    for t in tv {
+        test(&t, t.hex("KEY").len());
+    }
+}
+
+#[test]
+fn aes_fips197() {
+    run(&["./tests/tv/AESFIPS197.rsp"], |t, keylen| match keylen {
+        16 => kat::<AES128>(t),
+        24 => kat::<AES192>(t),
+        _ => kat::<AES256>(t),
+    });
+}
+
+#[test]
+fn aes_kat() {
+    run(&["./tests/tv/ECBGFSbox128.rsp", "./tests/tv/ECBKeySbox128.rsp"], |t, _| kat::<AES128>(t));
+    run(&["./tests/tv/ECBGFSbox192.rsp", "./tests/tv/ECBKeySbox192.rsp"], |t, _| kat::<AES192>(t));
+    run(&["./tests/tv/ECBGFSbox256.rsp", "./tests/tv/ECBKeySbox256.rsp"], |t, _| kat::<AES256>(t));
+    run(&["./tests/tv/ECBVarKey128.rsp", "./tests/tv/ECBVarTxt128.rsp"], |t, _| kat::<AES128>(t));
+    run(&["./tests/tv/ECBVarKey192.rsp", "./tests/tv/ECBVarTxt192.rsp"], |t, _| kat::<AES192>(t));
+    run(&["./tests/tv/ECBVarKey256.rsp", "./tests/tv/ECBVarTxt256.rsp"], |t, _| kat::<AES256>(t));
+}
+
+#[test]
+fn aes_mct() {
+    run(&["./tests/tv/ECBMCT128.rsp", "./tests/tv/generated/ECBMCT128.rsp"], |t, _| mct::<AES128>(t, 10));
+    run(&["./tests/tv/generated/ECBMCT192.rsp"], |t, _| mct::<AES192>(t, 10));
+    run(&["./tests/tv/generated/ECBMCT256.rsp"], |t, _| mct::<AES256>(t, 10));
+}
+
+/* All 100 COUNTs, run with `cargo test --release --test aes -- --ignored`. */
+#[test]
+#[ignore]
+fn aes_mct_all_counts() {
+    run(&["./tests/tv/ECBMCT128.rsp", "./tests/tv/generated/ECBMCT128.rsp"], |t, _| mct::<AES128>(t, 100));
+    run(&["./tests/tv/generated/ECBMCT192.rsp"], |t, _| mct::<AES192>(t, 100));
+    run(&["./tests/tv/generated/ECBMCT256.rsp"], |t, _| mct::<AES256>(t, 100));
+}
+
+#[test]
+fn aes_key_sizes() {
+    assert!(AES128::new(&[0; 16]).is_ok());
+    assert!(AES128::new(&[0; 24]).is_err());
+    assert!(AES192::new(&[0; 16]).is_err());
+    assert!(AES256::new(&[0; 31]).is_err());
+    assert_eq!((AES128::KEY_SIZE, AES192::KEY_SIZE, AES256::KEY_SIZE), (16, 24, 32));
+}
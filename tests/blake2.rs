@@ -0,0 +1,125 @@
+use rucola::hash::{BLAKE2, BLAKE2Params};
+use rucola::common::Error;
+use rucola::common::api::{MAC, DefaultInit, SingleInputUpdate, SingleOutputFinish};
+use utilities::testutils::RspVector;
+
+#[test]
+fn blake2b_kat() {
+    let tv = utilities::testutils::parse_hash_vectors(&["./tests/tv/BLAKE2bKAT.rsp"]);
+    assert_eq!(tv.len(), 256);
+    utilities::testutils::streaming_api_test::<64, BLAKE2>(tv, &mut BLAKE2::new_blake2b(64).unwrap());
+}
+
+#[test]
+fn blake2s_kat() {
+    let tv = utilities::testutils::parse_hash_vectors(&["./tests/tv/BLAKE2sKAT.rsp"]);
+    assert_eq!(tv.len(), 256);
+    utilities::testutils::streaming_api_test::<32, BLAKE2>(tv, &mut BLAKE2::new_blake2s(32).unwrap());
+}
+
+fn run<M: MAC>(mac: &mut M, t: &RspVector) {
+    let (msg, md) = (t.hex("Msg"), t.hex("MD"));
+    let mut out = vec![0u8; md.len()];
+    assert_eq!(mac.mac_size(), md.len());
+
+    mac.update(&msg).unwrap();
+    mac.finish(&mut out).unwrap();
+    assert_eq!(out, md);
+
+    /* The context starts the next message with the same key and parameters. */
+    mac.init().unwrap();
+    for chunk in msg.chunks(7) {
+        mac.update(chunk).unwrap();
+    }
+    mac.finish(&mut out).unwrap();
+    assert_eq!(out, md);
+}
+
+#[test]
+fn blake2_keyed_kat() {
+    for (file, blake2b) in [("./tests/tv/BLAKE2bKeyed.rsp", true), ("./tests/tv/BLAKE2sKeyed.rsp", false)] {
+        let tv = utilities::testutils::parse_rsp_vectors(&[file]);
+        assert_eq!(tv.len(), 257);
+
+        for t in tv.iter() {
+            let key = t.hex("Key");
+            let params = BLAKE2Params { key: &key, ..BLAKE2Params::new(t.hex("MD").len()) };
+            let mut mac = match blake2b {
+                true => BLAKE2::blake2b_with_params(&params).unwrap(),
+                false => BLAKE2::blake2s_with_params(&params).unwrap(),
+            };
+            run(&mut mac, t);
+        }
+    }
+}
+
+#[test]
+fn blake2_params() {
+    let tv = utilities::testutils::parse_rsp_vectors(&["./tests/tv/BLAKE2Params.rsp"]);
+    assert!(!tv.is_empty());
+
+    for t in tv.iter() {
+        let field = |name: &str| if t.has(name) { t.hex(name) } else { vec![] };
+        let number = |name: &str, default: u64| if t.has(name) { t.get(name).parse::<u64>().unwrap() } else { default };
+        let (key, salt, personal) = (field("Key"), field("Salt"), field("Personal"));
+        let params = BLAKE2Params {
+            digest_len: number("DigestLen", 0) as usize,
+            key: &key,
+            salt: &salt,
+            personal: &personal,
+            fanout: number("Fanout", 1) as u8,
+            depth: number("Depth", 1) as u8,
+            leaf_length: number("LeafLength", 0) as u32,
+            node_offset: number("NodeOffset", 0),
+            node_depth: number("NodeDepth", 0) as u8,
+            inner_length: number("InnerLength", 0) as u8,
+            last_node: number("LastNode", 0) == 1,
+        };
+
+        let mut hash = match t.params.contains_key("BLAKE2b") {
+            true => BLAKE2::blake2b_with_params(&params).unwrap(),
+            false => BLAKE2::blake2s_with_params(&params).unwrap(),
+        };
+        run(&mut hash, t);
+    }
+}
+
+#[test]
+fn blake2_digest_length() {
+    /* A shorter digest is a different hash, a shorter output buffer truncates and a
+     * longer one is an error. */
+    let mut long = [0u8; 64];
+    let mut short = [0u8; 32];
+    let mut b = BLAKE2::new_blake2b(64).unwrap();
+    b.update(b"abc").unwrap();
+    b.finish(&mut long).unwrap();
+    b.init().unwrap();
+    b.update(b"abc").unwrap();
+    b.finish(&mut short).unwrap();
+    assert_eq!(long[..32], short);
+
+    let mut b = BLAKE2::new_blake2b(32).unwrap();
+    long.fill(0);
+    b.update(b"abc").unwrap();
+    assert_eq!(b.finish(&mut long), Err(Error::Err));
+    b.finish(&mut long[..32]).unwrap();
+    assert_ne!(long[..32], short);
+    assert_eq!((b.digest_size(), b.block_size()), (32, 128));
+    assert_eq!(BLAKE2::new_blake2s(20).unwrap().block_size(), 64);
+}
+
+#[test]
+fn blake2_errors() {
+    assert!(BLAKE2::new_blake2b(0).is_err());
+    assert!(BLAKE2::new_blake2b(65).is_err());
+    assert!(BLAKE2::new_blake2s(33).is_err());
+
+    let key = [0u8; 65];
+    assert!(BLAKE2::blake2b_with_params(&BLAKE2Params { key: &key, ..BLAKE2Params::new(64) }).is_err());
+    assert!(BLAKE2::blake2s_with_params(&BLAKE2Params { key: &key[..33], ..BLAKE2Params::new(32) }).is_err());
+    assert!(BLAKE2::blake2b_with_params(&BLAKE2Params { salt: &key[..17], ..BLAKE2Params::new(64) }).is_err());
+    assert!(BLAKE2::blake2s_with_params(&BLAKE2Params { personal: &key[..9], ..BLAKE2Params::new(32) }).is_err());
+    assert!(BLAKE2::blake2s_with_params(&BLAKE2Params { node_offset: 1 << 48, ..BLAKE2Params::new(32) }).is_err());
+    assert!(BLAKE2::blake2s_with_params(&BLAKE2Params { inner_length: 33, ..BLAKE2Params::new(32) }).is_err());
+    assert!(BLAKE2::blake2b_with_params(&BLAKE2Params { node_offset: u64::MAX, ..BLAKE2Params::new(64) }).is_ok());
+}
@@ -0,0 +1,112 @@
+use rucola::hash::{BLAKE3, BLAKE3_DIGEST_SIZE};
+use rucola::common::api::{MAC, DefaultInit, SingleInputUpdate, SingleOutputFinish};
+use rucola::common::Error;
+
+fn input(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i % 251) as u8).collect()
+}
+
+/* Full extended output, the default digest and the same message fed in pieces that
+ * straddle block and chunk boundaries. */
+fn check(ctx: &mut BLAKE3, msg: &[u8], expected: &[u8]) {
+    let mut out = vec![0u8; expected.len()];
+    ctx.update(msg).unwrap();
+    ctx.finish_xof().read(&mut out);
+    assert_eq!(out, expected);
+
+    let mut digest = [0u8; BLAKE3_DIGEST_SIZE];
+    ctx.finish(&mut digest).unwrap();
+    assert_eq!(digest, expected[..BLAKE3_DIGEST_SIZE]);
+
+    ctx.init().unwrap();
+    for chunk in msg.chunks(63).flat_map(|c| c.chunks(40)) {
+        ctx.update(chunk).unwrap();
+    }
+    ctx.finish_xof().read(&mut out);
+    assert_eq!(out, expected);
+}
+
+#[test]
+fn blake3_vectors() {
+    let tv = utilities::testutils::parse_rsp_vectors(&["./tests/tv/blake3.rsp"]);
+    assert_eq!(tv.len(), 35);
+
+    for t in tv.iter() {
+        let key = t.param("Key").unwrap().as_bytes();
+        let context = t.param("Context").unwrap();
+        let msg = input(t.get("InputLen").parse().unwrap());
+        let (hash, keyed, derived) = (t.hex("Hash"), t.hex("KeyedHash"), t.hex("DeriveKey"));
+
+        check(&mut BLAKE3::new(), &msg, &hash);
+        check(&mut BLAKE3::new_keyed(key).unwrap(), &msg, &keyed);
+        check(&mut BLAKE3::new_derive_key(context), &msg, &derived);
+
+        let mut out = vec![0u8; hash.len()];
+        BLAKE3::hash(&msg, &mut out);
+        assert_eq!(out, hash);
+        BLAKE3::keyed_hash(key, &msg, &mut out).unwrap();
+        assert_eq!(out, keyed);
+        BLAKE3::derive_key(context, &msg, &mut out);
+        assert_eq!(out, derived);
+    }
+}
+
+#[test]
+fn blake3_xof_seek() {
+    let mut ctx = BLAKE3::new();
+    ctx.update(&input(2049)).unwrap();
+    let mut full = vec![0u8; 1000];
+    ctx.finish(&mut full).unwrap();
+
+    let mut xof = ctx.finish_xof();
+    for (pos, len) in [(0, 1000), (1, 64), (63, 2), (64, 64), (100, 300), (999, 1), (500, 0)] {
+        let mut out = vec![0u8; len];
+        xof.seek(pos as u64);
+        assert_eq!(xof.position(), pos as u64);
+        xof.read(&mut out);
+        assert_eq!(out, full[pos..pos + len]);
+        assert_eq!(xof.position(), (pos + len) as u64);
+    }
+
+    /* Reading in small pieces continues the output. */
+    xof.seek(10);
+    let mut out = vec![0u8; 300];
+    for piece in out.chunks_mut(17) {
+        xof.read(piece);
+    }
+    assert_eq!(out, full[10..310]);
+
+    /* The output far into the stream is computed without the blocks before it. */
+    let mut far = [0u8; 8];
+    xof.seek(u64::MAX - 7);
+    xof.read(&mut far);
+    assert_ne!(far, [0u8; 8]);
+}
+
+#[test]
+fn blake3_keyed() {
+    assert!(BLAKE3::new_keyed(&[0u8; 31]).is_err());
+    assert!(BLAKE3::new_keyed(&[0u8; 33]).is_err());
+    assert!(BLAKE3::keyed_hash(&[0u8; 16], b"", &mut [0u8; 32]).is_err());
+
+    let mut mac = BLAKE3::new_keyed(&[7u8; 32]).unwrap();
+    assert_eq!(mac.mac_size(), BLAKE3_DIGEST_SIZE);
+
+    /* As a MAC the output is at most mac_size bytes, extended output needs the XOF. */
+    let mut long = [0u8; BLAKE3_DIGEST_SIZE + 1];
+    assert_eq!(mac.finish(&mut long), Err(Error::Err));
+    mac.finish(&mut long[..BLAKE3_DIGEST_SIZE]).unwrap();
+    let mut xof = [0u8; BLAKE3_DIGEST_SIZE + 1];
+    mac.finish_xof().read(&mut xof);
+    assert_eq!(xof[..BLAKE3_DIGEST_SIZE], long[..BLAKE3_DIGEST_SIZE]);
+    BLAKE3::new().finish(&mut long).unwrap();
+
+    /* Keyed hashing and key derivation are separated from plain hashing. */
+    let (mut plain, mut keyed, mut derived) = ([0u8; 32], [0u8; 32], [0u8; 32]);
+    BLAKE3::hash(b"abc", &mut plain);
+    BLAKE3::keyed_hash(&[0u8; 32], b"abc", &mut keyed).unwrap();
+    BLAKE3::derive_key("", b"abc", &mut derived);
+    assert_ne!(plain, keyed);
+    assert_ne!(plain, derived);
+    assert_ne!(keyed, derived);
+}
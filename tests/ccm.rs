@@ -0,0 +1,152 @@
+use rucola::cipher::{AES128, AES192, AES256, CCM};
+use rucola::common::api::{AEAD, BlockCipher};
+use rucola::common::Error;
+use utilities::testutils::RspVector;
+
+fn param(t: &RspVector, name: &str) -> usize {
+    t.param(name).unwrap().parse().unwrap()
+}
+
+fn ccm_vector<C: BlockCipher<16>>(t: &RspVector) {
+    let ccm = CCM::<C>::with_lengths(&t.hex("Key"), param(t, "Nlen"), param(t, "Tlen")).unwrap();
+    let (nonce, aad, pt, ct) = (t.hex("Nonce"), t.hex("Adata"), t.hex("Payload"), t.hex("CT"));
+    let tag_len = ccm.tag_len();
+
+    if t.get("Result") == "Fail" {
+        let mut out = vec![0xaau8; ct.len()];
+        assert_eq!(ccm.open(&nonce, &aad, &ct, &mut out), Err(Error::AuthFailed));
+        assert!(out[..ct.len() - tag_len].iter().all(|b| *b == 0));
+
+        let (msg, tag) = ct.split_at(ct.len() - tag_len);
+        let mut buffer = msg.to_vec();
+        assert_eq!(ccm.open_in_place(&nonce, &aad, &mut buffer, tag), Err(Error::AuthFailed));
+        assert_eq!(buffer, msg);
+        return;
+    }
+
+    let mut out = vec![0u8; ct.len()];
+    assert_eq!(ccm.seal(&nonce, &aad, &pt, &mut out), Ok(ct.len()));
+    assert_eq!(out, ct);
+
+    let mut pt_out = vec![0u8; pt.len()];
+    assert_eq!(ccm.open(&nonce, &aad, &ct, &mut pt_out), Ok(pt.len()));
+    assert_eq!(pt_out, pt);
+
+    let mut buffer = ct[..pt.len()].to_vec();
+    ccm.open_in_place(&nonce, &aad, &mut buffer, &ct[pt.len()..]).unwrap();
+    assert_eq!(buffer, pt);
+}
+
+fn run(file: &str) {
+    let tv = utilities::testutils::parse_rsp_vectors(&[file]);
+    assert!(!tv.is_empty());
+
+    for t in tv.iter() {
+        match param(t, "Keylen") {
+            128 => ccm_vector::<AES128>(t),
+            192 => ccm_vector::<AES192>(t),
+            _ => ccm_vector::<AES256>(t),
+        }
+    }
+}
+
+#[test]
+fn ccm_cavp() {
+    run("./tests/tv/ccmCAVP.rsp");
+}
+
+#[test]
+fn ccm_rfc3610() {
+    run("./tests/tv/ccmRFC3610.rsp");
+}
+
+/* Examples of appendix C of SP 800-38C. */
+#[test]
+fn ccm_sp800_38c() {
+    let key: Vec<u8> = (0x40..0x50).collect();
+    let nonce: Vec<u8> = (0x10..0x1d).collect();
+    let aad: Vec<u8> = (0..0x14).collect();
+    let pt: Vec<u8> = (0x20..0x40).collect();
+
+    let examples: [(usize, usize, usize, usize, &str); 3] = [
+        (7, 4, 8, 4, "7162015b4dac255d"),
+        (8, 6, 16, 16, "d2a1f0e051ea5f62081a7792073d593d1fc64fbfaccd"),
+        (12, 8, 20, 24, "e3b201a9f5b71a7a9b1ceaeccd97e70b6176aad9a4428aa5484392fbc1b09951"),
+    ];
+    for (n, t, a, p, ct) in examples {
+        let ccm = CCM::<AES128>::with_lengths(&key, n, t).unwrap();
+        let mut out = [0u8; 32];
+        assert_eq!(ccm.seal(&nonce[..n], &aad[..a], &pt[..p], &mut out), Ok(p + t));
+        assert_eq!(hex::encode(&out[..p + t]), ct);
+    }
+
+    /* Example 4 has 2^16 bytes of associated data, encoded with the 0xfffe prefix. */
+    let aad: Vec<u8> = (0..0x10000).map(|i| i as u8).collect();
+    let ccm = CCM::<AES128>::with_lengths(&key, 13, 14).unwrap();
+    let mut out = [0u8; 46];
+    ccm.seal(&nonce, &aad, &pt, &mut out).unwrap();
+    assert_eq!(
+        hex::encode(out),
+        concat!("69915dad1e84c6376a68c2967e4dab615ae0fd1faec44cc484828529463ccf72", "b4ac6bec93e8598e7f0dadbcea5b")
+    );
+}
+
+#[test]
+fn ccm_length_encoding() {
+    let key: Vec<u8> = (0x40..0x50).collect();
+    let nonce: Vec<u8> = (0x10..0x1d).collect();
+    let ccm = CCM::<AES128>::with_lengths(&key, 13, 16).unwrap();
+
+    /* Associated data just below and at the 0xff00 switch to the 6-byte encoding. */
+    for (len, ct) in [
+        (0xfeff, "69915dada0c77188341e163814bfdab2b01198dc"),
+        (0xff00, "69915dad2dcc2d10ca26d09508dd5231dd63f728"),
+    ] {
+        let aad: Vec<u8> = (0..len).map(|i| i as u8).collect();
+        let mut out = [0u8; 20];
+        ccm.seal(&nonce, &aad, &[0x20, 0x21, 0x22, 0x23], &mut out).unwrap();
+        assert_eq!(hex::encode(out), ct);
+    }
+
+    /* A 13-byte nonce leaves two bytes for the message length. */
+    let mut buffer = vec![1u8; 0x10000];
+    let mut tag = [0u8; 16];
+    assert!(ccm.seal_in_place(&nonce, &[], &mut buffer[..0xffff], &mut tag).is_ok());
+    assert_eq!(ccm.seal_in_place(&nonce, &[], &mut buffer, &mut tag), Err(Error::Err));
+    assert_eq!(ccm.open_in_place(&nonce, &[], &mut buffer, &tag), Err(Error::Err));
+    let ccm = CCM::<AES128>::with_lengths(&key, 12, 16).unwrap();
+    assert!(ccm.seal_in_place(&nonce[..12], &[], &mut buffer, &mut tag).is_ok());
+}
+
+#[test]
+fn ccm_parameters() {
+    let key = [0x11u8; 16];
+    for n in 7..=13 {
+        for t in [4, 6, 8, 10, 12, 14, 16] {
+            assert!(CCM::<AES128>::with_lengths(&key, n, t).is_ok());
+        }
+    }
+    for (n, t) in [(6, 16), (14, 16), (13, 0), (13, 2), (13, 5), (13, 18)] {
+        assert!(CCM::<AES128>::with_lengths(&key, n, t).is_err());
+    }
+    assert!(CCM::<AES128>::with_lengths(&[0u8; 24], 13, 16).is_err());
+    let ccm = CCM::<AES128>::new(&key).unwrap();
+    assert_eq!((ccm.nonce_len(), ccm.tag_len()), (12, 16));
+
+    /* Nonce and tag lengths are fixed by the instance. */
+    let ccm = CCM::<AES128>::with_lengths(&key, 12, 8).unwrap();
+    assert_eq!((ccm.nonce_len(), ccm.tag_len()), (12, 8));
+    assert_eq!(CCM::<AES128>::with_lengths(&key, 13, 4).unwrap().nonce_len(), 13);
+    let msg = [0x33u8; 40];
+    let mut out = [0u8; 56];
+    assert_eq!(ccm.seal(&[0; 13], b"aad", &msg, &mut out), Err(Error::Err));
+    assert_eq!(ccm.seal(&[0; 12], b"aad", &msg, &mut out[..47]), Err(Error::Err));
+    assert_eq!(ccm.seal(&[0; 12], b"aad", &msg, &mut out), Ok(48));
+
+    let mut pt = [0u8; 40];
+    assert_eq!(ccm.open(&[0; 12], b"aad", &out[..48], &mut pt), Ok(40));
+    assert_eq!(pt, msg);
+    assert_eq!(ccm.open(&[0; 12], b"aaD", &out[..48], &mut pt), Err(Error::AuthFailed));
+    assert_eq!(ccm.open_detached(&[0; 12], b"aad", &out[..40], &out[40..47], &mut pt), Err(Error::Err));
+    assert_eq!(ccm.open(&[0; 12], b"aad", &out[..7], &mut pt), Err(Error::Err));
+}
@@ -0,0 +1,130 @@
+use rucola::cipher::{ChaCha20, ChaCha20Legacy, XChaCha20, hchacha20, CHACHA_BLOCKSIZE};
+use rucola::common::api::{CipherStreamingAPI, IVInit, InputOutputUpdate, OutputFinish};
+use rucola::common::{Success, Error};
+use utilities::testutils::RspVector;
+
+/* Seeks to the initial counter, then checks the whole message at once and once
+ * split at an odd position. */
+fn run<C: CipherStreamingAPI>(c: &mut C, seek: fn(&mut C, u64) -> Result<Success, Error>, t: &RspVector) {
+    let (nonce, pt, ct) = (t.hex("Nonce"), t.hex("PT"), t.hex("CT"));
+    let pos = t.get("Counter").parse::<u64>().unwrap() * CHACHA_BLOCKSIZE as u64;
+    let mut out = vec![0u8; pt.len()];
+
+    c.init(&nonce).unwrap();
+    seek(c, pos).unwrap();
+    assert_eq!(c.update(&pt, &mut out), Ok(pt.len()));
+    assert_eq!(c.finish(&mut []), Ok(0));
+    assert_eq!(ct, out);
+
+    let split = pt.len() / 3 + 1;
+    if split < pt.len() {
+        c.init(&nonce).unwrap();
+        seek(c, pos).unwrap();
+        c.update(&ct[..split], &mut out[..split]).unwrap();
+        c.update(&ct[split..], &mut out[split..]).unwrap();
+        c.finish(&mut []).unwrap();
+        assert_eq!(pt, out);
+    }
+}
+
+#[test]
+fn chacha20_vectors() {
+    let tv = utilities::testutils::parse_rsp_vectors(&["./tests/tv/chacha20.rsp"]);
+    assert!(tv.len() > 30);
+
+    for t in tv.iter() {
+        let key = t.hex("Key");
+        if t.params.contains_key("IETF") {
+            run(&mut ChaCha20::new(&key).unwrap(), ChaCha20::seek, t);
+        } else if t.params.contains_key("LEGACY") {
+            run(&mut ChaCha20Legacy::new(&key).unwrap(), ChaCha20Legacy::seek, t);
+        } else {
+            run(&mut XChaCha20::new(&key).unwrap(), XChaCha20::seek, t);
+        }
+    }
+}
+
+#[test]
+fn hchacha20_draft() {
+    let key: [u8; 32] = core::array::from_fn(|i| i as u8);
+    let nonce = hex::decode("000000090000004a0000000031415927").unwrap();
+    let subkey = hchacha20(&key, nonce[..].try_into().unwrap());
+    assert_eq!(hex::decode("82413b4227b27bfed30e42508a877d73a0f9e4d58a74a853c12ec41326d3ecdc").unwrap(), subkey);
+}
+
+#[test]
+fn chacha20_seek() {
+    let mut c = ChaCha20::new(&[7u8; 32]).unwrap();
+    let mut keystream = [0u8; 300];
+    c.init(&[1u8; 12]).unwrap();
+    c.update(&[0u8; 300], &mut keystream).unwrap();
+
+    /* Every start position gives the matching suffix of the keystream. */
+    for pos in [0, 1, 63, 64, 65, 128, 200, 299] {
+        let mut out = vec![0u8; 300 - pos];
+        c.init(&[1u8; 12]).unwrap();
+        c.seek(pos as u64).unwrap();
+        c.update(&vec![0u8; 300 - pos], &mut out).unwrap();
+        assert_eq!(keystream[pos..], out[..]);
+    }
+
+    /* Seeking back within the same nonce. */
+    let mut out = [0u8; 10];
+    c.seek(100).unwrap();
+    c.update(&[0u8; 10], &mut out).unwrap();
+    assert_eq!(keystream[100..110], out);
+}
+
+#[test]
+fn chacha20_counter_overflow() {
+    let mut c = ChaCha20::new(&[0u8; 32]).unwrap();
+    let mut out = [0u8; 128];
+    let end = (1u64 << 32) * CHACHA_BLOCKSIZE as u64;
+
+    c.init(&[0u8; 12]).unwrap();
+    assert_eq!(c.seek(end + 1), Err(Error::CounterOverflow));
+    c.seek(end).unwrap();
+    assert_eq!(c.update(&[0u8; 1], &mut out), Err(Error::CounterOverflow));
+
+    /* The last block is available, nothing is written if the request would wrap. */
+    c.seek(end - 10).unwrap();
+    assert_eq!(c.update(&[0u8; 11], &mut out), Err(Error::CounterOverflow));
+    assert_eq!(out, [0u8; 128]);
+    assert_eq!(c.update(&[0u8; 10], &mut out[..10]), Ok(10));
+    assert_eq!(c.update(&[], &mut []), Ok(0));
+    assert_eq!(c.update(&[0u8; 1], &mut out), Err(Error::CounterOverflow));
+
+    /* The 64-bit counter carries into the next word instead. */
+    let mut l = ChaCha20Legacy::new(&[0u8; 32]).unwrap();
+    l.init(&[0u8; 8]).unwrap();
+    l.seek(end - 64).unwrap();
+    assert_eq!(l.update(&[0u8; 128], &mut out), Ok(128));
+
+    let mut x = XChaCha20::new(&[0u8; 32]).unwrap();
+    x.init(&[0u8; 24]).unwrap();
+    x.seek(end).unwrap();
+    assert_eq!(x.update(&[0u8; 1], &mut out), Err(Error::CounterOverflow));
+}
+
+#[test]
+fn chacha20_errors() {
+    let mut out = [0u8; 16];
+    assert!(ChaCha20::new(&[0u8; 16]).is_err());
+    assert!(XChaCha20::new(&[0u8; 33]).is_err());
+
+    let mut c = ChaCha20::new(&[0u8; 32]).unwrap();
+    assert_eq!(c.update(&[0u8; 16], &mut out), Err(Error::Err));
+    assert_eq!(c.seek(0), Err(Error::Err));
+    assert_eq!(c.finish(&mut []), Err(Error::Err));
+    assert_eq!(c.init(&[0u8; 8]), Err(Error::Err));
+
+    c.init(&[0u8; 12]).unwrap();
+    assert_eq!(c.update(&[0u8; 17], &mut out), Err(Error::Err));
+    c.finish(&mut []).unwrap();
+    assert_eq!(c.update(&[0u8; 16], &mut out), Err(Error::Err));
+
+    let mut l = ChaCha20Legacy::new(&[0u8; 32]).unwrap();
+    assert_eq!(l.init(&[0u8; 12]), Err(Error::Err));
+    let mut x = XChaCha20::new(&[0u8; 32]).unwrap();
+    assert_eq!(x.init(&[0u8; 12]), Err(Error::Err));
+}
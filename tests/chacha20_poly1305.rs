@@ -0,0 +1,148 @@
+use rucola::cipher::{ChaCha20Poly1305, XChaCha20Poly1305, CHACHA20_POLY1305_TAG_SIZE};
+use rucola::common::api::{AEAD, DefaultInit, SingleInputUpdate, SingleOutputFinish};
+use rucola::common::Error;
+use rucola::mac::Poly1305;
+use utilities::testutils::RspVector;
+
+#[test]
+fn poly1305() {
+    let tv = utilities::testutils::parse_rsp_vectors(&["./tests/tv/poly1305.rsp"]);
+    assert!(!tv.is_empty());
+
+    for t in tv {
+        let mut poly = Poly1305::new(&t.hex("Key")).unwrap();
+        let (msg, tag) = (t.hex("Msg"), t.hex("Tag"));
+        let mut out = [0u8; 16];
+
+        poly.update(&msg).unwrap();
+        poly.finish(&mut out).unwrap();
+        assert_eq!(tag, out);
+
+        for step in [1, 15, 17] {
+            for chunk in msg.chunks(step) {
+                poly.update(chunk).unwrap();
+            }
+            poly.finish(&mut out).unwrap();
+            assert_eq!(tag, out);
+        }
+
+        poly.update(b"discarded").unwrap();
+        poly.init().unwrap();
+        poly.update(&msg).unwrap();
+        poly.finish(&mut out[..8]).unwrap();
+        assert_eq!(tag[..8], out[..8]);
+    }
+
+    assert!(Poly1305::new(&[0u8; 16]).is_err());
+    assert_eq!(Poly1305::new(&[0u8; 32]).unwrap().finish(&mut [0u8; 17]), Err(Error::Err));
+}
+
+fn wycheproof<A: AEAD>(aead: &A, t: &RspVector) {
+    let (nonce, aad, pt, ct) = (t.hex("Nonce"), t.hex("AAD"), t.hex("PT"), t.hex("CT"));
+    let mut out = vec![0u8; pt.len() + CHACHA20_POLY1305_TAG_SIZE];
+
+    if t.get("Result") == "pass" {
+        assert_eq!(aead.seal(&nonce, &aad, &pt, &mut out), Ok(ct.len()));
+        assert_eq!(ct, out);
+        assert_eq!(aead.open(&nonce, &aad, &ct, &mut out), Ok(pt.len()));
+        assert_eq!(pt, out[..pt.len()]);
+    } else {
+        /* A wrong tag zeroes the plaintext, malformed input leaves the output alone. */
+        let mut out = vec![0xa5u8; ct.len()];
+        match aead.open(&nonce, &aad, &ct, &mut out) {
+            Err(Error::AuthFailed) => assert!(out[..ct.len() - CHACHA20_POLY1305_TAG_SIZE].iter().all(|&b| b == 0)),
+            ret => {
+                assert!(ret.is_err());
+                assert!(out.iter().all(|&b| b == 0xa5));
+            }
+        }
+    }
+}
+
+#[test]
+fn chacha20_poly1305_wycheproof() {
+    let tv = utilities::testutils::parse_rsp_vectors(&["./tests/tv/chacha20poly1305Wycheproof.rsp"]);
+    assert!(tv.len() > 250);
+
+    for t in tv.iter() {
+        match ChaCha20Poly1305::new(&t.hex("Key")) {
+            Ok(aead) => wycheproof(&aead, t),
+            Err(_) => assert_eq!(t.get("Result"), "fail"),
+        }
+    }
+}
+
+#[test]
+fn xchacha20_poly1305_wycheproof() {
+    let tv = utilities::testutils::parse_rsp_vectors(&["./tests/tv/xchacha20poly1305Wycheproof.rsp"]);
+    assert!(tv.len() > 250);
+
+    for t in tv.iter() {
+        match XChaCha20Poly1305::new(&t.hex("Key")) {
+            Ok(aead) => wycheproof(&aead, t),
+            Err(_) => assert_eq!(t.get("Result"), "fail"),
+        }
+    }
+}
+
+/* Example of draft-irtf-cfrg-xchacha, appendix A.3.1. */
+#[test]
+fn xchacha20_poly1305_draft() {
+    let aead = XChaCha20Poly1305::new(&hex::decode("808182838485868788898a8b8c8d8e8f909192939495969798999a9b9c9d9e9f").unwrap()).unwrap();
+    let nonce = hex::decode("404142434445464748494a4b4c4d4e4f5051525354555657").unwrap();
+    let aad = hex::decode("50515253c0c1c2c3c4c5c6c7").unwrap();
+    let pt = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+    let mut ct = [0u8; 114];
+    let mut tag = [0u8; 16];
+
+    aead.seal_detached(&nonce, &aad, pt, &mut ct, &mut tag).unwrap();
+    assert_eq!(hex::encode(&ct[..16]), "bd6d179d3e83d43b9576579493c0e939");
+    assert_eq!(hex::encode(tag), "c0875924c1c7987947deafd8780acf49");
+}
+
+#[test]
+fn chacha20_poly1305_detached_in_place() {
+    let aead = ChaCha20Poly1305::new(&[9u8; 32]).unwrap();
+    let nonce = [3u8; 12];
+    let pt: Vec<u8> = (0..200).map(|i| i as u8).collect();
+    let mut combined = vec![0u8; pt.len() + 16];
+    aead.seal(&nonce, b"header", &pt, &mut combined).unwrap();
+
+    let mut ct = vec![0u8; pt.len()];
+    let mut tag = [0u8; 16];
+    aead.seal_detached(&nonce, b"header", &pt, &mut ct, &mut tag).unwrap();
+    assert_eq!(combined, [ct.clone(), tag.to_vec()].concat());
+
+    let mut buffer = pt.clone();
+    aead.seal_in_place(&nonce, b"header", &mut buffer, &mut tag).unwrap();
+    assert_eq!(buffer, ct);
+
+    let mut out = vec![0u8; pt.len()];
+    aead.open_detached(&nonce, b"header", &ct, &tag, &mut out).unwrap();
+    assert_eq!(out, pt);
+
+    /* A forged tag leaves the buffer untouched. */
+    tag[0] ^= 1;
+    assert_eq!(aead.open_in_place(&nonce, b"header", &mut buffer, &tag), Err(Error::AuthFailed));
+    assert_eq!(buffer, ct);
+    tag[0] ^= 1;
+    aead.open_in_place(&nonce, b"header", &mut buffer, &tag).unwrap();
+    assert_eq!(buffer, pt);
+}
+
+#[test]
+fn chacha20_poly1305_errors() {
+    let aead = ChaCha20Poly1305::new(&[0u8; 32]).unwrap();
+    let xaead = XChaCha20Poly1305::new(&[0u8; 32]).unwrap();
+    let mut out = [0u8; 48];
+    let mut tag = [0u8; 16];
+
+    assert!(ChaCha20Poly1305::new(&[0u8; 16]).is_err());
+    assert!(XChaCha20Poly1305::new(&[0u8; 31]).is_err());
+    assert_eq!(aead.seal(&[0u8; 24], &[], &[0u8; 32], &mut out), Err(Error::Err));
+    assert_eq!(xaead.seal(&[0u8; 12], &[], &[0u8; 32], &mut out), Err(Error::Err));
+    assert_eq!(aead.seal(&[0u8; 12], &[], &[0u8; 33], &mut out), Err(Error::Err));
+    assert_eq!(aead.open(&[0u8; 12], &[], &[0u8; 15], &mut out), Err(Error::Err));
+    assert_eq!(aead.seal_in_place(&[0u8; 12], &[], &mut out, &mut tag[..12]), Err(Error::Err));
+    assert_eq!(aead.open_in_place(&[0u8; 12], &[], &mut out, &tag[..12]), Err(Error::Err));
+}
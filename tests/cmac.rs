@@ -0,0 +1,116 @@
+use rucola::cipher::{AES128, AES192, AES256};
+use rucola::common::api::{BlockCipher, MAC, DefaultInit, SingleInputUpdate, SingleOutputFinish};
+use rucola::common::Error;
+use rucola::mac::{CMAC, GMAC};
+use utilities::testutils::RspVector;
+
+fn cmac_vector<C: BlockCipher<16>>(t: &RspVector) {
+    let mut cmac = CMAC::<C>::new(&t.hex("Key")).unwrap();
+    let len: usize = t.get("Mlen").parse().unwrap();
+    let msg = &t.hex("Msg")[..len];
+    let mac = t.hex("Mac");
+    let mut out = vec![0u8; mac.len()];
+
+    cmac.init().unwrap();
+    cmac.update(msg).unwrap();
+    cmac.finish(&mut out).unwrap();
+    assert_eq!(mac, out);
+
+    /* The same MAC fed in uneven pieces. */
+    for chunk in msg.chunks(7) {
+        cmac.update(chunk).unwrap();
+    }
+    cmac.finish(&mut out).unwrap();
+    assert_eq!(mac, out);
+}
+
+fn gmac_vector<C: BlockCipher<16>>(t: &RspVector) {
+    let mut gmac = GMAC::<C>::new(&t.hex("Key")).unwrap();
+    let tag = t.hex("Tag");
+    let mut out = vec![0u8; tag.len()];
+
+    gmac.init(&t.hex("IV")).unwrap();
+    gmac.update(&t.hex("AAD")).unwrap();
+    gmac.finish(&mut out).unwrap();
+    assert_eq!(tag, out);
+}
+
+#[test]
+fn cmac_cavp() {
+    let tv = utilities::testutils::parse_rsp_vectors(&[
+        "./tests/tv/CMACSP800-38B.rsp",
+        "./tests/tv/generated/CMACGenAES.rsp",
+    ]);
+    assert!(!tv.is_empty());
+
+    for t in tv.iter() {
+        match t.get("Klen") {
+            "16" => cmac_vector::<AES128>(t),
+            "24" => cmac_vector::<AES192>(t),
+            "32" => cmac_vector::<AES256>(t),
+            _ => panic!("unexpected Klen"),
+        }
+    }
+}
+
+#[test]
+fn cmac_rfc4493() {
+    let key = hex::decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let msg = hex::decode(concat!(
+        "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51",
+        "30c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710")).unwrap();
+    let tv = [
+        (0, "bb1d6929e95937287fa37d129b756746"),
+        (16, "070a16b46b4d4144f79bdd9dd04a287c"),
+        (40, "dfa66747de9ae63030ca32611497c827"),
+        (64, "51f0bebf7e3b9d92fc49741779363cfe"),
+    ];
+
+    let mut cmac = CMAC::<AES128>::new(&key).unwrap();
+    let mut out = [0u8; 16];
+    for (len, mac) in tv {
+        cmac.init().unwrap();
+        cmac.update(&msg[..len]).unwrap();
+        cmac.finish(&mut out).unwrap();
+        assert_eq!(hex::decode(mac).unwrap(), out);
+    }
+
+    assert_eq!(cmac.mac_size(), 16);
+    assert_eq!(cmac.finish(&mut [0u8; 17]), Err(Error::Err));
+}
+
+#[test]
+fn gmac_cavp() {
+    let tv = utilities::testutils::parse_rsp_vectors(&["./tests/tv/gcmEncryptExtIV.rsp"]);
+    let tv: Vec<_> = tv.iter().filter(|t| t.get("PT").is_empty()).collect();
+    assert!(!tv.is_empty());
+
+    for t in tv {
+        match t.hex("Key").len() {
+            16 => gmac_vector::<AES128>(t),
+            _ => gmac_vector::<AES256>(t),
+        }
+    }
+}
+
+#[test]
+fn gmac_iv_required() {
+    let mut gmac = GMAC::<AES128>::new(&[0u8; 16]).unwrap();
+    let mut out = [0u8; 16];
+
+    assert_eq!(gmac.update(b"abc"), Err(Error::Err));
+    assert_eq!(gmac.finish(&mut out), Err(Error::Err));
+    assert_eq!(gmac.init(&[]), Err(Error::Err));
+
+    gmac.init(&[0u8; 12]).unwrap();
+    gmac.update(b"abc").unwrap();
+    gmac.init(&[0u8; 12]).unwrap();
+    gmac.finish(&mut out).unwrap();
+    /* GMAC of the empty message under a zero key and IV is the GCM test case 1 tag. */
+    assert_eq!(hex::decode("58e2fccefa7e3061367f1d57a4e7455a").unwrap(), out);
+
+    /* The IV is consumed by finish. */
+    assert_eq!(gmac.update(b"abc"), Err(Error::Err));
+    assert_eq!(gmac.finish(&mut out), Err(Error::Err));
+    assert_eq!(gmac.finish(&mut [0u8; 17]), Err(Error::Err));
+}
@@ -0,0 +1,141 @@
+use rucola::hash::SHA;
+use rucola::drbg::{DRBG, HashDRBG, HMACDRBG, CTRDRBG128, CTRDRBG192, CTRDRBG256};
+use rucola::common::Error;
+use utilities::testutils::{RspVector, hash_param};
+
+#[test]
+fn hash_drbg() {
+    let tv = utilities::testutils::parse_rsp_vectors(&["./tests/tv/generated/Hash_DRBG.rsp"]);
+    assert!(!tv.is_empty());
+
+    for t in tv {
+        utilities::testutils::drbg_vector_test(&mut HashDRBG::new(hash_param(&t)).unwrap(), &t);
+    }
+}
+
+#[test]
+fn hash_drbg_cavs() {
+    let tv = utilities::testutils::parse_rsp_vectors(&["./tests/tv/Hash_DRBG_CAVS.rsp"]);
+    assert_eq!(tv.len(), 2);
+
+    for t in tv {
+        utilities::testutils::drbg_vector_test(&mut HashDRBG::new(hash_param(&t)).unwrap(), &t);
+    }
+}
+
+#[test]
+fn hmac_drbg() {
+    let tv = utilities::testutils::parse_rsp_vectors(&["./tests/tv/generated/HMAC_DRBG.rsp"]);
+    assert!(!tv.is_empty());
+
+    for t in tv {
+        utilities::testutils::drbg_vector_test(&mut HMACDRBG::new(hash_param(&t)).unwrap(), &t);
+    }
+}
+
+#[test]
+fn hmac_drbg_cavs() {
+    let tv = utilities::testutils::parse_rsp_vectors(&["./tests/tv/HMAC_DRBG_CAVS.rsp"]);
+    assert_eq!(tv.len(), 900);
+
+    for t in tv {
+        utilities::testutils::drbg_vector_test(&mut HMACDRBG::new(hash_param(&t)).unwrap(), &t);
+    }
+}
+
+fn ctr_drbg_vector_test(t: &RspVector) {
+    let (alg, df) = t.params.keys()
+        .find(|k| k.starts_with("AES-"))
+        .map(|k| k.split_once(' ').unwrap())
+        .unwrap();
+    let df = df == "use df";
+
+    match alg {
+        "AES-128" => utilities::testutils::drbg_vector_test(&mut CTRDRBG128::new(df).unwrap(), t),
+        "AES-192" => utilities::testutils::drbg_vector_test(&mut CTRDRBG192::new(df).unwrap(), t),
+        _ => utilities::testutils::drbg_vector_test(&mut CTRDRBG256::new(df).unwrap(), t),
+    }
+}
+
+#[test]
+fn ctr_drbg() {
+    let tv = utilities::testutils::parse_rsp_vectors(&["./tests/tv/generated/CTR_DRBG.rsp"]);
+    assert!(!tv.is_empty());
+
+    for t in tv {
+        ctr_drbg_vector_test(&t);
+    }
+}
+
+#[test]
+fn ctr_drbg_cavs() {
+    let tv = utilities::testutils::parse_rsp_vectors(&["./tests/tv/CTR_DRBG_CAVS.rsp"]);
+    assert_eq!(tv.len(), 512);
+
+    for t in tv {
+        ctr_drbg_vector_test(&t);
+    }
+}
+
+#[test]
+fn ctr_drbg_no_df_lengths() {
+    let mut drbg = CTRDRBG128::new(false).unwrap();
+    let entropy = [0x11u8; 33];
+    let mut out = [0u8; 16];
+
+    /* Without derivation function the entropy input must be exactly seedlen bytes. */
+    assert_eq!(drbg.instantiate(&entropy[..16], &[], &[], false), Err(Error::Err));
+    assert_eq!(drbg.instantiate(&entropy, &[], &[], false), Err(Error::Err));
+    /* Personalization string and additional input can't exceed seedlen. */
+    assert_eq!(drbg.instantiate(&entropy[..32], &[], &entropy, false), Err(Error::Err));
+
+    drbg.instantiate(&entropy[..32], &[], &entropy[..32], false).unwrap();
+    assert_eq!(drbg.generate(&mut out, &entropy), Err(Error::Err));
+    drbg.generate(&mut out, &entropy[..32]).unwrap();
+    assert_eq!(drbg.reseed(&entropy[..31], &[]), Err(Error::Err));
+}
+
+fn state_machine<D: DRBG>(drbg: &mut D) {
+    let entropy = [0x5au8; 32];
+    let nonce = [0xa5u8; 16];
+    let mut out = [0u8; 64];
+
+    /* Not instantiated yet. */
+    assert_eq!(drbg.generate(&mut out, &[]), Err(Error::Err));
+    assert_eq!(drbg.reseed(&entropy, &[]), Err(Error::Err));
+
+    /* Not enough entropy for the security strength. */
+    assert_eq!(drbg.instantiate(&entropy[..15], &nonce, &[], false), Err(Error::Err));
+
+    drbg.instantiate(&entropy, &nonce, b"pers", false).unwrap();
+    drbg.set_reseed_interval(2).unwrap();
+    drbg.generate(&mut out, &[]).unwrap();
+    drbg.generate(&mut out, b"additional").unwrap();
+    assert_eq!(drbg.generate(&mut out, &[]), Err(Error::ReseedRequired));
+    drbg.reseed(&entropy, &[]).unwrap();
+    drbg.generate(&mut out, &[]).unwrap();
+
+    /* Requests are limited to 2^19 bits. */
+    let mut big = vec![0u8; (1 << 16) + 1];
+    assert_eq!(drbg.generate(&mut big, &[]), Err(Error::Err));
+    drbg.generate(&mut big[..1 << 16], &[]).unwrap();
+
+    /* With prediction resistance every request needs fresh entropy. */
+    drbg.uninstantiate();
+    drbg.instantiate(&entropy, &nonce, &[], true).unwrap();
+    assert_eq!(drbg.generate(&mut out, &[]), Err(Error::ReseedRequired));
+    drbg.generate_pr(&entropy, &mut out, &[]).unwrap();
+    assert_eq!(drbg.generate_pr(&entropy[..8], &mut out, &[]), Err(Error::Err));
+
+    drbg.uninstantiate();
+    assert_eq!(drbg.generate(&mut out, &[]), Err(Error::Err));
+    assert_eq!(drbg.set_reseed_interval(0), Err(Error::Err));
+    assert_eq!(drbg.set_reseed_interval((1 << 48) + 1), Err(Error::Err));
+}
+
+#[test]
+fn drbg_state_machine() {
+    state_machine(&mut HashDRBG::new(SHA::new_sha256()).unwrap());
+    state_machine(&mut HMACDRBG::new(SHA::new_sha256()).unwrap());
+    state_machine(&mut CTRDRBG256::new(true).unwrap());
+}
@@ -0,0 +1,219 @@
+use rucola::common::api::EntropySource;
+use rucola::common::{Error, Success};
+use rucola::drbg::{DRBG, HMACDRBG, CTRDRBG256};
+use rucola::entropy::{
+    AdaptiveProportionTest, HealthTestConfig, HealthTested, RepetitionCountTest, TestEntropy, APT_WINDOW_SIZE,
+};
+use rucola::hash::SHA;
+
+fn pattern(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i * 7 + 3) as u8).collect()
+}
+
+#[test]
+fn test_entropy() {
+    let data = pattern(40);
+    let mut source = TestEntropy::new(&data);
+    let mut out = [0u8; 16];
+
+    assert_eq!(source.fill_entropy(&mut out), Ok(Success::OK));
+    assert_eq!(out, data[..16]);
+    assert_eq!(source.fill_entropy(&mut out), Ok(Success::OK));
+    assert_eq!(out, data[16..32]);
+    assert_eq!(source.remaining(), 8);
+
+    /* Never hands out partial output. */
+    assert_eq!(source.fill_entropy(&mut out), Err(Error::EntropyFailure));
+    assert_eq!(source.remaining(), 8);
+    assert_eq!(source.fill_entropy(&mut out[..8]), Ok(Success::OK));
+    assert_eq!(source.fill_entropy(&mut out[..1]), Err(Error::EntropyFailure));
+}
+
+#[test]
+fn drbg_from_entropy_source() {
+    let data = pattern(200);
+    let mut expected = [0u8; 64];
+    let mut out = [0u8; 64];
+
+    /* SHA-256 takes 32 bytes of entropy input and a 16 byte nonce. */
+    let mut drbg = HMACDRBG::new(SHA::new_sha256()).unwrap();
+    drbg.instantiate(&data[..32], &data[32..48], b"pers", false).unwrap();
+    drbg.reseed(&data[48..80], b"add").unwrap();
+    drbg.generate(&mut expected, &[]).unwrap();
+
+    let mut source = TestEntropy::new(&data);
+    let mut drbg = HMACDRBG::new(SHA::new_sha256()).unwrap();
+    drbg.instantiate_from(&mut source, b"pers", false).unwrap();
+    drbg.reseed_from(&mut source, b"add").unwrap();
+    drbg.generate_from(&mut source, &mut out, &[]).unwrap();
+    assert_eq!(out, expected);
+    assert_eq!(source.remaining(), 120);
+
+    /* Without derivation function CTR_DRBG takes seedlen bytes and no nonce. */
+    let mut drbg = CTRDRBG256::new(false).unwrap();
+    drbg.instantiate(&data[..48], &[], &[], false).unwrap();
+    drbg.generate(&mut expected, &[]).unwrap();
+
+    let mut source = TestEntropy::new(&data);
+    let mut drbg = CTRDRBG256::new(false).unwrap();
+    drbg.instantiate_from(&mut source, &[], false).unwrap();
+    drbg.generate_from(&mut source, &mut out, &[]).unwrap();
+    assert_eq!(out, expected);
+    assert_eq!(source.remaining(), 152);
+}
+
+#[test]
+fn drbg_from_entropy_source_reseeds() {
+    let data = pattern(200);
+    let mut expected = [0u8; 32];
+    let mut out = [0u8; 32];
+
+    /* Prediction resistance pulls fresh entropy for every request. */
+    let mut drbg = HMACDRBG::new(SHA::new_sha256()).unwrap();
+    drbg.instantiate(&data[..32], &data[32..48], &[], true).unwrap();
+    drbg.generate_pr(&data[48..80], &mut expected, b"add").unwrap();
+
+    let mut source = TestEntropy::new(&data);
+    let mut drbg = HMACDRBG::new(SHA::new_sha256()).unwrap();
+    drbg.instantiate_from(&mut source, &[], true).unwrap();
+    drbg.generate_from(&mut source, &mut out, b"add").unwrap();
+    assert_eq!(out, expected);
+    assert_eq!(source.remaining(), 120);
+
+    /* An exhausted reseed interval triggers a reseed. */
+    let mut drbg = HMACDRBG::new(SHA::new_sha256()).unwrap();
+    drbg.instantiate(&data[..32], &data[32..48], &[], false).unwrap();
+    drbg.set_reseed_interval(1).unwrap();
+    drbg.generate(&mut out, &[]).unwrap();
+    drbg.reseed(&data[48..80], b"add").unwrap();
+    drbg.generate(&mut expected, &[]).unwrap();
+
+    let mut source = TestEntropy::new(&data);
+    let mut drbg = HMACDRBG::new(SHA::new_sha256()).unwrap();
+    drbg.instantiate_from(&mut source, &[], false).unwrap();
+    drbg.set_reseed_interval(1).unwrap();
+    drbg.generate_from(&mut source, &mut out, &[]).unwrap();
+    drbg.generate_from(&mut source, &mut out, b"add").unwrap();
+    assert_eq!(out, expected);
+
+    /* Entropy failures are passed on and no output is generated. */
+    let mut source = TestEntropy::new(&data[..40]);
+    let mut drbg = HMACDRBG::new(SHA::new_sha256()).unwrap();
+    assert_eq!(drbg.instantiate_from(&mut source, &[], false), Err(Error::EntropyFailure));
+    assert_eq!(drbg.generate(&mut out, &[]), Err(Error::Err));
+}
+
+/* Synthetic noise source producing `sample(i)` as its i-th byte. */
+struct Synthetic<F: Fn(usize) -> u8> {
+    sample: F,
+    pos: usize,
+}
+
+impl<F: Fn(usize) -> u8> EntropySource for Synthetic<F> {
+    fn fill_entropy(&mut self, output: &mut [u8]) -> Result<Success, Error> {
+        for b in output.iter_mut() {
+            *b = (self.sample)(self.pos);
+            self.pos += 1;
+        }
+        Ok(Success::OK)
+    }
+}
+
+fn synthetic<F: Fn(usize) -> u8>(sample: F) -> HealthTested<Synthetic<F>> {
+    let config = HealthTestConfig::for_min_entropy(8).unwrap();
+    HealthTested::new(Synthetic { sample, pos: 0 }, config).unwrap()
+}
+
+#[test]
+fn health_test_config() {
+    let config = HealthTestConfig::for_min_entropy(8).unwrap();
+    assert_eq!((config.rct_cutoff, config.apt_cutoff, config.apt_window), (4, 13, APT_WINDOW_SIZE));
+    let config = HealthTestConfig::for_min_entropy(1).unwrap();
+    assert_eq!((config.rct_cutoff, config.apt_cutoff), (21, 311));
+
+    assert!(HealthTestConfig::for_min_entropy(0).is_err());
+    assert!(HealthTestConfig::for_min_entropy(9).is_err());
+    assert!(RepetitionCountTest::new(1).is_err());
+    assert!(AdaptiveProportionTest::new(13, 12).is_err());
+}
+
+#[test]
+fn repetition_count_test() {
+    let mut rct = RepetitionCountTest::new(4).unwrap();
+    for b in [1, 1, 1, 2, 2, 2, 1, 1, 1] {
+        assert_eq!(rct.update(b), Ok(Success::OK));
+    }
+    assert_eq!(rct.update(1), Err(Error::HealthTest));
+}
+
+#[test]
+fn adaptive_proportion_test() {
+    /* 12 occurrences of the first sample per window pass, the window restarts afterwards. */
+    let mut apt = AdaptiveProportionTest::new(13, 64).unwrap();
+    for i in 0..3 * 64 {
+        let b = if i % 64 < 12 { 7 } else { i as u8 };
+        assert_eq!(apt.update(b), Ok(Success::OK));
+    }
+
+    let mut apt = AdaptiveProportionTest::new(13, 64).unwrap();
+    for i in 0..24 {
+        assert_eq!(apt.update(if i % 2 == 0 { 7 } else { 100 + i }), Ok(Success::OK));
+    }
+    assert_eq!(apt.update(7), Err(Error::HealthTest));
+}
+
+#[test]
+fn health_tested_source() {
+    let mut out = [0u8; 1024];
+
+    /* Every byte value occurs twice per window and never twice in a row. */
+    let mut good = synthetic(|i| i as u8);
+    for _ in 0..16 {
+        assert_eq!(good.fill_entropy(&mut out), Ok(Success::OK));
+    }
+    assert!(!good.failed());
+
+    /* A stuck source trips the Repetition Count Test. */
+    let mut stuck = synthetic(|i| if i < 100 { i as u8 } else { 0x5a });
+    assert_eq!(stuck.fill_entropy(&mut out[..100]), Ok(Success::OK));
+    assert_eq!(stuck.fill_entropy(&mut out[..16]), Err(Error::HealthTest));
+    assert_eq!(out[..16], [0; 16]);
+    assert!(stuck.failed());
+
+    /* A source that delivers 0x00 every fourth byte trips the Adaptive Proportion Test. */
+    let mut biased = synthetic(|i| if i % 4 == 0 { 0 } else { (i % 255 + 1) as u8 });
+    assert_eq!(biased.fill_entropy(&mut out), Err(Error::HealthTest));
+    assert_eq!(out, [0; 1024]);
+
+    /* Failed sources stay failed. */
+    assert_eq!(stuck.fill_entropy(&mut out[..1]), Err(Error::HealthTest));
+    assert_eq!(biased.fill_entropy(&mut out[..1]), Err(Error::HealthTest));
+
+    let mut drbg = HMACDRBG::new(SHA::new_sha256()).unwrap();
+    let mut stuck = synthetic(|_| 0);
+    assert_eq!(drbg.instantiate_from(&mut stuck, &[], false), Err(Error::HealthTest));
+
+    let mut good = synthetic(|i| (i * 3) as u8);
+    drbg.instantiate_from(&mut good, &[], true).unwrap();
+    drbg.generate_from(&mut good, &mut out[..32], &[]).unwrap();
+}
+
+#[cfg(all(feature = "std", target_os = "linux"))]
+#[test]
+fn getrandom() {
+    use rucola::entropy::GetRandom;
+
+    let mut a = [0u8; 64];
+    let mut b = [0u8; 64];
+    assert_eq!(GetRandom.fill_entropy(&mut a), Ok(Success::OK));
+    assert_eq!(GetRandom.fill_entropy(&mut b), Ok(Success::OK));
+    assert_ne!(a, b);
+    assert_eq!(GetRandom.fill_entropy(&mut []), Ok(Success::OK));
+
+    let mut drbg = CTRDRBG256::new(true).unwrap();
+    drbg.instantiate_from(&mut GetRandom, &[], false).unwrap();
+    drbg.generate_from(&mut GetRandom, &mut a, &[]).unwrap();
+
+    let mut source = HealthTested::new(GetRandom, HealthTestConfig::for_min_entropy(8).unwrap()).unwrap();
+    drbg.reseed_from(&mut source, &[]).unwrap();
+}
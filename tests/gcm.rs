@@ -0,0 +1,191 @@
+use rucola::cipher::{AES128, AES256, Direction, GCM, GCMCtx, GCM_TAG_SIZE};
+use rucola::common::api::{AEAD, BlockCipher, IVInit, InputOutputUpdate, OutputFinish};
+use rucola::common::Error;
+use utilities::testutils::RspVector;
+
+fn one_shot<C: BlockCipher<16>>(t: &RspVector) {
+    let gcm = GCM::<C>::new(&t.hex("Key")).unwrap();
+    let (iv, aad, pt, ct, tag) = (t.hex("IV"), t.hex("AAD"), t.hex("PT"), t.hex("CT"), t.hex("Tag"));
+    let valid = !t.has("Result") || t.get("Result") == "pass";
+
+    let mut out = vec![0u8; pt.len() + GCM_TAG_SIZE];
+    if valid {
+        assert_eq!(gcm.seal(&iv, &aad, &pt, &mut out), Ok(out.len()));
+        assert_eq!(out[..pt.len()], ct);
+        assert_eq!(out[pt.len()..], tag);
+    }
+
+    /* The plaintext is zeroed if the tag does not match. */
+    let mut input = ct.clone();
+    input.extend_from_slice(&tag);
+    let mut pt_out = vec![0xaau8; ct.len()];
+    if valid {
+        assert_eq!(gcm.open(&iv, &aad, &input, &mut pt_out), Ok(pt.len()));
+        assert_eq!(pt_out, pt);
+    } else {
+        assert_eq!(gcm.open(&iv, &aad, &input, &mut pt_out), Err(Error::AuthFailed));
+        assert!(pt_out.iter().all(|b| *b == 0));
+    }
+
+    let mut buffer = ct.clone();
+    let ret = gcm.open_in_place(&iv, &aad, &mut buffer, &tag);
+    if valid {
+        assert!(ret.is_ok());
+        assert_eq!(buffer, pt);
+    } else {
+        assert_eq!(ret, Err(Error::AuthFailed));
+        assert_eq!(buffer, ct);
+    }
+}
+
+fn streaming<C: BlockCipher<16>>(t: &RspVector) {
+    let (iv, aad, pt, ct, tag) = (t.hex("IV"), t.hex("AAD"), t.hex("PT"), t.hex("CT"), t.hex("Tag"));
+
+    for step in [1, 15, 17, 64] {
+        let mut enc = GCMCtx::<C>::new(&t.hex("Key"), Direction::Encrypt).unwrap();
+        let mut out = vec![0u8; pt.len()];
+        let mut out_tag = [0u8; GCM_TAG_SIZE];
+        enc.init(&iv).unwrap();
+        for a in aad.chunks(step) {
+            enc.update_aad(a).unwrap();
+        }
+        for (i, o) in pt.chunks(step).zip(out.chunks_mut(step)) {
+            assert_eq!(enc.update(i, o), Ok(i.len()));
+        }
+        assert_eq!(enc.finish(&mut out_tag), Ok(GCM_TAG_SIZE));
+        assert_eq!(out, ct);
+        assert_eq!(out_tag[..], tag);
+
+        let mut dec = GCMCtx::<C>::new(&t.hex("Key"), Direction::Decrypt).unwrap();
+        dec.init(&iv).unwrap();
+        dec.update_aad(&aad).unwrap();
+        for (i, o) in ct.chunks(step).zip(out.chunks_mut(step)) {
+            dec.update(i, o).unwrap();
+        }
+        assert!(dec.verify(&tag).is_ok());
+        assert_eq!(out, pt);
+    }
+}
+
+fn by_keylen(t: &RspVector, f128: fn(&RspVector), f256: fn(&RspVector)) {
+    match t.hex("Key").len() {
+        16 => f128(t),
+        _ => f256(t),
+    }
+}
+
+#[test]
+fn gcm_cavp() {
+    let tv = utilities::testutils::parse_rsp_vectors(&["./tests/tv/gcmEncryptExtIV.rsp"]);
+    assert!(!tv.is_empty());
+
+    for t in tv.iter() {
+        by_keylen(t, one_shot::<AES128>, one_shot::<AES256>);
+    }
+    for t in tv.iter().step_by(13) {
+        by_keylen(t, streaming::<AES128>, streaming::<AES256>);
+    }
+}
+
+#[test]
+fn gcm_wycheproof() {
+    let tv = utilities::testutils::parse_rsp_vectors(&["./tests/tv/gcmWycheproof.rsp"]);
+    assert!(!tv.is_empty());
+
+    for t in tv.iter() {
+        by_keylen(t, one_shot::<AES128>, one_shot::<AES256>);
+    }
+}
+
+#[test]
+fn gcm_tag_truncation() {
+    let key = [0x11u8; 16];
+    let iv = [0x22u8; 12];
+    let msg = [0x33u8; 40];
+
+    let mut full = [0u8; 56];
+    GCM::<AES128>::new(&key).unwrap().seal(&iv, b"aad", &msg, &mut full).unwrap();
+
+    for tag_len in [4, 8, 12, 13, 14, 15, 16] {
+        let gcm = GCM::<AES128>::with_tag_len(&key, tag_len).unwrap();
+        let mut out = [0u8; 56];
+        assert_eq!(gcm.seal(&iv, b"aad", &msg, &mut out), Ok(40 + tag_len));
+        assert_eq!(out[..40 + tag_len], full[..40 + tag_len]);
+
+        let mut pt = [0u8; 40];
+        assert_eq!(gcm.open(&iv, b"aad", &out[..40 + tag_len], &mut pt), Ok(40));
+        assert_eq!(pt, msg);
+
+        /* The tag has to have exactly the configured length. */
+        let mut long = [0u8; 56];
+        let expected = if tag_len == 16 { Ok(40) } else { Err(Error::AuthFailed) };
+        assert_eq!(gcm.open(&iv, b"aad", &full, &mut long), expected);
+        let mut buffer = out;
+        assert_eq!(gcm.open_in_place(&iv, b"aad", &mut buffer[..40], &full[40..39 + tag_len]), Err(Error::Err));
+    }
+
+    for tag_len in [0, 1, 3, 5, 7, 9, 11, 17] {
+        assert!(GCM::<AES128>::with_tag_len(&key, tag_len).is_err());
+    }
+}
+
+#[test]
+fn gcm_detached_in_place() {
+    let gcm = GCM::<AES256>::new(&[0x42u8; 32]).unwrap();
+    let iv = [7u8; 12];
+    let msg: Vec<u8> = (0..100).collect();
+
+    let mut combined = [0u8; 116];
+    gcm.seal(&iv, b"header", &msg, &mut combined).unwrap();
+
+    let mut ct = [0u8; 100];
+    let mut tag = [0u8; 16];
+    gcm.seal_detached(&iv, b"header", &msg, &mut ct, &mut tag).unwrap();
+    assert_eq!(ct, combined[..100]);
+    assert_eq!(tag, combined[100..]);
+
+    let mut buffer = msg.clone();
+    gcm.seal_in_place(&iv, b"header", &mut buffer, &mut tag).unwrap();
+    assert_eq!(buffer, ct);
+    assert_eq!(tag, combined[100..]);
+
+    let mut pt = [0u8; 100];
+    gcm.open_detached(&iv, b"header", &ct, &tag, &mut pt).unwrap();
+    assert_eq!(pt[..], msg[..]);
+
+    /* Modified ciphertext, AAD, IV or tag. */
+    let mut bad = ct;
+    bad[99] ^= 1;
+    assert_eq!(gcm.open_detached(&iv, b"header", &bad, &tag, &mut pt), Err(Error::AuthFailed));
+    assert_eq!(gcm.open_detached(&iv, b"headeR", &ct, &tag, &mut pt), Err(Error::AuthFailed));
+    assert_eq!(gcm.open_detached(&[8u8; 12], b"header", &ct, &tag, &mut pt), Err(Error::AuthFailed));
+    tag[0] ^= 0x80;
+    assert_eq!(gcm.open_in_place(&iv, b"header", &mut buffer, &tag), Err(Error::AuthFailed));
+    assert_eq!(buffer, ct);
+
+    /* Output too small, empty IV. */
+    assert_eq!(gcm.seal(&iv, &[], &msg, &mut combined[..115]), Err(Error::Err));
+    assert_eq!(gcm.seal(&[], &[], &msg, &mut combined), Err(Error::Err));
+}
+
+#[test]
+fn gcm_streaming_errors() {
+    let key = [1u8; 16];
+    let mut out = [0u8; 16];
+
+    let mut enc = GCMCtx::<AES128>::new(&key, Direction::Encrypt).unwrap();
+    assert_eq!(enc.update_aad(b"a"), Err(Error::Err));
+    assert_eq!(enc.update(b"a", &mut out), Err(Error::Err));
+    enc.init(&[0; 12]).unwrap();
+    enc.update(b"a", &mut out).unwrap();
+    assert_eq!(enc.update_aad(b"a"), Err(Error::Err));
+    assert_eq!(enc.verify(&out), Err(Error::Err));
+    assert_eq!(enc.finish(&mut out[..15]), Err(Error::Err));
+    assert_eq!(enc.finish(&mut out), Ok(16));
+    assert_eq!(enc.finish(&mut out), Err(Error::Err));
+
+    let mut dec = GCMCtx::<AES128>::new(&key, Direction::Decrypt).unwrap();
+    dec.init(&[0; 12]).unwrap();
+    assert_eq!(dec.finish(&mut out), Err(Error::Err));
+    assert_eq!(dec.verify(&[0; 16]), Err(Error::AuthFailed));
+}
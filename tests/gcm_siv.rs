@@ -0,0 +1,88 @@
+use rucola::cipher::{AES128, AES192, AES256, GCMSIV, GCM_SIV_TAG_SIZE, POLYVAL};
+use rucola::common::api::{AEAD, BlockCipher};
+use rucola::common::Error;
+use utilities::testutils::RspVector;
+
+fn gcm_siv_vector<C: BlockCipher<16>>(t: &RspVector) {
+    let aead = GCMSIV::<C>::new(&t.hex("Key")).unwrap();
+    let (nonce, aad, pt, ct) = (t.hex("Nonce"), t.hex("AAD"), t.hex("PT"), t.hex("CT"));
+    let valid = !t.has("Result") || t.get("Result") == "pass";
+
+    let mut out = vec![0u8; pt.len() + GCM_SIV_TAG_SIZE];
+    let mut pt_out = vec![0xaau8; ct.len().saturating_sub(GCM_SIV_TAG_SIZE)];
+    if valid {
+        assert_eq!(aead.seal(&nonce, &aad, &pt, &mut out), Ok(out.len()));
+        assert_eq!(out, ct);
+        assert_eq!(aead.open(&nonce, &aad, &ct, &mut pt_out), Ok(pt.len()));
+        assert_eq!(pt_out, pt);
+    } else {
+        assert!(aead.open(&nonce, &aad, &ct, &mut pt_out).is_err());
+        assert!(pt_out.iter().all(|b| *b == 0 || *b == 0xaa));
+    }
+}
+
+fn by_keylen(t: &RspVector) {
+    match t.param("Keylen").unwrap() {
+        "128" => gcm_siv_vector::<AES128>(t),
+        _ => gcm_siv_vector::<AES256>(t),
+    }
+}
+
+#[test]
+fn gcm_siv_rfc8452() {
+    let tv = utilities::testutils::parse_rsp_vectors(&["./tests/tv/gcmsivRFC8452.rsp"]);
+    assert_eq!(tv.len(), 50);
+
+    for t in tv.iter() {
+        by_keylen(t);
+    }
+}
+
+#[test]
+fn gcm_siv_wycheproof() {
+    let tv = utilities::testutils::parse_rsp_vectors(&["./tests/tv/gcmsivWycheproof.rsp"]);
+    assert!(!tv.is_empty());
+
+    for t in tv.iter() {
+        by_keylen(t);
+    }
+}
+
+/* Example of RFC 8452, appendix A. */
+#[test]
+fn polyval() {
+    let h = hex::decode("25629347589242761d31f826ba4b757b").unwrap();
+    let x = hex::decode("4f4f95668c83dfb6401762bb2d01a262d1a24ddd2721d006bbe45f20d3c9f362").unwrap();
+    let mut out = [0u8; 16];
+
+    for step in [1, 5, 16, 32] {
+        let mut polyval = POLYVAL::new(h[..].try_into().unwrap());
+        for chunk in x.chunks(step) {
+            polyval.update(chunk);
+        }
+        polyval.finish(&mut out);
+        assert_eq!(hex::encode(out), "f7a3b47b846119fae5b7866cf5e5b77e");
+    }
+}
+
+#[test]
+fn gcm_siv_errors() {
+    let aead = GCMSIV::<AES128>::new(&[3u8; 16]).unwrap();
+    let mut out = [0u8; 48];
+    let mut pt = [0u8; 32];
+
+    assert_eq!(aead.seal(&[0; 11], &[], &[0; 32], &mut out), Err(Error::Err));
+    assert_eq!(aead.seal(&[0; 12], &[], &[0; 32], &mut out[..47]), Err(Error::Err));
+    assert_eq!(aead.seal(&[0; 12], &[], &[0; 32], &mut out), Ok(48));
+    assert_eq!(aead.open(&[0; 12], &[], &out[..15], &mut pt), Err(Error::Err));
+    assert_eq!(aead.open(&[0; 12], &[], &out, &mut pt[..31]), Err(Error::Err));
+
+    /* A failed decryption leaves no plaintext behind. */
+    out[47] ^= 1;
+    assert_eq!(aead.open(&[0; 12], &[], &out, &mut pt), Err(Error::AuthFailed));
+    assert_eq!(pt, [0; 32]);
+
+    /* RFC 8452 only defines AES-128 and AES-256. */
+    assert!(GCMSIV::<AES192>::new(&[0u8; 24]).is_err());
+    assert!(GCMSIV::<AES256>::new(&[0u8; 16]).is_err());
+}
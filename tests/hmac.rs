@@ -0,0 +1,71 @@
+use rucola::hash::SHA;
+use rucola::mac::HMAC;
+use rucola::common::Error;
+use rucola::common::api::{DefaultInit, SingleInputUpdate, SingleOutputFinish};
+
+fn sha_for_len(l: &str) -> SHA {
+    match l {
+        "20" => SHA::new_sha1(),
+        "28" => SHA::new_sha224(),
+        "32" => SHA::new_sha256(),
+        "48" => SHA::new_sha384(),
+        "64" => SHA::new_sha512(),
+        _ => panic!("unexpected L"),
+    }
+}
+
+#[test]
+fn hmac_cavp() {
+    let tv = utilities::testutils::parse_rsp_vectors(&["./tests/tv/HMAC.rsp"]);
+    assert!(tv.len() > 1000);
+
+    for t in tv {
+        let mut hmac = HMAC::new(sha_for_len(t.param("L").unwrap()), &t.hex("Key")).unwrap();
+        let mac = t.hex("Mac");
+        let mut out = vec![0u8; mac.len()];
+
+        hmac.init().unwrap();
+        hmac.update(&t.hex("Msg")).unwrap();
+        hmac.finish(&mut out).unwrap();
+        assert_eq!(mac, out);
+    }
+}
+
+#[test]
+fn hmac_streaming_api() {
+    let tv = utilities::testutils::parse_rsp_vectors(&["./tests/tv/HMAC.rsp"]);
+    for l in ["20", "28", "32", "48", "64"] {
+        let mut hmac = HMAC::new(sha_for_len(l), b"").unwrap();
+        for t in tv.iter().filter(|t| t.param("L") == Some(l) && t.get("Tlen") == l) {
+            hmac.set_key(&t.hex("Key")).unwrap();
+            let v = vec![(t.hex("Msg"), t.hex("Mac"))];
+            match l {
+                "20" => utilities::testutils::streaming_api_test::<20, HMAC>(v, &mut hmac),
+                "28" => utilities::testutils::streaming_api_test::<28, HMAC>(v, &mut hmac),
+                "32" => utilities::testutils::streaming_api_test::<32, HMAC>(v, &mut hmac),
+                "48" => utilities::testutils::streaming_api_test::<48, HMAC>(v, &mut hmac),
+                _ => utilities::testutils::streaming_api_test::<64, HMAC>(v, &mut hmac),
+            }
+        }
+    }
+}
+
+#[test]
+fn hmac_output_size() {
+    /* Like every MAC a shorter output is truncated, a longer one is an error. */
+    let mut hmac = HMAC::new(SHA::new_sha256(), b"key").unwrap();
+    let mut long = [0u8; 33];
+    let mut short = [0u8; 16];
+
+    hmac.init().unwrap();
+    hmac.update(b"msg").unwrap();
+    assert_eq!(hmac.finish(&mut long), Err(Error::Err));
+
+    hmac.init().unwrap();
+    hmac.update(b"msg").unwrap();
+    hmac.finish(&mut long[..32]).unwrap();
+    hmac.init().unwrap();
+    hmac.update(b"msg").unwrap();
+    hmac.finish(&mut short).unwrap();
+    assert_eq!(long[..16], short);
+}
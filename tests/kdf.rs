@@ -0,0 +1,70 @@
+use rucola::cipher::{AES128, AES192, AES256};
+use rucola::hash::SHA;
+use rucola::mac::{CMAC, HMAC};
+use rucola::kdf::{x963_kdf, one_step_kdf, one_step_kdf_hmac, kbkdf_counter};
+use utilities::testutils::hash_param;
+
+#[test]
+fn x963() {
+    let tv = utilities::testutils::parse_rsp_vectors(&["./tests/tv/X963KDF.rsp"]);
+    assert!(!tv.is_empty());
+
+    for t in tv {
+        let mut hash = hash_param(&t);
+        let expected = t.hex("key_data");
+        let mut out = vec![0u8; expected.len()];
+
+        x963_kdf(&mut hash, &t.hex("Z"), &t.hex("SharedInfo"), &mut out).unwrap();
+        assert_eq!(expected, out);
+    }
+}
+
+#[test]
+fn one_step() {
+    let tv = utilities::testutils::parse_rsp_vectors(&[
+        "./tests/tv/OneStepKDF.rsp",
+        "./tests/tv/generated/OneStepKDF.rsp",
+    ]);
+    assert!(!tv.is_empty());
+
+    for t in tv {
+        let hash = hash_param(&t);
+        let expected = t.hex("DerivedKey");
+        let mut out = vec![0u8; expected.len()];
+
+        if t.param("Aux") == Some("hmac") {
+            let mut hmac = HMAC::new(hash, &t.hex("Salt")).unwrap();
+            one_step_kdf_hmac(&mut hmac, &t.hex("Z"), &t.hex("OtherInfo"), &mut out).unwrap();
+        } else {
+            let mut hash = hash;
+            one_step_kdf(&mut hash, &t.hex("Z"), &t.hex("OtherInfo"), &mut out).unwrap();
+        }
+        assert_eq!(expected, out);
+    }
+}
+
+#[test]
+fn kbkdf() {
+    let tv = utilities::testutils::parse_rsp_vectors(&[
+        "./tests/tv/KDFCTR_gen.rsp",
+        "./tests/tv/generated/KBKDFCTR.rsp",
+    ]);
+    assert!(!tv.is_empty());
+
+    for t in tv {
+        let (ki, fixed, expected) = (t.hex("KI"), t.hex("FixedInputData"), t.hex("KO"));
+        let mut out = vec![0u8; t.get("L").parse::<usize>().unwrap() / 8];
+
+        match t.param("PRF").unwrap() {
+            "CMAC_AES128" => kbkdf_counter(&mut CMAC::<AES128>::new(&ki).unwrap(), &fixed, &mut out),
+            "CMAC_AES192" => kbkdf_counter(&mut CMAC::<AES192>::new(&ki).unwrap(), &fixed, &mut out),
+            "CMAC_AES256" => kbkdf_counter(&mut CMAC::<AES256>::new(&ki).unwrap(), &fixed, &mut out),
+            "HMAC_SHA1" => kbkdf_counter(&mut HMAC::new(SHA::new_sha1(), &ki).unwrap(), &fixed, &mut out),
+            "HMAC_SHA256" => kbkdf_counter(&mut HMAC::new(SHA::new_sha256(), &ki).unwrap(), &fixed, &mut out),
+            "HMAC_SHA384" => kbkdf_counter(&mut HMAC::new(SHA::new_sha384(), &ki).unwrap(), &fixed, &mut out),
+            "HMAC_SHA512" => kbkdf_counter(&mut HMAC::new(SHA::new_sha512(), &ki).unwrap(), &fixed, &mut out),
+            _ => panic!("unexpected PRF"),
+        }.unwrap();
+        assert_eq!(expected, out);
+    }
+}
@@ -0,0 +1,161 @@
+use rucola::cipher::{AES128, AES192, AES256, KW, KWP};
+use rucola::common::api::BlockCipher;
+use rucola::common::Error;
+use utilities::testutils::RspVector;
+
+/* Wrap or unwrap one CAVS vector with KW or KWP, forward or inverse cipher. */
+fn kw_vector<C: BlockCipher<16>>(t: &RspVector, padding: bool, inverse: bool) {
+    let kek = t.hex("K");
+    let wrap = |input: &[u8], output: &mut [u8]| match (padding, inverse) {
+        (false, false) => KW::<C>::new(&kek).unwrap().wrap(input, output),
+        (false, true) => KW::<C>::with_inverse_cipher(&kek).unwrap().wrap(input, output),
+        (true, false) => KWP::<C>::new(&kek).unwrap().wrap(input, output),
+        (true, true) => KWP::<C>::with_inverse_cipher(&kek).unwrap().wrap(input, output),
+    };
+    let unwrap = |input: &[u8], output: &mut [u8]| match (padding, inverse) {
+        (false, false) => KW::<C>::new(&kek).unwrap().unwrap(input, output),
+        (false, true) => KW::<C>::with_inverse_cipher(&kek).unwrap().unwrap(input, output),
+        (true, false) => KWP::<C>::new(&kek).unwrap().unwrap(input, output),
+        (true, true) => KWP::<C>::with_inverse_cipher(&kek).unwrap().unwrap(input, output),
+    };
+
+    let c = t.hex("C");
+    let mut p = vec![0xaau8; c.len() - 8];
+    if t.has("FAIL") {
+        assert_eq!(unwrap(&c, &mut p), Err(Error::IntegrityCheck));
+        assert!(p.iter().all(|b| *b == 0));
+        return;
+    }
+
+    let expected = t.hex("P");
+    assert_eq!(unwrap(&c, &mut p), Ok(expected.len()));
+    assert_eq!(p[..expected.len()], expected);
+
+    if t.params.contains_key("AE") {
+        let mut out = vec![0u8; c.len()];
+        assert_eq!(wrap(&expected, &mut out), Ok(c.len()));
+        assert_eq!(out, c);
+    }
+}
+
+fn run(files: &[&str], padding: bool, inverse: bool) {
+    let tv = utilities::testutils::parse_rsp_vectors(files);
+    assert!(!tv.is_empty());
+
+    for t in tv.iter() {
+        if t.params.contains_key("AES-128") {
+            kw_vector::<AES128>(t, padding, inverse);
+        } else if t.params.contains_key("AES-192") {
+            kw_vector::<AES192>(t, padding, inverse);
+        } else {
+            kw_vector::<AES256>(t, padding, inverse);
+        }
+    }
+}
+
+#[test]
+fn kw() {
+    run(&["./tests/tv/KW_AE.rsp", "./tests/tv/generated/KW.rsp"], false, false);
+}
+
+#[test]
+fn kw_inverse() {
+    run(&["./tests/tv/generated/KW_inv.rsp"], false, true);
+}
+
+#[test]
+fn kwp() {
+    run(&["./tests/tv/KWP_AE.rsp", "./tests/tv/generated/KWP.rsp"], true, false);
+}
+
+#[test]
+fn kwp_inverse() {
+    run(&["./tests/tv/generated/KWP_inv.rsp"], true, true);
+}
+
+/* RFC 3394, section 4.1 and 4.6, and the examples of RFC 5649, section 6. */
+#[test]
+fn kw_rfc() {
+    let kek: Vec<u8> = (0..32).collect();
+    let key = hex::decode("00112233445566778899aabbccddeeff000102030405060708090a0b0c0d0e0f").unwrap();
+    let mut out = [0u8; 40];
+
+    let kw = KW::<AES128>::new(&kek[..16]).unwrap();
+    assert_eq!(kw.wrap(&key[..16], &mut out), Ok(24));
+    assert_eq!(hex::encode(&out[..24]), "1fa68b0a8112b447aef34bd8fb5a7b829d3e862371d2cfe5");
+
+    let kw = KW::<AES256>::new(&kek).unwrap();
+    assert_eq!(kw.wrap(&key, &mut out), Ok(40));
+    assert_eq!(
+        hex::encode(out),
+        "28c9f404c4b810f4cbccb35cfb87f8263f5786e2d80ed326cbc7f0e71a99f43bfb988b9b7a02dd21"
+    );
+
+    let kek = hex::decode("5840df6e29b02af1ab493b705bf16ea1ae8338f4dcc176a8").unwrap();
+    let kwp = KWP::<AES192>::new(&kek).unwrap();
+    assert_eq!(kwp.wrap(&hex::decode("c37b7e6492584340bed12207808941155068f738").unwrap(), &mut out), Ok(32));
+    assert_eq!(hex::encode(&out[..32]), "138bdeaa9b8fa7fc61f97742e72248ee5ae6ae5360d1ae6a5f54f373fa543b6a");
+    assert_eq!(kwp.wrap(b"\x46\x6f\x72\x50\x61\x73\x69", &mut out), Ok(16));
+    assert_eq!(hex::encode(&out[..16]), "afbeb0f07dfbf5419200f2ccb50bb24f");
+}
+
+#[test]
+fn kw_errors() {
+    let kw = KW::<AES128>::new(&[1u8; 16]).unwrap();
+    let kwp = KWP::<AES128>::new(&[1u8; 16]).unwrap();
+    let mut out = [0u8; 40];
+    let mut key = [0u8; 32];
+
+    /* KW needs at least two whole semiblocks, KWP at least one byte. */
+    assert_eq!(kw.wrap(&[0; 8], &mut out), Err(Error::Err));
+    assert_eq!(kw.wrap(&[0; 17], &mut out), Err(Error::Err));
+    assert_eq!(kw.wrap(&[0; 32], &mut out[..39]), Err(Error::Err));
+    assert_eq!(kwp.wrap(&[], &mut out), Err(Error::Err));
+    assert_eq!(kwp.wrap(&[0; 17], &mut out[..31]), Err(Error::Err));
+
+    /* Malformed input is an error, a wrong ICV an integrity check failure. */
+    kw.wrap(&[7; 32], &mut out).unwrap();
+    assert_eq!(kw.unwrap(&out[..16], &mut key), Err(Error::Err));
+    assert_eq!(kw.unwrap(&out[..39], &mut key), Err(Error::Err));
+    assert_eq!(kw.unwrap(&out, &mut key[..31]), Err(Error::Err));
+    assert_eq!(kw.unwrap(&out, &mut key), Ok(32));
+    assert_eq!(KW::<AES128>::with_inverse_cipher(&[1u8; 16]).unwrap().unwrap(&out, &mut key), Err(Error::IntegrityCheck));
+    out[20] ^= 4;
+    assert_eq!(kw.unwrap(&out, &mut key), Err(Error::IntegrityCheck));
+    assert_eq!(key, [0; 32]);
+
+    /* A KW ciphertext does not unwrap with KWP and vice versa. */
+    kw.wrap(&[7; 16], &mut out).unwrap();
+    assert_eq!(kwp.unwrap(&out[..24], &mut key), Err(Error::IntegrityCheck));
+    kwp.wrap(&[7; 16], &mut out).unwrap();
+    assert_eq!(kw.unwrap(&out[..24], &mut key), Err(Error::IntegrityCheck));
+    assert_eq!(kwp.unwrap(&out[..24], &mut key), Ok(16));
+    assert_eq!(kwp.unwrap(&out[..24], &mut key[..15]), Err(Error::Err));
+}
+
+/* Ciphertexts forged with the raw wrapping function W over A || P, where A carries
+ * a chosen message length indicator and P has twelve 0x55 bytes and four bytes of
+ * padding. Wrong length indicators and non-zero padding are integrity failures. */
+#[test]
+fn kwp_padding_checks() {
+    let kwp = KWP::<AES128>::new(&[9u8; 16]).unwrap();
+    let mut key = [0u8; 16];
+
+    let forged = [
+        ("24cff39a5883fdc9a88ced54fe160808c14c8635515aa3f5", Ok(12)),
+        ("9c97f32e0464637b7cf33e1e3da14e41f6a4f6afeafb5822", Ok(13)),
+        ("fefa6967eaedb5f2963ad80c30310ce0e16028324db25a16", Err(Error::IntegrityCheck)),
+        ("1edbc1478e5ecef474162e2475a179ccb5f23ed12c806eca", Err(Error::IntegrityCheck)),
+        ("098f2d659a68ad056febc75759b8c04605f5fd4e23b38941", Err(Error::IntegrityCheck)),
+        /* A single block with a length indicator of nine. */
+        ("50c8c8d1d4bcc4f937c83af6e3247f17", Err(Error::IntegrityCheck)),
+    ];
+    for (c, expected) in forged {
+        assert_eq!(kwp.unwrap(&hex::decode(c).unwrap(), &mut key), expected);
+        if expected.is_ok() {
+            assert_eq!(key[..12], [0x55; 12]);
+        } else {
+            assert_eq!(key, [0; 16]);
+        }
+    }
+}
@@ -0,0 +1,56 @@
+#![cfg(feature = "legacy")]
+
+use rucola::hash::{LegacyHash, SHA};
+use rucola::common::api::{DefaultInit, SingleInputUpdate, SingleOutputFinish};
+
+#[test]
+fn md5() {
+    let tv = utilities::testutils::parse_hash_vectors(&["./tests/tv/MD5.rsp"]);
+    assert_eq!(tv.len(), 28);
+    utilities::testutils::streaming_api_test::<16, LegacyHash>(tv, &mut LegacyHash::new_md5());
+}
+
+#[test]
+fn ripemd160() {
+    let tv = utilities::testutils::parse_hash_vectors(&["./tests/tv/RIPEMD160.rsp"]);
+    assert_eq!(tv.len(), 26);
+    utilities::testutils::streaming_api_test::<20, LegacyHash>(tv, &mut LegacyHash::new_ripemd160());
+}
+
+#[test]
+fn million_a() {
+    for (mut h, md) in [(LegacyHash::new_md5(), "7707d6ae4e027c70eea2a935c2296f21"),
+                        (LegacyHash::new_ripemd160(), "52783243c1697bdbe16d37f97f68f08325dc1528")] {
+        let mut out = vec![0u8; h.digest_size()];
+        h.init().unwrap();
+        for _ in 0..1000 {
+            h.update(&[b'a'; 1000]).unwrap();
+        }
+        h.finish(&mut out).unwrap();
+        assert_eq!(hex::encode(out), md);
+    }
+}
+
+#[test]
+fn bitcoin_hash160() {
+    /* RIPEMD-160 of SHA-256 of the compressed public key of private key 1. */
+    let pubkey = hex::decode("0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798").unwrap();
+    let (mut sha, mut digest, mut hash160) = (SHA::new_sha256(), [0u8; 32], [0u8; 20]);
+    sha.init().unwrap();
+    sha.update(&pubkey).unwrap();
+    sha.finish(&mut digest).unwrap();
+
+    let mut ripemd = LegacyHash::new_ripemd160();
+    ripemd.init().unwrap();
+    ripemd.update(&digest).unwrap();
+    ripemd.finish(&mut hash160).unwrap();
+    assert_eq!(hex::encode(hash160), "751e76e8199196d454941c45d1b3a323f1433bd6");
+
+    /* A shorter output buffer truncates. */
+    let mut short = [0u8; 8];
+    ripemd.init().unwrap();
+    ripemd.update(&digest).unwrap();
+    ripemd.finish(&mut short).unwrap();
+    assert_eq!(short, hash160[..8]);
+    assert_eq!((ripemd.digest_size(), ripemd.block_size()), (20, 64));
+}
@@ -0,0 +1,282 @@
+use rucola::cipher::{AES128, AES192, AES256, AES_BLOCKSIZE, Direction, Padding, ECB, CBC, CFB8, CFB128, OFB, CTR};
+use rucola::common::api::{BlockCipher, CipherStreamingAPI, IVInit, InputOutputUpdate};
+use rucola::common::Error;
+use utilities::testutils::RspVector;
+
+fn new_mode<C: BlockCipher<AES_BLOCKSIZE> + 'static>(
+    mode: &str,
+    key: &[u8],
+    direction: Direction,
+    padding: Padding,
+) -> Box<dyn CipherStreamingAPI> {
+    match mode {
+        "ECB" => Box::new(ECB::<C>::new(key, direction, padding).unwrap()),
+        "CBC" => Box::new(CBC::<C>::new(key, direction, padding).unwrap()),
+        "CFB8" => Box::new(CFB8::<C>::new(key, direction).unwrap()),
+        "CFB128" => Box::new(CFB128::<C>::new(key, direction).unwrap()),
+        "OFB" => Box::new(OFB::<C>::new(key).unwrap()),
+        "CTR" => Box::new(CTR::<C>::new(key).unwrap()),
+        _ => panic!("unexpected mode"),
+    }
+}
+
+fn mode_by_name(mode: &str, key: &[u8], direction: Direction, padding: Padding) -> Box<dyn CipherStreamingAPI> {
+    match key.len() {
+        16 => new_mode::<AES128>(mode, key, direction, padding),
+        24 => new_mode::<AES192>(mode, key, direction, padding),
+        _ => new_mode::<AES256>(mode, key, direction, padding),
+    }
+}
+
+fn direction(t: &RspVector) -> Direction {
+    if t.params.contains_key("ENCRYPT") {
+        Direction::Encrypt
+    } else {
+        Direction::Decrypt
+    }
+}
+
+fn io_names(direction: Direction) -> (&'static str, &'static str) {
+    match direction {
+        Direction::Encrypt => ("PLAINTEXT", "CIPHERTEXT"),
+        Direction::Decrypt => ("CIPHERTEXT", "PLAINTEXT"),
+    }
+}
+
+/* Runs a whole message through `ctx`, split into updates of varying sizes. */
+fn process(ctx: &mut dyn CipherStreamingAPI, iv: &[u8], input: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut output = vec![0u8; input.len() + AES_BLOCKSIZE];
+    let mut written = 0;
+    let mut pos = 0;
+
+    ctx.init(iv)?;
+    for step in [1, 7, 16, 33, 5].iter().cycle() {
+        if pos == input.len() {
+            break;
+        }
+        let end = usize::min(pos + step, input.len());
+        written += ctx.update(&input[pos..end], &mut output[written..])?;
+        pos = end;
+    }
+    written += ctx.finish(&mut output[written..])?;
+
+    output.truncate(written);
+    Ok(output)
+}
+
+fn kat(mode: &str, t: &RspVector) {
+    let dir = direction(t);
+    let (input, output) = io_names(dir);
+    let iv = if t.has("IV") { t.hex("IV") } else { vec![] };
+
+    let mut ctx = mode_by_name(mode, &t.hex("KEY"), dir, Padding::None);
+    assert_eq!(process(ctx.as_mut(), &iv, &t.hex(input)).unwrap(), t.hex(output));
+}
+
+/* The NIST files in tests/tv, where the tree has them, and the generated ones. */
+fn rsp_files(name: &str) -> Vec<String> {
+    [format!("./tests/tv/{}.rsp", name), format!("./tests/tv/generated/{}.rsp", name)]
+        .into_iter()
+        .filter(|f| std::path::Path::new(f).exists())
+        .collect()
+}
+
+fn mmt(mode: &str) {
+    for keysize in [128, 192, 256] {
+        let files = rsp_files(&format!("{}MMT{}", mode, keysize));
+        let tv = utilities::testutils::parse_rsp_vectors(&files.iter().map(String::as_str).collect::<Vec<_>>());
+        assert!(!tv.is_empty());
+
+        for t in tv {
+            kat(mode, &t);
+        }
+    }
+}
+
+/* AESAVS Monte Carlo test of the feedback modes. The input of each of the 1000 steps
+ * is the output from two steps before, the IV respectively the IV bytes for the
+ * first steps. Only the first `counts` COUNTs of each direction are run. */
+fn mct(mode: &str, counts: usize) {
+    for keysize in [128, 192, 256] {
+        let files = rsp_files(&format!("{}MCT{}", mode, keysize));
+        let tv = utilities::testutils::parse_rsp_vectors(&files.iter().map(String::as_str).collect::<Vec<_>>());
+        assert!(!tv.is_empty());
+
+        for t in tv.iter().filter(|t| t.get("COUNT").parse::<usize>().unwrap() < counts) {
+            let dir = direction(t);
+            let (input, output) = io_names(dir);
+            let iv = t.hex("IV");
+            let seg = t.hex(input).len();
+
+            let mut ctx = mode_by_name(mode, &t.hex("KEY"), dir, Padding::None);
+            ctx.init(&iv).unwrap();
+
+            let mut outputs: Vec<Vec<u8>> = Vec::new();
+            let mut x = t.hex(input);
+            for j in 0..1000 {
+                let mut y = vec![0u8; seg];
+                assert_eq!(ctx.update(&x, &mut y), Ok(seg));
+                outputs.push(y);
+
+                x = if seg == 1 {
+                    if j < 16 { vec![iv[j]] } else { outputs[j - 16].clone() }
+                } else if j == 0 {
+                    iv.clone()
+                } else {
+                    outputs[j - 1].clone()
+                };
+            }
+            assert_eq!(outputs[999], t.hex(output));
+        }
+    }
+}
+
+#[test]
+fn sp800_38a() {
+    let tv = utilities::testutils::parse_rsp_vectors(&["./tests/tv/SP800-38A.rsp"]);
+    assert_eq!(tv.len(), 36);
+
+    for t in tv {
+        let mode = ["ECB", "CBC", "CFB8", "CFB128", "OFB", "CTR"]
+            .into_iter()
+            .find(|m| t.params.contains_key(*m))
+            .unwrap();
+        kat(mode, &t);
+    }
+}
+
+#[test]
+fn ecb() {
+    mmt("ECB");
+}
+
+#[test]
+fn cbc() {
+    mmt("CBC");
+    mct("CBC", 10);
+}
+
+#[test]
+fn cfb8() {
+    mmt("CFB8");
+    mct("CFB8", 10);
+}
+
+#[test]
+fn cfb128() {
+    mmt("CFB128");
+    mct("CFB128", 10);
+}
+
+#[test]
+fn ofb() {
+    mmt("OFB");
+    mct("OFB", 10);
+}
+
+/* All 100 COUNTs of the Monte Carlo tests, the unit tests above run the first ten.
+ * Takes too long for debug builds, run with
+ * `cargo test --release --test modes -- --ignored`. */
+#[test]
+#[ignore]
+fn mct_all_counts() {
+    for mode in ["CBC", "CFB8", "CFB128", "OFB"] {
+        mct(mode, 100);
+    }
+}
+
+#[test]
+fn pkcs7() {
+    let key = [0x42u8; 16];
+    let iv = [0x24u8; 16];
+    let msg: Vec<u8> = (0..64).collect();
+
+    for mode in ["ECB", "CBC"] {
+        let iv: &[u8] = if mode == "ECB" { &[] } else { &iv };
+
+        for len in 0..msg.len() {
+            /* Padding is a full block for block aligned messages. */
+            let pad = AES_BLOCKSIZE - len % AES_BLOCKSIZE;
+            let mut padded = msg[..len].to_vec();
+            padded.resize(len + pad, pad as u8);
+
+            let mut enc = mode_by_name(mode, &key, Direction::Encrypt, Padding::PKCS7);
+            let ct = process(enc.as_mut(), iv, &msg[..len]).unwrap();
+            let mut raw = mode_by_name(mode, &key, Direction::Encrypt, Padding::None);
+            assert_eq!(ct, process(raw.as_mut(), iv, &padded).unwrap());
+
+            let mut dec = mode_by_name(mode, &key, Direction::Decrypt, Padding::PKCS7);
+            assert_eq!(process(dec.as_mut(), iv, &ct).unwrap(), msg[..len]);
+        }
+
+        /* Decryption holds back the last full block until finish. */
+        let mut enc = mode_by_name(mode, &key, Direction::Encrypt, Padding::PKCS7);
+        let ct = process(enc.as_mut(), iv, &msg[..32]).unwrap();
+        let mut dec = mode_by_name(mode, &key, Direction::Decrypt, Padding::PKCS7);
+        let mut out = [0u8; 48];
+        dec.init(iv).unwrap();
+        assert_eq!(dec.update(&ct[..32], &mut out), Ok(16));
+        assert_eq!(dec.update(&ct[32..], &mut out[16..]), Ok(16));
+        assert_eq!(dec.finish(&mut out[32..]), Ok(0));
+        assert_eq!(out[..32], msg[..32]);
+
+        /* Invalid padding: wrong pad bytes, pad length zero or larger than a block. */
+        for last in [[1u8, 2], [0, 0], [17, 17]] {
+            let mut padded = msg[..30].to_vec();
+            padded.extend_from_slice(&last);
+            let mut raw = mode_by_name(mode, &key, Direction::Encrypt, Padding::None);
+            let ct = process(raw.as_mut(), iv, &padded).unwrap();
+
+            let mut dec = mode_by_name(mode, &key, Direction::Decrypt, Padding::PKCS7);
+            assert_eq!(process(dec.as_mut(), iv, &ct), Err(Error::Err));
+        }
+
+        /* Ciphertext that is not a multiple of the block size. */
+        let mut dec = mode_by_name(mode, &key, Direction::Decrypt, Padding::PKCS7);
+        assert_eq!(process(dec.as_mut(), iv, &ct[..31]), Err(Error::Err));
+    }
+}
+
+#[test]
+fn mode_errors() {
+    let key = [0u8; 16];
+    let mut out = [0u8; 32];
+
+    for mode in ["ECB", "CBC", "CFB8", "CFB128", "OFB", "CTR"] {
+        let mut ctx = mode_by_name(mode, &key, Direction::Encrypt, Padding::None);
+        assert_eq!(ctx.update(&[0; 16], &mut out), Err(Error::Err));
+        assert_eq!(ctx.finish(&mut out), Err(Error::Err));
+        assert_eq!(ctx.init(&[0; 15]), Err(Error::Err));
+    }
+
+    /* Without padding a partial block is an error, as is too little output space. */
+    let mut ecb = ECB::<AES128>::new(&key, Direction::Encrypt, Padding::None).unwrap();
+    assert_eq!(process(&mut ecb, &[], &[0; 17]), Err(Error::Err));
+    let mut cbc = CBC::<AES128>::new(&key, Direction::Encrypt, Padding::None).unwrap();
+    assert!(process(&mut cbc, &[0; 16], &[0; 32]).is_ok());
+    cbc.init(&[0; 16]).unwrap();
+    assert_eq!(cbc.update(&[0; 32], &mut out[..31]), Err(Error::Err));
+    let mut ctr = CTR::<AES128>::new(&key).unwrap();
+    ctr.init(&[0; 16]).unwrap();
+    assert_eq!(ctr.update(&[0; 32], &mut out[..31]), Err(Error::Err));
+
+    assert!(CBC::<AES256>::new(&key, Direction::Encrypt, Padding::None).is_err());
+}
+
+#[test]
+fn ctr_counter_wraps() {
+    let key = [7u8; 16];
+    let mut ks = [0u8; 32];
+
+    let mut ctr = CTR::<AES128>::new(&key).unwrap();
+    ctr.init(&[0xff; 16]).unwrap();
+    ctr.update(&[0; 32], &mut ks).unwrap();
+
+    let aes = AES128::new(&key).unwrap();
+    let mut blocks = [[0xffu8; 16], [0; 16]];
+    for b in blocks.iter_mut() {
+        aes.encrypt_block(b);
+    }
+    assert_eq!(ks[..16], blocks[0]);
+    assert_eq!(ks[16..], blocks[1]);
+}
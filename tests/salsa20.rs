@@ -0,0 +1,122 @@
+use rucola::cipher::{Salsa20, XSalsa20, SecretBox, hsalsa20, SALSA_BLOCKSIZE, SECRETBOX_MAC_SIZE};
+use rucola::common::api::{CipherStreamingAPI, IVInit, InputOutputUpdate};
+use rucola::common::{Success, Error};
+use utilities::testutils::RspVector;
+
+fn run<C: CipherStreamingAPI>(c: &mut C, seek: fn(&mut C, u64) -> Result<Success, Error>, t: &RspVector) {
+    let (nonce, pt, ct) = (t.hex("Nonce"), t.hex("PT"), t.hex("CT"));
+    let pos = t.get("Counter").parse::<u64>().unwrap() * SALSA_BLOCKSIZE as u64;
+    let mut out = vec![0u8; pt.len()];
+
+    c.init(&nonce).unwrap();
+    seek(c, pos).unwrap();
+    assert_eq!(c.update(&pt, &mut out), Ok(pt.len()));
+    assert_eq!(c.finish(&mut []), Ok(0));
+    assert_eq!(ct, out);
+
+    /* Decrypt from an odd position on. */
+    let split = pt.len() / 2 + 3;
+    if split < pt.len() {
+        c.init(&nonce).unwrap();
+        seek(c, pos + split as u64).unwrap();
+        c.update(&ct[split..], &mut out[split..]).unwrap();
+        c.finish(&mut []).unwrap();
+        assert_eq!(pt[split..], out[split..]);
+    }
+}
+
+#[test]
+fn salsa20_vectors() {
+    let tv = utilities::testutils::parse_rsp_vectors(&["./tests/tv/salsa20.rsp"]);
+    assert!(!tv.is_empty());
+
+    for t in tv.iter() {
+        let key = t.hex("Key");
+        if t.params.contains_key("SALSA20") {
+            run(&mut Salsa20::new(&key).unwrap(), Salsa20::seek, t);
+        } else {
+            run(&mut XSalsa20::new(&key).unwrap(), XSalsa20::seek, t);
+        }
+    }
+}
+
+/* NaCl tests/core1.c and core2.c: the crypto_box shared key from the X25519 secret
+ * and the secretbox subkey. */
+#[test]
+fn hsalsa20_nacl() {
+    let shared = hex::decode("4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742").unwrap();
+    let first = hsalsa20(shared[..].try_into().unwrap(), &[0; 16]);
+    assert_eq!(hex::encode(first), "1b27556473e985d462cd51197a9a46c76009549eac6474f206c4ee0844f68389");
+
+    let nonce = hex::decode("69696ee955b62b73cd62bda875fc73d6").unwrap();
+    let second = hsalsa20(&first, nonce[..].try_into().unwrap());
+    assert_eq!(hex::encode(second), "dc908dda0b9344a953629b733820778880f3ceb421bb61b91cbd4c3e66256ce4");
+}
+
+#[test]
+fn salsa20_errors() {
+    let mut out = [0u8; 16];
+    assert!(Salsa20::new(&[0u8; 16]).is_err());
+    assert!(XSalsa20::new(&[0u8; 24]).is_err());
+
+    let mut s = Salsa20::new(&[0u8; 32]).unwrap();
+    assert_eq!(s.update(&[0u8; 16], &mut out), Err(Error::Err));
+    assert_eq!(s.seek(0), Err(Error::Err));
+    assert_eq!(s.init(&[0u8; 12]), Err(Error::Err));
+    s.init(&[0u8; 8]).unwrap();
+    assert_eq!(s.update(&[0u8; 17], &mut out), Err(Error::Err));
+
+    /* Any byte position is within the 64-bit block counter. */
+    s.seek(u64::MAX).unwrap();
+    assert_eq!(s.update(&[0u8; 16], &mut out), Ok(16));
+
+    let mut x = XSalsa20::new(&[0u8; 32]).unwrap();
+    assert_eq!(x.init(&[0u8; 8]), Err(Error::Err));
+}
+
+#[test]
+fn secretbox() {
+    let tv = utilities::testutils::parse_rsp_vectors(&["./tests/tv/secretbox.rsp"]);
+    assert!(!tv.is_empty());
+
+    for t in tv {
+        let sb = SecretBox::new(&t.hex("Key")).unwrap();
+        let (nonce, pt, ct) = (t.hex("Nonce"), t.hex("PT"), t.hex("CT"));
+        let mut out = vec![0u8; ct.len()];
+
+        assert_eq!(sb.seal(&nonce, &pt, &mut out), Ok(ct.len()));
+        assert_eq!(ct, out);
+        assert_eq!(sb.open(&nonce, &ct, &mut out), Ok(pt.len()));
+        assert_eq!(pt, out[..pt.len()]);
+
+        let (mac, c) = ct.split_at(SECRETBOX_MAC_SIZE);
+        let mut buffer = pt.clone();
+        let mut tag = [0u8; SECRETBOX_MAC_SIZE];
+        sb.seal_in_place(&nonce, &mut buffer, &mut tag).unwrap();
+        assert_eq!((&buffer[..], &tag[..]), (c, mac));
+        sb.open_in_place(&nonce, &mut buffer, &tag).unwrap();
+        assert_eq!(buffer, pt);
+
+        let mut forged = ct.clone();
+        let last = forged.len() - 1;
+        forged[last] ^= 0x80;
+        let mut out = vec![0xa5u8; pt.len()];
+        assert_eq!(sb.open(&nonce, &forged, &mut out), Err(Error::AuthFailed));
+        assert!(out.iter().all(|&b| b == 0xa5));
+    }
+}
+
+#[test]
+fn secretbox_errors() {
+    let sb = SecretBox::new(&[1u8; 32]).unwrap();
+    let mut out = [0u8; 64];
+    let mut mac = [0u8; 16];
+
+    assert!(SecretBox::new(&[1u8; 16]).is_err());
+    assert_eq!(sb.seal(&[0u8; 12], b"msg", &mut out), Err(Error::Err));
+    assert_eq!(sb.seal(&[0u8; 24], &[0u8; 49], &mut out), Err(Error::Err));
+    assert_eq!(sb.open(&[0u8; 24], &[0u8; 15], &mut out), Err(Error::Err));
+    assert_eq!(sb.seal_detached(&[0u8; 24], &[0u8; 8], &mut out[..7], &mut mac), Err(Error::Err));
+    assert_eq!(sb.seal_in_place(&[0u8; 24], &mut out, &mut mac[..8]), Err(Error::Err));
+    assert_eq!(sb.open_in_place(&[0u8; 24], &mut out, &mac[..8]), Err(Error::Err));
+}
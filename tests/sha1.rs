@@ -1,8 +1,119 @@
-use rucola::hash::SHA;
+use rucola::hash::{SHA, SHA1Mode};
+use rucola::common::api::{DefaultInit, SingleInputUpdate, SingleOutputFinish};
+use rucola::common::Error;
+use rand::Rng;
 
 #[test]
 fn streaming_api_test() {
     let tv = utilities::testutils::parse_hash_vectors(&["./tests/tv/SHA1LongMsg.rsp",
                                         "./tests/tv/SHA1ShortMsg.rsp"]);
     utilities::testutils::streaming_api_test::<20, SHA>(tv, &mut SHA::new_sha1());
-}
\ No newline at end of file
+}
+
+#[test]
+fn sha1_many() {
+    let tv = utilities::testutils::parse_hash_vectors(&["./tests/tv/SHA1LongMsg.rsp",
+                                        "./tests/tv/SHA1ShortMsg.rsp"]);
+    let inputs: Vec<&[u8]> = tv.iter().map(|t| &t.0[..]).collect();
+    let mut outputs = vec![0u8; 20 * tv.len()];
+    SHA::sha1_many(&inputs, &mut outputs).unwrap();
+
+    for (t, out) in tv.iter().zip(outputs.chunks(20)) {
+        assert_eq!(t.1, out);
+    }
+
+    /* The lanes (Scalar) and every other backend of the host. */
+    let mut rng = rand::thread_rng();
+    let msgs: Vec<Vec<u8>> = (0..150).map(|i| (0..(i * 53 % 150)).map(|_| rng.gen()).collect()).collect();
+    let inputs: Vec<&[u8]> = msgs.iter().map(|m| &m[..]).collect();
+    let mut outputs = vec![0u8; 20 * msgs.len()];
+
+    for backend in utilities::testutils::sha_backends() {
+        outputs.fill(0);
+        SHA::sha1_many_with_backend(backend, &inputs, &mut outputs).unwrap();
+
+        let mut sha = SHA::new_sha1();
+        let mut md = [0u8; 20];
+        for (m, out) in msgs.iter().zip(outputs.chunks(20)) {
+            sha.init().unwrap();
+            sha.update(m).unwrap();
+            sha.finish(&mut md).unwrap();
+            assert_eq!(md, out);
+        }
+    }
+}
+
+#[test]
+fn backends() {
+    let tv = utilities::testutils::parse_hash_vectors(&["./tests/tv/SHA1LongMsg.rsp",
+                                        "./tests/tv/SHA1ShortMsg.rsp"]);
+    utilities::testutils::sha_backend_test::<20>(SHA::new_sha1, &tv);
+}
+
+#[test]
+fn hardened_without_attack() {
+    /* The hardened modes compute plain SHA-1 for ordinary messages. */
+    let tv = utilities::testutils::parse_hash_vectors(&["./tests/tv/SHA1ShortMsg.rsp"]);
+    for mode in [SHA1Mode::DetectCollisions, SHA1Mode::SafeHash] {
+        utilities::testutils::streaming_api_test::<20, SHA>(tv.clone(), &mut SHA::new_sha1_with_mode(mode));
+    }
+}
+
+#[test]
+fn collision_detection() {
+    let tv = utilities::testutils::parse_rsp_vectors(&["./tests/tv/SHA1CD.rsp"]);
+    assert_eq!(tv.len(), 11);
+
+    for t in tv {
+        let msg = t.hex("Msg");
+        let attacked = t.get("Collision") == "1";
+
+        for (mode, md) in [(SHA1Mode::Standard, t.hex("MD")),
+                           (SHA1Mode::DetectCollisions, t.hex("MD")),
+                           (SHA1Mode::SafeHash, t.hex("SafeMD"))] {
+            let mut sha = SHA::new_sha1_with_mode(mode);
+            let mut out = [0u8; 20];
+            sha.init().unwrap();
+            for chunk in msg.chunks(100) {
+                sha.update(chunk).unwrap();
+            }
+            let res = sha.finish(&mut out);
+            assert_eq!(out[..], md);
+
+            match (mode, attacked) {
+                (SHA1Mode::Standard, _) | (_, false) => assert!(res.is_ok()),
+                _ => assert_eq!(res, Err(Error::CollisionDetected)),
+            }
+
+            if let SHA::SHA1(ctx) = &sha {
+                assert_eq!(ctx.collision(), mode != SHA1Mode::Standard && attacked);
+            }
+
+            /* The next message starts without the finding. */
+            sha.init().unwrap();
+            sha.update(b"abc").unwrap();
+            assert!(sha.finish(&mut out).is_ok());
+        }
+    }
+}
+
+#[test]
+fn set_mode() {
+    let mut sha = SHA::new_sha1();
+    let SHA::SHA1(ctx) = &mut sha else { unreachable!() };
+    assert_eq!(ctx.mode(), SHA1Mode::Standard);
+
+    /* Only between `init` and the first input. */
+    sha.init().unwrap();
+    let SHA::SHA1(ctx) = &mut sha else { unreachable!() };
+    ctx.set_mode(SHA1Mode::SafeHash).unwrap();
+    sha.update(b"abc").unwrap();
+    let SHA::SHA1(ctx) = &mut sha else { unreachable!() };
+    assert_eq!(ctx.set_mode(SHA1Mode::Standard), Err(Error::Err));
+    assert_eq!(ctx.mode(), SHA1Mode::SafeHash);
+    assert!(!ctx.collision());
+
+    sha.init().unwrap();
+    let SHA::SHA1(ctx) = &mut sha else { unreachable!() };
+    ctx.set_mode(SHA1Mode::Standard).unwrap();
+}
@@ -1,4 +1,6 @@
-use rucola::hash::SHA;
+use rucola::hash::{SHA, SHABackend};
+use rucola::common::api::{DefaultInit, SingleInputUpdate, SingleOutputFinish};
+use rand::Rng;
 
 #[test]
 fn streaming_api_256() {
@@ -28,3 +30,66 @@ fn streaming_api_384() {
                                         "./tests/tv/SHA384ShortMsg.rsp"]);
     utilities::testutils::streaming_api_test::<48, SHA>(tv, &mut SHA::new_sha384());
 }
+
+#[test]
+fn sha256_many() {
+    let tv = utilities::testutils::parse_hash_vectors(&["./tests/tv/SHA256LongMsg.rsp",
+                                        "./tests/tv/SHA256ShortMsg.rsp"]);
+    let inputs: Vec<&[u8]> = tv.iter().map(|t| &t.0[..]).collect();
+    let mut outputs = vec![0u8; 32 * tv.len()];
+    SHA::sha256_many(&inputs, &mut outputs).unwrap();
+
+    for (t, out) in tv.iter().zip(outputs.chunks(32)) {
+        assert_eq!(t.1, out);
+    }
+}
+
+#[test]
+fn sha256_many_matches_scalar() {
+    /* Lengths around the block and padding boundaries in random order, so lanes
+     * finish at different times and take over new messages. */
+    let mut rng = rand::thread_rng();
+    let msgs: Vec<Vec<u8>> = (0..203)
+        .map(|i| (0..(i * 37 % 203)).map(|_| rng.gen()).collect())
+        .collect();
+
+    /* The lanes are the Scalar backend, they run on every host. */
+    for (backend, n) in utilities::testutils::sha_backends().into_iter().flat_map(|b| [0, 1, 7, 8, 9, msgs.len()].map(|n| (b, n))) {
+        let inputs: Vec<&[u8]> = msgs[..n].iter().map(|m| &m[..]).collect();
+        let mut outputs = vec![0u8; 32 * n];
+        SHA::sha256_many_with_backend(backend, &inputs, &mut outputs).unwrap();
+
+        let mut sha = SHA::new_sha256();
+        let mut md = [0u8; 32];
+        for (m, out) in inputs.iter().zip(outputs.chunks(32)) {
+            sha.init().unwrap();
+            sha.update(m).unwrap();
+            sha.finish(&mut md).unwrap();
+            assert_eq!(md, out);
+        }
+    }
+
+    /* One digest per message. */
+    assert!(SHA::sha256_many(&[b"abc"], &mut [0u8; 31]).is_err());
+    assert!(SHA::sha256_many(&[b"abc"], &mut [0u8; 64]).is_err());
+    assert!(SHA::sha256_many_with_backend(SHABackend::Scalar, &[b"abc"], &mut [0u8; 31]).is_err());
+}
+
+#[test]
+fn backends_256() {
+    let tv256 = utilities::testutils::parse_hash_vectors(&["./tests/tv/SHA256LongMsg.rsp",
+                                        "./tests/tv/SHA256ShortMsg.rsp"]);
+    let tv224 = utilities::testutils::parse_hash_vectors(&["./tests/tv/SHA224LongMsg.rsp",
+                                        "./tests/tv/SHA224ShortMsg.rsp"]);
+    utilities::testutils::sha_backend_test::<32>(SHA::new_sha256, &tv256);
+    utilities::testutils::sha_backend_test::<28>(SHA::new_sha224, &tv224);
+
+    for backend in [SHABackend::SHANI, SHABackend::ARMv8].into_iter().filter(|b| !b.is_supported()) {
+        assert!(SHA::sha256_many_with_backend(backend, &[b"abc"], &mut [0u8; 32]).is_err());
+    }
+
+    let mut sha512 = SHA::new_sha512();
+    assert!(sha512.set_backend(SHABackend::Scalar).is_ok());
+    assert!(sha512.set_backend(SHABackend::SHANI).is_err());
+    assert_eq!(sha512.backend(), SHABackend::Scalar);
+}
@@ -0,0 +1,141 @@
+use rucola::cipher::{AES128, AES192, AES256, CCM, GCM, GCMSIV, SIV, SIV_TAG_SIZE, ChaCha20Poly1305, XChaCha20Poly1305};
+use rucola::common::api::{AEAD, BlockCipher};
+use rucola::common::Error;
+use rucola::hash::{SHA, BLAKE2, BLAKE2Params};
+use rucola::mac::{CMAC, HMAC};
+use utilities::testutils::RspVector;
+
+fn siv_vector<C: BlockCipher<16> + Clone>(t: &RspVector) {
+    let siv = SIV::<C>::new(&t.hex("Key")).unwrap();
+    let ad = t.hex_all("AD");
+    let ad: Vec<&[u8]> = ad.iter().map(|a| a.as_slice()).collect();
+    let (pt, ct) = (t.hex("PT"), t.hex("CT"));
+
+    let mut out = vec![0u8; ct.len()];
+    assert_eq!(siv.encrypt_with_ad(&ad, &pt, &mut out), Ok(ct.len()));
+    assert_eq!(out, ct);
+
+    let mut pt_out = vec![0u8; pt.len()];
+    assert_eq!(siv.decrypt_with_ad(&ad, &ct, &mut pt_out), Ok(pt.len()));
+    assert_eq!(pt_out, pt);
+
+    /* Any change of V, the ciphertext or an AD component is detected. */
+    for pos in [0, ct.len() - 1] {
+        let mut bad = ct.clone();
+        bad[pos] ^= 1;
+        assert_eq!(siv.decrypt_with_ad(&ad, &bad, &mut pt_out), Err(Error::AuthFailed));
+        assert!(pt_out.iter().all(|b| *b == 0));
+    }
+    if !ad.is_empty() {
+        assert_eq!(siv.decrypt_with_ad(&ad[1..], &ct, &mut pt_out), Err(Error::AuthFailed));
+    }
+}
+
+#[test]
+fn siv_rfc5297() {
+    let tv = utilities::testutils::parse_rsp_vectors(&["./tests/tv/SIV.rsp"]);
+    assert!(!tv.is_empty());
+
+    for t in tv.iter() {
+        match t.param("Keylen").unwrap() {
+            "256" => siv_vector::<AES128>(t),
+            "384" => siv_vector::<AES192>(t),
+            _ => siv_vector::<AES256>(t),
+        }
+    }
+}
+
+#[test]
+fn siv_components() {
+    let siv = SIV::<AES128>::new(&[0x42u8; 32]).unwrap();
+    let mut out = [0u8; 48];
+    let mut pt = [0u8; 32];
+
+    /* An empty plaintext still gets a synthetic IV. */
+    assert_eq!(siv.encrypt_with_ad(&[b"header"], &[], &mut out), Ok(SIV_TAG_SIZE));
+    assert_eq!(siv.decrypt_with_ad(&[b"header"], &out[..16], &mut pt), Ok(0));
+    assert_eq!(siv.decrypt_with_ad(&[b"headeR"], &out[..16], &mut pt), Err(Error::AuthFailed));
+
+    /* The AEAD interface passes the associated data and the nonce as two components. */
+    let msg = [7u8; 32];
+    let mut expected = [0u8; 48];
+    siv.encrypt_with_ad(&[b"aad", b"nonce"], &msg, &mut expected).unwrap();
+    assert_eq!(AEAD::seal(&siv, b"nonce", b"aad", &msg, &mut out), Ok(48));
+    assert_eq!(out, expected);
+    assert_eq!(AEAD::open(&siv, b"nonce", b"aad", &out, &mut pt), Ok(32));
+    assert_eq!(pt, msg);
+
+    /* Component boundaries matter, as does the order. */
+    siv.encrypt_with_ad(&[b"aadnonce"], &msg, &mut out).unwrap();
+    assert_ne!(out, expected);
+    siv.encrypt_with_ad(&[b"nonce", b"aad"], &msg, &mut out).unwrap();
+    assert_ne!(out, expected);
+
+    /* At most 126 AD components, output and input sizes. */
+    let many: Vec<&[u8]> = vec![b"x"; 127];
+    assert!(siv.encrypt_with_ad(&many[..126], &msg, &mut out).is_ok());
+    assert_eq!(siv.encrypt_with_ad(&many, &msg, &mut out), Err(Error::Err));
+    assert_eq!(siv.encrypt_with_ad(&[], &msg, &mut out[..47]), Err(Error::Err));
+    assert_eq!(siv.decrypt_with_ad(&[], &out[..15], &mut pt), Err(Error::Err));
+    assert_eq!(siv.decrypt_with_ad(&[], &out, &mut pt[..31]), Err(Error::Err));
+
+    /* The key holds the CMAC and the CTR key. */
+    assert!(SIV::<AES128>::new(&[0u8; 16]).is_err());
+    assert!(SIV::<AES256>::new(&[0u8; 32]).is_err());
+    assert!(SIV::<AES256>::new(&[0u8; 64]).is_ok());
+}
+
+#[test]
+fn siv_with_mac() {
+    let key: Vec<u8> = (0..32).collect();
+    let msg = [7u8; 40];
+    let mut expected = [0u8; 56];
+    let mut out = [0u8; 56];
+    SIV::<AES128>::new(&key).unwrap().encrypt_with_ad(&[b"aad"], &msg, &mut expected).unwrap();
+
+    /* S2V over any MAC with 128-bit output, CMAC gives AES-SIV. */
+    let siv = SIV::<AES128, _>::with_mac(CMAC::<AES128>::new(&key[..16]).unwrap(), &key[16..]).unwrap();
+    siv.encrypt_with_ad(&[b"aad"], &msg, &mut out).unwrap();
+    assert_eq!(out, expected);
+
+    let mac = BLAKE2::blake2s_with_params(&BLAKE2Params { key: &key[..16], ..BLAKE2Params::new(16) }).unwrap();
+    let siv = SIV::<AES128, _>::with_mac(mac, &key[16..]).unwrap();
+    let mut pt = [0u8; 40];
+    siv.encrypt_with_ad(&[b"aad"], &msg, &mut out).unwrap();
+    assert_ne!(out, expected);
+    assert_eq!(siv.decrypt_with_ad(&[b"aad"], &out, &mut pt), Ok(40));
+    assert_eq!(pt, msg);
+
+    assert!(SIV::<AES128, _>::with_mac(HMAC::new(SHA::new_sha256(), &key).unwrap(), &key[16..]).is_err());
+}
+
+/* The same code runs on every AEAD, selected by type parameter. */
+fn seal_open<A: AEAD>(aead: &A, nonce: &[u8]) {
+    let msg: Vec<u8> = (0..77).collect();
+    let tag_len = aead.tag_len();
+    let mut sealed = vec![0u8; msg.len() + tag_len];
+    let mut opened = vec![0u8; msg.len()];
+
+    assert_eq!(aead.seal(nonce, b"aad", &msg, &mut sealed), Ok(msg.len() + tag_len));
+    assert_eq!(aead.open(nonce, b"aad", &sealed, &mut opened), Ok(msg.len()));
+    assert_eq!(opened, msg);
+
+    sealed[40] ^= 0x10;
+    assert_eq!(aead.open(nonce, b"aad", &sealed, &mut opened), Err(Error::AuthFailed));
+    sealed[40] ^= 0x10;
+    assert_eq!(aead.open(nonce, b"AAD", &sealed, &mut opened), Err(Error::AuthFailed));
+    assert_eq!(aead.seal(nonce, b"aad", &msg, &mut sealed[1..]), Err(Error::Err));
+}
+
+#[test]
+fn aead_trait() {
+    let key = [0x5au8; 32];
+    seal_open(&GCM::<AES256>::new(&key).unwrap(), &[1; 12]);
+    seal_open(&GCM::<AES128>::with_tag_len(&key[..16], 12).unwrap(), &[1; 12]);
+    seal_open(&CCM::<AES128>::with_lengths(&key[..16], 13, 8).unwrap(), &[1; 13]);
+    seal_open(&SIV::<AES128>::new(&key).unwrap(), &[1; 16]);
+    seal_open(&GCMSIV::<AES128>::new(&key[..16]).unwrap(), &[1; 12]);
+    seal_open(&GCMSIV::<AES256>::new(&key).unwrap(), &[1; 12]);
+    seal_open(&ChaCha20Poly1305::new(&key).unwrap(), &[1; 12]);
+    seal_open(&XChaCha20Poly1305::new(&key).unwrap(), &[1; 24]);
+}
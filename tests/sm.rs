@@ -0,0 +1,109 @@
+use rucola::cipher::{SM4, SM4_BLOCKSIZE, CBC, Direction, Padding};
+use rucola::common::api::{BlockCipher, DefaultInit, SingleInputUpdate, SingleOutputFinish, IVInit, InputOutputUpdate, OutputFinish};
+use rucola::hash::SHA;
+use rucola::mac::HMAC;
+use utilities::testutils::RspVector;
+
+#[test]
+fn sm3() {
+    let tv = utilities::testutils::parse_hash_vectors(&["./tests/tv/SM3.rsp"]);
+    assert_eq!(tv.len(), 16);
+    utilities::testutils::streaming_api_test::<32, SHA>(tv, &mut SHA::new_sm3());
+
+    let sm3 = SHA::new_sm3();
+    assert_eq!((sm3.digest_size(), sm3.block_size()), (32, 64));
+}
+
+#[test]
+fn hmac_sm3() {
+    let tv = utilities::testutils::parse_rsp_vectors(&["./tests/tv/SM3HMAC.rsp"]);
+    assert!(!tv.is_empty());
+
+    for t in tv {
+        let mut hmac = HMAC::new(SHA::new_sm3(), &t.hex("Key")).unwrap();
+        let mut out = [0u8; 32];
+        hmac.init().unwrap();
+        hmac.update(&t.hex("Msg")).unwrap();
+        hmac.finish(&mut out).unwrap();
+        assert_eq!(t.hex("Mac"), out);
+    }
+}
+
+fn block(t: &RspVector, name: &str) -> [u8; SM4_BLOCKSIZE] {
+    t.hex(name).try_into().unwrap()
+}
+
+#[test]
+fn sm4() {
+    let tv = utilities::testutils::parse_rsp_vectors(&["./tests/tv/SM4.rsp"]);
+    assert_eq!(tv.len(), 11);
+
+    for t in tv {
+        let sm4 = SM4::new(&t.hex("KEY")).unwrap();
+        let (pt, ct) = (block(&t, "PLAINTEXT"), block(&t, "CIPHERTEXT"));
+        let mut b = pt;
+        sm4.encrypt_block(&mut b);
+        assert_eq!(b, ct);
+        sm4.decrypt_block(&mut b);
+        assert_eq!(b, pt);
+    }
+}
+
+#[test]
+fn sm4_iterated() {
+    let tv = utilities::testutils::parse_rsp_vectors(&["./tests/tv/SM4MCT.rsp"]);
+    assert!(!tv.is_empty());
+
+    for t in tv {
+        let sm4 = SM4::new(&t.hex("KEY")).unwrap();
+        let mut b = block(&t, "PLAINTEXT");
+        for _ in 0..1000 {
+            sm4.encrypt_block(&mut b);
+        }
+        assert_eq!(b, block(&t, "CIPHERTEXT"));
+        for _ in 0..1000 {
+            sm4.decrypt_block(&mut b);
+        }
+        assert_eq!(b, block(&t, "PLAINTEXT"));
+    }
+}
+
+/* Example 2 of GB/T 32907-2016: the plaintext of example 1 encrypted 1000000 times.
+ * The table-free S-box makes this take minutes in a debug build, run it with
+ * `cargo test --release --test sm -- --ignored`. */
+#[test]
+#[ignore]
+fn sm4_example2() {
+    let key = hex::decode("0123456789abcdeffedcba9876543210").unwrap();
+    let sm4 = SM4::new(&key).unwrap();
+    let mut b: [u8; SM4_BLOCKSIZE] = key.try_into().unwrap();
+    for _ in 0..1000000 {
+        sm4.encrypt_block(&mut b);
+    }
+    assert_eq!(hex::encode(b), "595298c7c6fd271f0402f804c33d3f66");
+}
+
+#[test]
+fn sm4_modes() {
+    assert!(SM4::new(&[0u8; 15]).is_err());
+    assert!(SM4::new(&[0u8; 32]).is_err());
+
+    /* The block cipher trait makes SM4 available to the generic modes. */
+    let key = hex::decode("0123456789abcdeffedcba9876543210").unwrap();
+    let msg = b"SM4 in CBC mode with PKCS#7 padding";
+    let iv: Vec<u8> = (0..16).collect();
+    let expected = hex::decode("c028a6d8873f78aa4a8c2c7cf992b7f12badea47c6602d52b1be6b93ce9de4702e2d844fd70d2730dd06557fce972ddf").unwrap();
+    let (mut ct, mut pt) = ([0u8; 48], [0u8; 48]);
+
+    let mut enc = CBC::<SM4, SM4_BLOCKSIZE>::new(&key, Direction::Encrypt, Padding::PKCS7).unwrap();
+    enc.init(&iv).unwrap();
+    let n = enc.update(msg, &mut ct).unwrap();
+    let n = n + enc.finish(&mut ct[n..]).unwrap();
+    assert_eq!(ct[..n], expected);
+
+    let mut dec = CBC::<SM4, SM4_BLOCKSIZE>::new(&key, Direction::Decrypt, Padding::PKCS7).unwrap();
+    dec.init(&iv).unwrap();
+    let m = dec.update(&ct[..n], &mut pt).unwrap();
+    let m = m + dec.finish(&mut pt[m..]).unwrap();
+    assert_eq!(&pt[..m], msg);
+}
@@ -0,0 +1,164 @@
+use rucola::cipher::{AES128, AES256, CCM, GCM, GCMSIV, ChaCha20Poly1305, STREAMDecryptor, STREAMEncryptor, STREAM_NONCE_OVERHEAD};
+use rucola::common::api::AEAD;
+use rucola::common::Error;
+use utilities::testutils::RspVector;
+
+fn stream_vector<A: AEAD + Clone>(t: &RspVector) {
+    let aead = A::new(&t.hex("Key")).unwrap();
+    let (prefix, aad) = (t.hex("Prefix"), t.hex("AAD"));
+    let (pts, cts) = (t.hex_all("PT"), t.hex_all("CT"));
+    let last = pts.len() - 1;
+
+    let mut enc = STREAMEncryptor::new(aead.clone(), &prefix).unwrap();
+    for (i, (pt, ct)) in pts.iter().zip(cts.iter()).enumerate() {
+        let mut out = vec![0u8; ct.len()];
+        let written = match i == last {
+            true => enc.seal_last_segment(&aad, pt, &mut out),
+            false => enc.seal_segment(&aad, pt, &mut out),
+        };
+        assert_eq!(written, Ok(ct.len()));
+        assert_eq!(&out, ct);
+    }
+    assert_eq!(enc.seal_segment(&aad, &[], &mut [0u8; 16]), Err(Error::Err));
+
+    let mut dec = STREAMDecryptor::new(aead, &prefix).unwrap();
+    for (i, (pt, ct)) in pts.iter().zip(cts.iter()).enumerate() {
+        assert_eq!(dec.finish(), Err(Error::Truncated));
+        let mut out = vec![0u8; pt.len()];
+        let len = match i == last {
+            true => dec.open_last_segment(&aad, ct, &mut out),
+            false => dec.open_segment(&aad, ct, &mut out),
+        };
+        assert_eq!(len, Ok(pt.len()));
+        assert_eq!(&out, pt);
+    }
+    dec.finish().unwrap();
+}
+
+#[test]
+fn stream_vectors() {
+    let tv = utilities::testutils::parse_rsp_vectors(&["./tests/tv/stream.rsp"]);
+    assert!(!tv.is_empty());
+
+    for t in tv.iter() {
+        if t.params.contains_key("CHACHA20POLY1305") {
+            stream_vector::<ChaCha20Poly1305>(t);
+        } else {
+            stream_vector::<GCM<AES256>>(t);
+        }
+    }
+}
+
+/* Seals segments of 0, 1, ..., n - 1 bytes, the last one as final. */
+fn seal_stream<A: AEAD + Clone>(aead: &A, prefix: &[u8], n: usize) -> Vec<Vec<u8>> {
+    let mut enc = STREAMEncryptor::new(aead.clone(), prefix).unwrap();
+    (0..n).map(|i| {
+        let mut out = vec![0u8; i + aead.tag_len()];
+        let pt = vec![i as u8; i];
+        match i == n - 1 {
+            true => enc.seal_last_segment(b"", &pt, &mut out).unwrap(),
+            false => enc.seal_segment(b"", &pt, &mut out).unwrap(),
+        };
+        out
+    }).collect()
+}
+
+fn attacks<A: AEAD + Clone>() {
+    let aead = A::new(&vec![3u8; A::KEY_SIZE]).unwrap();
+    let prefix = vec![1u8; A::NONCE_SIZE - STREAM_NONCE_OVERHEAD];
+    let segments = seal_stream(&aead, &prefix, 4);
+    let dec = STREAMDecryptor::new(aead.clone(), &prefix).unwrap();
+    let mut out = vec![0xa5u8; 64];
+
+    /* With diagnosis turned off a segment at the wrong position is only not authentic. */
+    let mut d = dec.clone();
+    d.set_diagnose(false);
+    assert_eq!(d.open_segment(b"", &segments[1], &mut out), Err(Error::AuthFailed));
+    assert_eq!(d.open_last_segment(b"", &segments[0], &mut out), Err(Error::AuthFailed));
+    assert_eq!(d.open_segment(b"", &segments[3], &mut out), Err(Error::AuthFailed));
+    assert_eq!(d.finish(), Err(Error::Truncated));
+
+    /* Dropping the final segment, or all segments after the first. */
+    let mut d = dec.clone();
+    for s in &segments[..3] {
+        d.open_segment(b"", s, &mut out).unwrap();
+    }
+    assert_eq!(d.finish(), Err(Error::Truncated));
+
+    let mut d = dec.clone();
+    out.fill(0xa5);
+    assert_eq!(d.open_last_segment(b"", &segments[0], &mut out), Err(Error::Truncated));
+    assert!(out.iter().all(|&b| b == 0xa5 || b == 0));
+    assert_eq!(d.finish(), Err(Error::Truncated));
+
+    /* Swapping, dropping and duplicating segments in the middle. */
+    let mut d = dec.clone();
+    assert_eq!(d.open_segment(b"", &segments[1], &mut out), Err(Error::OutOfOrder));
+    assert!(out[..1].iter().all(|&b| b == 0));
+    d.open_segment(b"", &segments[0], &mut out).unwrap();
+    assert_eq!(d.open_segment(b"", &segments[0], &mut out), Err(Error::OutOfOrder));
+    assert_eq!(d.open_segment(b"", &segments[2], &mut out), Err(Error::OutOfOrder));
+    d.open_segment(b"", &segments[1], &mut out).unwrap();
+
+    /* The final segment early, where more are expected, and data after it. */
+    assert_eq!(d.open_segment(b"", &segments[3], &mut out), Err(Error::OutOfOrder));
+    d.open_segment(b"", &segments[2], &mut out).unwrap();
+    assert_eq!(d.open_segment(b"", &segments[3], &mut out), Err(Error::OutOfOrder));
+    d.open_last_segment(b"", &segments[3], &mut out).unwrap();
+    assert_eq!(d.open_segment(b"", &segments[0], &mut out), Err(Error::OutOfOrder));
+    d.finish().unwrap();
+
+    /* Segments of another stream or modified ones are just not authentic. */
+    let other = seal_stream(&aead, &vec![2u8; prefix.len()], 4);
+    let mut d = dec.clone();
+    assert_eq!(d.open_segment(b"", &other[0], &mut out), Err(Error::AuthFailed));
+    let mut bad = segments[0].clone();
+    bad[0] ^= 1;
+    assert_eq!(d.open_segment(b"", &bad, &mut out), Err(Error::AuthFailed));
+    assert_eq!(d.open_segment(b"aad", &segments[0], &mut out), Err(Error::AuthFailed));
+    d.open_segment(b"", &segments[0], &mut out).unwrap();
+}
+
+#[test]
+fn stream_attacks() {
+    attacks::<ChaCha20Poly1305>();
+    attacks::<GCM<AES128>>();
+    attacks::<GCMSIV<AES128>>();
+}
+
+/* The nonce prefix follows the nonce length of the instance, not A::NONCE_SIZE. */
+#[test]
+fn stream_ccm() {
+    let aead = CCM::<AES128>::with_lengths(&[5u8; 16], 13, 8).unwrap();
+    assert!(STREAMEncryptor::new(aead.clone(), &[0u8; CCM::<AES128>::NONCE_SIZE - STREAM_NONCE_OVERHEAD]).is_err());
+    assert!(STREAMDecryptor::new(aead.clone(), &[0u8; 9]).is_err());
+
+    let prefix = [4u8; 8];
+    let segments = seal_stream(&aead, &prefix, 4);
+    assert_eq!(segments[3].len(), 3 + 8);
+
+    let mut dec = STREAMDecryptor::new(aead.clone(), &prefix).unwrap();
+    let mut out = [0u8; 16];
+    assert_eq!(dec.open_segment(b"", &segments[1], &mut out), Err(Error::OutOfOrder));
+    for (i, s) in segments.iter().enumerate() {
+        let len = match i == 3 {
+            true => dec.open_last_segment(b"", s, &mut out).unwrap(),
+            false => dec.open_segment(b"", s, &mut out).unwrap(),
+        };
+        assert_eq!(out[..len], vec![i as u8; i]);
+    }
+    dec.finish().unwrap();
+}
+
+#[test]
+fn stream_errors() {
+    let aead = ChaCha20Poly1305::new(&[0u8; 32]).unwrap();
+    assert!(STREAMEncryptor::new(aead.clone(), &[0u8; 8]).is_err());
+    assert!(STREAMDecryptor::new(aead.clone(), &[0u8; 6]).is_err());
+
+    let mut enc = STREAMEncryptor::new(aead, &[0u8; 7]).unwrap();
+    assert_eq!(enc.seal_segment(b"", &[0u8; 16], &mut [0u8; 31]), Err(Error::Err));
+    let mut out = [0u8; 16];
+    assert_eq!(enc.seal_last_segment(b"", &[], &mut out), Ok(16));
+    assert_eq!(enc.seal_last_segment(b"", &[], &mut out), Err(Error::Err));
+}
@@ -0,0 +1,129 @@
+use rucola::cipher::{AES128, AES192, AES256, XTS};
+use rucola::common::api::BlockCipher;
+use rucola::common::{Error, Success};
+use utilities::testutils::RspVector;
+
+fn xts_vector<C: BlockCipher<16>>(t: &RspVector) {
+    let xts = XTS::<C>::new(&t.hex("Key")).unwrap();
+    let (pt, ct) = (t.hex("PT"), t.hex("CT"));
+    let bits: usize = t.get("DataUnitLen").parse().unwrap();
+    assert_eq!(pt.len(), bits.div_ceil(8));
+
+    let mut buffer = if t.params.contains_key("ENCRYPT") { pt.clone() } else { ct.clone() };
+    if !bits.is_multiple_of(8) {
+        let tweak: [u8; 16] = t.hex("i").try_into().unwrap();
+        if t.params.contains_key("ENCRYPT") {
+            xts.encrypt_with_tweak_bits(&tweak, &mut buffer, bits).unwrap();
+            assert_eq!(buffer, ct);
+        } else {
+            xts.decrypt_with_tweak_bits(&tweak, &mut buffer, bits).unwrap();
+            assert_eq!(buffer, pt);
+        }
+    } else if t.has("i") {
+        let tweak: [u8; 16] = t.hex("i").try_into().unwrap();
+        if t.params.contains_key("ENCRYPT") {
+            xts.encrypt_with_tweak(&tweak, &mut buffer).unwrap();
+            assert_eq!(buffer, ct);
+        } else {
+            xts.decrypt_with_tweak(&tweak, &mut buffer).unwrap();
+            assert_eq!(buffer, pt);
+        }
+    } else {
+        let sector: u64 = t.get("DataUnitSeqNumber").parse().unwrap();
+        if t.params.contains_key("ENCRYPT") {
+            xts.encrypt_sector(sector, &mut buffer).unwrap();
+            assert_eq!(buffer, ct);
+        } else {
+            xts.decrypt_sector(sector, &mut buffer).unwrap();
+            assert_eq!(buffer, pt);
+        }
+    }
+}
+
+#[test]
+fn xts_aes128() {
+    let tv = utilities::testutils::parse_rsp_vectors(&["./tests/tv/XTSGenAES128.rsp", "./tests/tv/generated/XTSGenAES128.rsp"]);
+    assert!(!tv.is_empty());
+
+    for t in tv.iter() {
+        xts_vector::<AES128>(t);
+    }
+}
+
+#[test]
+fn xts_aes256() {
+    let tv = utilities::testutils::parse_rsp_vectors(&["./tests/tv/XTSGenAES256.rsp", "./tests/tv/generated/XTSGenAES256.rsp"]);
+    assert!(!tv.is_empty());
+
+    for t in tv.iter() {
+        xts_vector::<AES256>(t);
+    }
+}
+
+/* IEEE 1619, annex B, vector 2. */
+#[test]
+fn xts_ieee1619() {
+    let mut key = [0x11u8; 32];
+    key[16..].fill(0x22);
+    let xts = XTS::<AES128>::new(&key).unwrap();
+
+    let mut buffer = [0x44u8; 32];
+    xts.encrypt_sector(0x3333333333, &mut buffer).unwrap();
+    assert_eq!(hex::encode(buffer), "c454185e6a16936e39334038acef838bfb186fff7480adc4289382ecd6d394f0");
+    xts.decrypt_sector(0x3333333333, &mut buffer).unwrap();
+    assert_eq!(buffer, [0x44u8; 32]);
+}
+
+#[test]
+fn xts_ciphertext_stealing() {
+    let key: Vec<u8> = (0..64).collect();
+    let xts = XTS::<AES256>::new(&key).unwrap();
+    let msg: Vec<u8> = (0..100).map(|i| (i * 7) as u8).collect();
+
+    for len in 16..msg.len() {
+        let mut buffer = msg[..len].to_vec();
+        xts.encrypt_sector(9, &mut buffer).unwrap();
+        assert_ne!(buffer, msg[..len]);
+
+        /* The blocks before the last two do not depend on the stealing. */
+        let mut full = msg[..len / 16 * 16].to_vec();
+        xts.encrypt_sector(9, &mut full).unwrap();
+        let keep = (len / 16).saturating_sub(1) * 16;
+        assert_eq!(buffer[..keep], full[..keep]);
+
+        xts.decrypt_sector(9, &mut buffer).unwrap();
+        assert_eq!(buffer, msg[..len]);
+    }
+
+    /* Sectors are independent. */
+    let mut a = msg.clone();
+    let mut b = msg.clone();
+    xts.encrypt_sector(1, &mut a).unwrap();
+    xts.encrypt_sector(2, &mut b).unwrap();
+    assert_ne!(a, b);
+}
+
+#[test]
+fn xts_errors() {
+    /* Identical key halves are rejected. */
+    assert!(XTS::<AES128>::new(&[0x42u8; 32]).is_err());
+    assert!(XTS::<AES256>::new(&[0x42u8; 64]).is_err());
+    assert!(XTS::<AES128>::new(&[0x42u8; 31]).is_err());
+    assert!(XTS::<AES192>::new(&(0..48).collect::<Vec<u8>>()).is_err());
+
+    let mut key = [0x42u8; 32];
+    key[31] = 0;
+    let xts = XTS::<AES128>::new(&key).unwrap();
+    let mut buffer = vec![0u8; (1 << 24) + 16];
+    assert_eq!(xts.encrypt_sector(0, &mut buffer[..15]), Err(Error::Err));
+    assert_eq!(xts.decrypt_sector(0, &mut buffer[..0]), Err(Error::Err));
+    assert_eq!(xts.encrypt_sector(0, &mut buffer[..(1 << 24) + 1]), Err(Error::Err));
+
+    /* The bit length must fill the buffer and be at least one block. */
+    let tweak = [0u8; 16];
+    assert_eq!(xts.encrypt_with_tweak_bits(&tweak, &mut buffer[..16], 127), Err(Error::Err));
+    assert_eq!(xts.encrypt_with_tweak_bits(&tweak, &mut buffer[..17], 128), Err(Error::Err));
+    assert_eq!(xts.decrypt_with_tweak_bits(&tweak, &mut buffer[..17], 137), Err(Error::Err));
+    assert_eq!(xts.encrypt_with_tweak_bits(&tweak, &mut buffer[..17], 130), Ok(Success::OK));
+    assert_eq!(buffer[16] & 0x3f, 0);
+}
@@ -1,10 +1,13 @@
 use std::fs::File;
 use std::io::{self, BufRead, Lines, BufReader};
 use std::path::Path;
+use std::collections::HashMap;
 use regex::Regex;
 use hex::FromHex;
 use rand::Rng;
 use rucola::hash::SHA;
+use rucola::hash::SHABackend;
+use rucola::drbg::DRBG;
 use rucola::common::api::{StreamingAPI, DefaultInit, SingleInputUpdate, SingleOutputFinish};
 
 fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
@@ -49,6 +52,112 @@ pub fn parse_hash_vectors(files: &[&'static str]) -> Vec<(Vec<u8>, Vec<u8>)>{
     return vec;
 }
 
+/// A single vector of a CAVP style response file.
+///
+/// `params` holds the bracketed section headers (e.g. `[L=20]` or `[SHA-1]`) that
+/// were in effect for the vector. Fields keep their order since some files repeat
+/// a name within one vector (e.g. `AdditionalInput` in the DRBG vectors).
+#[derive(Debug, Clone, Default)]
+pub struct RspVector {
+    pub params: HashMap<String, String>,
+    pub fields: Vec<(String, String)>,
+}
+
+impl RspVector {
+    pub fn has(&self, name: &str) -> bool {
+        self.fields.iter().any(|(k, _)| k == name)
+    }
+
+    pub fn get(&self, name: &str) -> &str {
+        self.fields.iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+            .unwrap_or_else(|| panic!("missing field {}", name))
+    }
+
+    pub fn hex(&self, name: &str) -> Vec<u8> {
+        hex::decode(self.get(name)).expect("Failed")
+    }
+
+    pub fn hex_all(&self, name: &str) -> Vec<Vec<u8>> {
+        self.fields.iter()
+            .filter(|(k, _)| k == name)
+            .map(|(_, v)| hex::decode(v).expect("Failed"))
+            .collect()
+    }
+
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params.get(name).map(|v| v.as_str())
+    }
+}
+
+/// A fresh context of the SHA variant named as in the CAVP files, e.g. `SHA-256`.
+pub fn sha_by_name(name: &str) -> SHA {
+    match name {
+        "SHA-1" => SHA::new_sha1(),
+        "SHA-224" => SHA::new_sha224(),
+        "SHA-256" => SHA::new_sha256(),
+        "SHA-384" => SHA::new_sha384(),
+        "SHA-512" => SHA::new_sha512(),
+        _ => panic!("unexpected hash"),
+    }
+}
+
+/// The SHA variant of the `[SHA-...]` section `t` is in.
+pub fn hash_param(t: &RspVector) -> SHA {
+    sha_by_name(t.params.keys().find(|k| k.starts_with("SHA-")).unwrap())
+}
+
+/// Parses CAVP style response files into a list of vectors. Vectors are separated
+/// by empty lines, `#` starts a comment.
+pub fn parse_rsp_vectors(files: &[&str]) -> Vec<RspVector> {
+    let mut vec = Vec::<RspVector>::new();
+    for file in files {
+        let lines = read_lines(file).unwrap_or_else(|_| panic!("{} not found", file));
+        let mut params = HashMap::<String, String>::new();
+        let mut cur = RspVector::default();
+        let mut in_header = false;
+
+        for line in lines {
+            let line = line.expect("Failed");
+            let l = line.trim();
+
+            if l.is_empty() || l.starts_with('#') {
+                if l.is_empty() && !cur.fields.is_empty() {
+                    vec.push(std::mem::take(&mut cur));
+                }
+                continue;
+            }
+
+            if l.starts_with('[') {
+                if !cur.fields.is_empty() {
+                    vec.push(std::mem::take(&mut cur));
+                }
+                // A new group of headers replaces the previous one.
+                if !in_header {
+                    params.clear();
+                    in_header = true;
+                }
+                let inner = l.trim_start_matches('[').trim_end_matches(']');
+                let (k, v) = inner.split_once('=').unwrap_or((inner, ""));
+                params.insert(k.trim().to_string(), v.trim().to_string());
+                continue;
+            }
+
+            in_header = false;
+            if cur.fields.is_empty() {
+                cur.params = params.clone();
+            }
+            let (k, v) = l.split_once('=').unwrap_or((l, ""));
+            cur.fields.push((k.trim().to_string(), v.trim().to_string()));
+        }
+        if !cur.fields.is_empty() {
+            vec.push(cur);
+        }
+    }
+    vec
+}
+
 pub fn streaming_api_test<const DS: usize, Prim>(tv: Vec<(Vec<u8>, Vec<u8>)>, s: &mut Prim)
     where Prim: StreamingAPI {
     let mut rng = rand::thread_rng();
@@ -78,4 +187,88 @@ pub fn single_test<const DS: usize, Prim>(msg: &[u8], prim: &mut Prim, output: &
     prim.init();
     prim.update(msg);
     prim.finish(output);
-}
\ No newline at end of file
+}
+
+/// Runs a CAVP style DRBG vector: instantiate, optional reseed, generate twice and
+/// compare the output of the second call. Without prediction resistance the calls
+/// follow the field order, older CAVS files reseed between the two generates.
+pub fn drbg_vector_test<D: DRBG>(drbg: &mut D, t: &RspVector) {
+    let expected = t.hex("ReturnedBits");
+    let mut out = vec![0u8; expected.len()];
+    let additional = t.hex_all("AdditionalInput");
+    let pr = t.has("EntropyInputPR");
+
+    drbg.instantiate(&t.hex("EntropyInput"), &t.hex("Nonce"), &t.hex("PersonalizationString"), pr).unwrap();
+
+    if pr {
+        for (e, a) in t.hex_all("EntropyInputPR").iter().zip(additional.iter()) {
+            drbg.generate_pr(e, &mut out, a).unwrap();
+        }
+    } else {
+        for (k, v) in t.fields.iter() {
+            match k.as_str() {
+                "EntropyInputReseed" => {
+                    drbg.reseed(&hex::decode(v).unwrap(), &t.hex("AdditionalInputReseed")).unwrap();
+                }
+                "AdditionalInput" => {
+                    drbg.generate(&mut out, &hex::decode(v).unwrap()).unwrap();
+                }
+                _ => {}
+            }
+        }
+    }
+    assert_eq!(expected, out);
+
+    drbg.uninstantiate();
+}
+
+/// The SHA backends the host CPU supports, as detected by std. Panics if the crate
+/// doesn't detect the same set, so the tests never skip accelerated code the host
+/// could run.
+pub fn sha_backends() -> Vec<SHABackend> {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    let shani = is_x86_feature_detected!("sha") && is_x86_feature_detected!("ssse3")
+        && is_x86_feature_detected!("sse4.1");
+    #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+    let shani = false;
+    #[cfg(target_arch = "aarch64")]
+    let armv8 = std::arch::is_aarch64_feature_detected!("sha2");
+    #[cfg(not(target_arch = "aarch64"))]
+    let armv8 = false;
+
+    let host = [(SHABackend::Scalar, true), (SHABackend::SHANI, shani), (SHABackend::ARMv8, armv8)];
+    for (backend, supported) in host {
+        assert_eq!(backend.is_supported(), supported, "{:?} is not detected, rucola needs the std feature", backend);
+    }
+
+    host.iter().filter(|(_, supported)| *supported).map(|(backend, _)| *backend).collect()
+}
+
+/// Runs `tv` through a context from `new` on every backend of `sha_backends` and
+/// compares random messages, hashed with updates of many blocks at once, with the
+/// scalar backend. The backends the host lacks must be refused.
+pub fn sha_backend_test<const DS: usize>(new: fn() -> SHA, tv: &[(Vec<u8>, Vec<u8>)]) {
+    let supported = sha_backends();
+    let mut rng = rand::thread_rng();
+    let msgs: Vec<Vec<u8>> = (0..50).map(|_| (0..rng.gen_range(0..2000)).map(|_| rng.gen()).collect()).collect();
+
+    for backend in [SHABackend::Scalar, SHABackend::SHANI, SHABackend::ARMv8] {
+        let mut sha = new();
+        if !supported.contains(&backend) {
+            assert!(sha.set_backend(backend).is_err());
+            continue;
+        }
+        sha.set_backend(backend).unwrap();
+        assert_eq!(sha.backend(), backend);
+        streaming_api_test::<DS, SHA>(tv.to_vec(), &mut sha);
+
+        let mut scalar = new();
+        scalar.set_backend(SHABackend::Scalar).unwrap();
+        let (mut md, mut expected) = ([0u8; DS], [0u8; DS]);
+        for m in &msgs {
+            single_test(m, &mut scalar, &mut expected);
+            single_test(m, &mut sha, &mut md);
+            assert_eq!(md, expected);
+        }
+    }
+}